@@ -0,0 +1,131 @@
+//! Encoder benchmarking on a sample recording.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::core::config::{ProcessingConfig, Quality, Side, Trim};
+use crate::core::encoder::{probe_all_encoders, HardwareEncoder};
+use crate::core::error::Result;
+use crate::core::video::{process_side_with_progress, SideProgress};
+
+/// Encodes the first `duration_secs` of one side of `sample` with every
+/// available encoder.
+///
+/// Returns `(encoder, speed, size)` for each encoder that succeeded, fastest
+/// first, where `speed` is FFmpeg's realtime multiplier (2.0 means twice as
+/// fast as playback) and `size` is the clip's size in bytes. Encoders that
+/// fail on this system are left out. Clips are written to the temporary
+/// directory and removed afterwards.
+pub fn benchmark(sample: &Path, duration_secs: f64) -> Vec<(HardwareEncoder, f64, u64)> {
+    let config = ProcessingConfig::new()
+        .with_quality(Quality::High)
+        .with_trim(Some(Trim {
+            duration: Some(duration_secs),
+            ..Trim::default()
+        }));
+
+    let mut results: Vec<_> = probe_all_encoders()
+        .into_iter()
+        .filter_map(|encoder| {
+            let output = benchmark_output_path(sample, encoder.as_str());
+            let result = benchmark_encoder(sample, &output, &config, encoder, duration_secs);
+            let _ = std::fs::remove_file(&output);
+            result.ok()
+        })
+        .collect();
+
+    rank_by_speed(&mut results);
+    results
+}
+
+/// Encodes one side of `sample` to `output` with `encoder`, returning the
+/// encoder FFmpeg actually used, the speed, and the clip's size.
+pub(crate) fn benchmark_encoder(
+    sample: &Path,
+    output: &Path,
+    config: &ProcessingConfig,
+    encoder: HardwareEncoder,
+    duration_secs: f64,
+) -> Result<(HardwareEncoder, f64, u64)> {
+    let started = Instant::now();
+    let mut speed = 0.0;
+    let used = process_side_with_progress(
+        sample,
+        output,
+        Side::Left,
+        config,
+        &encoder,
+        None,
+        |progress| {
+            if let SideProgress::Encoding(progress) = progress {
+                speed = progress.speed;
+            }
+        },
+    )?;
+
+    // Very short clips can finish before FFmpeg reports a speed
+    if speed <= 0.0 {
+        let elapsed = started.elapsed().as_secs_f64();
+        speed = if elapsed > 0.0 {
+            duration_secs / elapsed
+        } else {
+            0.0
+        };
+    }
+
+    let size = std::fs::metadata(output)?.len();
+    Ok((used, speed, size))
+}
+
+/// Returns a scratch path in the temporary directory for the clip named
+/// `name` (e.g. the encoder), keeping the sample's container.
+pub(crate) fn benchmark_output_path(sample: &Path, name: &str) -> PathBuf {
+    let extension = sample
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mkv".to_string());
+    std::env::temp_dir().join(format!(
+        "obs-cutter-benchmark-{}-{}.{}",
+        std::process::id(),
+        name,
+        extension
+    ))
+}
+
+/// Sorts benchmark results fastest first.
+fn rank_by_speed(results: &mut [(HardwareEncoder, f64, u64)]) {
+    results.sort_by(|a, b| b.1.total_cmp(&a.1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_by_speed() {
+        let mut results = vec![
+            (HardwareEncoder::None, 0.8, 30_000_000),
+            (HardwareEncoder::Nvenc, 6.5, 45_000_000),
+            (HardwareEncoder::Vaapi, 3.2, 40_000_000),
+        ];
+        rank_by_speed(&mut results);
+        let encoders: Vec<_> = results.iter().map(|r| r.0).collect();
+        assert_eq!(
+            encoders,
+            [
+                HardwareEncoder::Nvenc,
+                HardwareEncoder::Vaapi,
+                HardwareEncoder::None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_benchmark_output_path() {
+        let path = benchmark_output_path(Path::new("/videos/sample.mov"), "nvenc");
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("obs-cutter-benchmark-"));
+        assert!(name.ends_with("-nvenc.mov"));
+        assert!(path.starts_with(std::env::temp_dir()));
+    }
+}
@@ -1,10 +1,39 @@
 //! Configuration types for video processing.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use crate::core::error::{ObsCutterError, Result};
 
+/// Implements `Serialize`/`Deserialize` for a type through its canonical
+/// `as_str` name and its `FromStr` parser, so the serialized form matches
+/// what the command line accepts.
+macro_rules! impl_serde_via_str {
+    ($ty:ty) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+pub(crate) use impl_serde_via_str;
+
 /// Quality preset for video encoding.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum Quality {
@@ -20,6 +49,11 @@ pub enum Quality {
     /// Medium quality (CRF 23 equivalent).
     /// Smaller files, acceptable quality.
     Medium,
+
+    /// Fast preview quality (CRF 23 with the fastest encoder preset).
+    /// Trades compression efficiency and quality for speed; meant for
+    /// quick previews rather than final output.
+    Fast,
 }
 
 impl Quality {
@@ -29,28 +63,49 @@ impl Quality {
             Quality::Lossless => "lossless",
             Quality::High => "high",
             Quality::Medium => "medium",
+            Quality::Fast => "fast",
+        }
+    }
+
+    /// Returns a one-line description of the preset for help text.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Quality::Lossless => "Largest files, best quality",
+            Quality::High => "Good balance of size and quality",
+            Quality::Medium => "Smaller files, decent quality",
+            Quality::Fast => "Quick previews, lower quality",
         }
     }
 
     /// Returns all available quality presets.
     pub fn all() -> &'static [Quality] {
-        &[Quality::Lossless, Quality::High, Quality::Medium]
+        &[
+            Quality::Lossless,
+            Quality::High,
+            Quality::Medium,
+            Quality::Fast,
+        ]
     }
 }
 
 impl FromStr for Quality {
     type Err = ObsCutterError;
 
+    /// Accepts the canonical names plus the aliases "best"/"archive"
+    /// (lossless), "balanced" (high) and "small" (medium).
     fn from_str(s: &str) -> Result<Self> {
         match s.to_lowercase().as_str() {
-            "lossless" => Ok(Quality::Lossless),
-            "high" => Ok(Quality::High),
-            "medium" => Ok(Quality::Medium),
+            "lossless" | "best" | "archive" => Ok(Quality::Lossless),
+            "high" | "balanced" => Ok(Quality::High),
+            "medium" | "small" => Ok(Quality::Medium),
+            "fast" => Ok(Quality::Fast),
             _ => Err(ObsCutterError::InvalidQuality(s.to_string())),
         }
     }
 }
 
+impl_serde_via_str!(Quality);
+
 impl std::fmt::Display for Quality {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
@@ -58,16 +113,20 @@ impl std::fmt::Display for Quality {
 }
 
 /// Which side of the video to extract.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum Side {
     /// Left half of the video (x=0).
     #[default]
     Left,
-    /// Right half of the video (x=1920).
+    /// Right half of the video (x=half the input width).
     Right,
 }
 
 impl Side {
+    /// Every side a video is split into, in encoding order. Iterate over
+    /// this rather than listing the sides by hand.
+    pub const ALL: [Side; 2] = [Side::Left, Side::Right];
+
     /// Returns the side as a string.
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -76,12 +135,109 @@ impl Side {
         }
     }
 
-    /// Returns the FFmpeg crop filter for this side.
-    pub fn crop_filter(&self) -> &'static str {
+    /// Returns the output size of one side for an input of the given size.
+    ///
+    /// Each side is half the input width. Both dimensions are rounded down to
+    /// even numbers, which 4:2:0 chroma subsampling requires.
+    pub fn crop_size(input_width: u32, input_height: u32) -> (u32, u32) {
+        ((input_width / 2) & !1, input_height & !1)
+    }
+
+    /// Returns the region of an input of the given size this side covers.
+    pub fn crop_rect(&self, input_width: u32, input_height: u32) -> CropRect {
+        let (width, height) = Side::crop_size(input_width, input_height);
+        let x = match self {
+            Side::Left => 0,
+            Side::Right => width,
+        };
+        CropRect {
+            width,
+            height,
+            x,
+            y: 0,
+        }
+    }
+
+    /// Returns the FFmpeg crop filter for this side of an input of the given size.
+    pub fn crop_filter(&self, input_width: u32, input_height: u32) -> String {
+        self.crop_rect(input_width, input_height).filter()
+    }
+}
+
+/// A rectangle within a video frame, in pixels from the top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CropRect {
+    /// Width of the rectangle.
+    pub width: u32,
+    /// Height of the rectangle.
+    pub height: u32,
+    /// Distance from the left edge of the frame.
+    pub x: u32,
+    /// Distance from the top edge of the frame.
+    pub y: u32,
+}
+
+impl CropRect {
+    /// Returns the FFmpeg crop filter keeping this rectangle.
+    pub fn filter(&self) -> String {
+        format!("crop={}:{}:{}:{}", self.width, self.height, self.x, self.y)
+    }
+
+    /// Returns this rectangle moved by the position of `outer`, turning a
+    /// rectangle within `outer` into one within the whole frame.
+    pub fn within(self, outer: CropRect) -> CropRect {
+        CropRect {
+            x: self.x + outer.x,
+            y: self.y + outer.y,
+            ..self
+        }
+    }
+}
+
+/// How a side that isn't 16:9 is fitted into its output frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FitMode {
+    /// Keep the side's own size, whatever its aspect ratio.
+    #[default]
+    Crop,
+    /// Add black bars around the side to make the frame 16:9, keeping all
+    /// of the picture.
+    Pad,
+}
+
+impl FitMode {
+    /// Returns the fit mode as a string.
+    pub fn as_str(&self) -> &'static str {
         match self {
-            Side::Left => "crop=1920:1080:0:0",
-            Side::Right => "crop=1920:1080:1920:0",
+            FitMode::Crop => "crop",
+            FitMode::Pad => "pad",
+        }
+    }
+
+    /// Returns the frame size a side of the given size is padded to, or
+    /// None if it is left as is.
+    ///
+    /// The padded frame is the smallest 16:9 frame holding the side, with
+    /// even dimensions.
+    pub fn padded_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if *self == FitMode::Crop || height == 0 {
+            return None;
         }
+        let (w, h) = (u64::from(width), u64::from(height));
+        let padded = if w * 9 > h * 16 {
+            (w, (w * 9).div_ceil(16))
+        } else {
+            ((h * 16).div_ceil(9), h)
+        };
+        let even = |n: u64| (n as u32 + 1) & !1;
+        let padded = (even(padded.0), even(padded.1));
+        (padded != (width, height)).then_some(padded)
+    }
+}
+
+impl std::fmt::Display for FitMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -97,35 +253,606 @@ impl FromStr for Side {
     }
 }
 
+impl_serde_via_str!(Side);
+
 impl std::fmt::Display for Side {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
     }
 }
 
+/// How two videos are placed when joined back into one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinLayout {
+    /// Side by side, the inverse of a split (default).
+    #[default]
+    Horizontal,
+    /// One above the other.
+    Vertical,
+}
+
+impl JoinLayout {
+    /// Returns the FFmpeg filter that stacks the inputs.
+    pub fn filter_name(&self) -> &'static str {
+        match self {
+            JoinLayout::Horizontal => "hstack",
+            JoinLayout::Vertical => "vstack",
+        }
+    }
+}
+
+/// YUV value range of a video stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorRange {
+    /// Full range (0-255 for 8-bit), FFmpeg's "pc"/"jpeg". OBS can record this.
+    Full,
+    /// Limited range (16-235 for 8-bit luma), FFmpeg's "tv"/"mpeg".
+    Limited,
+}
+
+impl ColorRange {
+    /// Returns the name FFmpeg uses for `-color_range`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorRange::Full => "pc",
+            ColorRange::Limited => "tv",
+        }
+    }
+
+    /// Returns the name the scale filter uses for `in_range`/`out_range`.
+    pub fn filter_name(&self) -> &'static str {
+        match self {
+            ColorRange::Full => "full",
+            ColorRange::Limited => "limited",
+        }
+    }
+}
+
+impl FromStr for ColorRange {
+    type Err = ObsCutterError;
+
+    /// Accepts "full"/"limited" as well as FFmpeg's "pc"/"tv" and
+    /// "jpeg"/"mpeg".
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "full" | "pc" | "jpeg" => Ok(ColorRange::Full),
+            "limited" | "tv" | "mpeg" => Ok(ColorRange::Limited),
+            _ => Err(ObsCutterError::InvalidColorRange(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for ColorRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.filter_name())
+    }
+}
+
+/// How audio is handled in the outputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AudioMode {
+    /// Copy the source audio stream unchanged.
+    #[default]
+    Copy,
+    /// Re-encode audio to AAC at the given bitrate (kbit/s).
+    Aac { bitrate: u32 },
+    /// Drop audio entirely.
+    None,
+}
+
+impl AudioMode {
+    /// Default AAC bitrate in kbit/s.
+    pub const DEFAULT_AAC_BITRATE: u32 = 192;
+
+    /// Returns the audio mode as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioMode::Copy => "copy",
+            AudioMode::Aac { .. } => "aac",
+            AudioMode::None => "none",
+        }
+    }
+
+    /// Returns the FFmpeg audio arguments for this mode.
+    pub fn codec_args(&self) -> Vec<String> {
+        match self {
+            AudioMode::Copy => vec!["-c:a".to_string(), "copy".to_string()],
+            AudioMode::Aac { bitrate } => vec![
+                "-c:a".to_string(),
+                "aac".to_string(),
+                "-b:a".to_string(),
+                format!("{}k", bitrate),
+            ],
+            AudioMode::None => vec!["-an".to_string()],
+        }
+    }
+
+    /// EBU R128 loudness normalization filter: -16 LUFS integrated loudness,
+    /// -1.5 dBTP true peak and a loudness range of 11 LU.
+    pub const LOUDNORM_FILTER: &'static str = "loudnorm=I=-16:TP=-1.5:LRA=11";
+
+    /// Sample rate normalized audio is written at. loudnorm works at 192 kHz
+    /// and outputs that rate, so it is resampled to OBS's default.
+    pub const LOUDNORM_SAMPLE_RATE: u32 = 48_000;
+
+    /// Returns the FFmpeg arguments that normalize loudness, if `normalize`
+    /// is set. Like downmixing, this needs audio to be re-encoded.
+    pub fn normalize_args(&self, normalize: bool) -> Vec<String> {
+        match self {
+            AudioMode::Aac { .. } if normalize => vec![
+                "-af".to_string(),
+                Self::LOUDNORM_FILTER.to_string(),
+                "-ar".to_string(),
+                Self::LOUDNORM_SAMPLE_RATE.to_string(),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the FFmpeg arguments that downmix audio to stereo, if
+    /// `downmix` is set. Copied audio can't be remixed, so this is empty
+    /// unless audio is re-encoded.
+    pub fn downmix_args(&self, downmix: bool) -> Vec<String> {
+        match self {
+            AudioMode::Aac { .. } if downmix => vec!["-ac".to_string(), "2".to_string()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FromStr for AudioMode {
+    type Err = ObsCutterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "copy" => Ok(AudioMode::Copy),
+            "aac" => Ok(AudioMode::Aac {
+                bitrate: AudioMode::DEFAULT_AAC_BITRATE,
+            }),
+            "none" => Ok(AudioMode::None),
+            _ => Err(ObsCutterError::InvalidAudioMode(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for AudioMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioMode::Aac { bitrate } => write!(f, "aac ({}k)", bitrate),
+            _ => write!(f, "{}", self.as_str()),
+        }
+    }
+}
+
+/// Parses a timestamp in `HH:MM:SS[.mmm]`, `MM:SS[.mmm]` or plain seconds.
+pub fn parse_timestamp(s: &str) -> Result<f64> {
+    let invalid = || ObsCutterError::InvalidTimestamp(s.to_string());
+
+    let parts: Vec<&str> = s.trim().split(':').collect();
+    if parts.len() > 3 || parts.iter().any(|p| p.is_empty()) {
+        return Err(invalid());
+    }
+
+    let mut secs = 0.0;
+    for (index, part) in parts.iter().enumerate() {
+        let value: f64 = part.parse().map_err(|_| invalid())?;
+        let is_last = index == parts.len() - 1;
+        if value < 0.0 || !value.is_finite() || (!is_last && part.contains('.')) {
+            return Err(invalid());
+        }
+        secs = secs * 60.0 + value;
+    }
+
+    Ok(secs)
+}
+
+/// Parses frame dimensions written as `WIDTHxHEIGHT`, e.g. "3840x1080".
+pub fn parse_dimensions(s: &str) -> Result<(u32, u32)> {
+    let invalid = || ObsCutterError::InvalidDimensionsFormat(s.to_string());
+
+    let (width, height) = s.trim().split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u32 = width.parse().map_err(|_| invalid())?;
+    let height: u32 = height.parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+
+    Ok((width, height))
+}
+
+/// Parses a frame rate such as "60" or "29.97".
+pub fn parse_fps(s: &str) -> Result<f64> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|fps| fps.is_finite() && *fps > 0.0)
+        .ok_or_else(|| ObsCutterError::InvalidFrameRate(s.to_string()))
+}
+
+/// Parses a scene change threshold, strictly between 0 and 1.
+pub fn parse_scene_threshold(s: &str) -> Result<f64> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|threshold| *threshold > 0.0 && *threshold < 1.0)
+        .ok_or_else(|| ObsCutterError::InvalidSceneThreshold(s.to_string()))
+}
+
+/// Where a trimmed range stops.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrimEnd {
+    /// Stop at a position measured from the start of the input (seconds).
+    At(f64),
+    /// Stop this many seconds before the end of the input.
+    BeforeEnd(f64),
+}
+
+impl FromStr for TrimEnd {
+    type Err = ObsCutterError;
+
+    /// Parses an end time; a leading `-` means relative to the end of the input.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().strip_prefix('-') {
+            Some(rest) => Ok(TrimEnd::BeforeEnd(parse_timestamp(rest)?)),
+            None => Ok(TrimEnd::At(parse_timestamp(s)?)),
+        }
+    }
+}
+
+/// Portion of the input to encode.
+///
+/// `start` maps to FFmpeg's `-ss`; `duration` (or an `end`, resolved against
+/// the input duration) maps to `-t`. `end` and `duration` are mutually exclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Trim {
+    /// Start position in seconds.
+    pub start: Option<f64>,
+    /// End position.
+    pub end: Option<TrimEnd>,
+    /// Length to encode in seconds, counted from `start`.
+    pub duration: Option<f64>,
+}
+
+/// A trim range resolved against the input duration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolvedTrim {
+    /// Seek position in seconds (`-ss`).
+    pub start: f64,
+    /// Length to encode in seconds (`-t`); None encodes to the end.
+    pub length: Option<f64>,
+}
+
+impl Trim {
+    /// Creates a trim range, rejecting contradictory combinations.
+    pub fn new(start: Option<f64>, end: Option<TrimEnd>, duration: Option<f64>) -> Result<Self> {
+        if end.is_some() && duration.is_some() {
+            return Err(ObsCutterError::InvalidTrim(
+                "--end and --duration cannot be used together".to_string(),
+            ));
+        }
+        if duration.is_some_and(|d| d <= 0.0) {
+            return Err(ObsCutterError::InvalidTrim(
+                "duration must be greater than zero".to_string(),
+            ));
+        }
+        if let Some(TrimEnd::At(end)) = end {
+            if end <= start.unwrap_or(0.0) {
+                return Err(ObsCutterError::InvalidTrim(
+                    "end must be after start".to_string(),
+                ));
+            }
+        }
+
+        Ok(Self {
+            start,
+            end,
+            duration,
+        })
+    }
+
+    /// Returns true if an end relative to the input duration must be resolved.
+    pub fn needs_total_duration(&self) -> bool {
+        matches!(self.end, Some(TrimEnd::BeforeEnd(_)))
+    }
+
+    /// Resolves the range into seek position and length.
+    ///
+    /// `total_duration` is only required when the end is relative to the end
+    /// of the input.
+    pub fn resolve(&self, total_duration: Option<f64>) -> Result<ResolvedTrim> {
+        let start = self.start.unwrap_or(0.0);
+
+        let end = match self.end {
+            Some(TrimEnd::At(end)) => Some(end),
+            Some(TrimEnd::BeforeEnd(offset)) => {
+                let total = total_duration.ok_or_else(|| {
+                    ObsCutterError::InvalidTrim(
+                        "an end relative to the end of the video needs a known duration"
+                            .to_string(),
+                    )
+                })?;
+                Some(total - offset)
+            }
+            None => None,
+        };
+
+        if let Some(total) = total_duration {
+            if start >= total {
+                return Err(ObsCutterError::InvalidTrim(format!(
+                    "start {:.2}s is past the end of the video ({:.2}s)",
+                    start, total
+                )));
+            }
+        }
+
+        let length = match end {
+            Some(end) if end <= start => {
+                return Err(ObsCutterError::InvalidTrim(
+                    "end must be after start".to_string(),
+                ))
+            }
+            Some(end) => Some(end - start),
+            None => self.duration,
+        };
+
+        Ok(ResolvedTrim { start, length })
+    }
+}
+
+impl ResolvedTrim {
+    /// Returns the expected output duration given the input duration.
+    pub fn output_duration(&self, total_duration: Option<f64>) -> Option<f64> {
+        let remaining = total_duration.map(|total| (total - self.start).max(0.0));
+        match (self.length, remaining) {
+            (Some(length), Some(remaining)) => Some(length.min(remaining)),
+            (length, remaining) => length.or(remaining),
+        }
+    }
+}
+
 /// Configuration for video processing.
 #[derive(Debug, Clone)]
 pub struct ProcessingConfig {
     /// Quality preset for encoding.
     pub quality: Quality,
 
+    /// Quality preset for the left side. If None, `quality` is used.
+    pub left_quality: Option<Quality>,
+
+    /// Quality preset for the right side. If None, `quality` is used.
+    pub right_quality: Option<Quality>,
+
     /// Output format (file extension). If None, uses input format.
     pub output_format: Option<String>,
 
+    /// Output format per input extension (e.g. `mkv` → `mp4`), used when
+    /// `output_format` is None. Extensions are matched case-insensitively;
+    /// inputs with an unlisted extension keep their own format.
+    pub format_map: HashMap<String, String>,
+
     /// Output directory. If None, uses input file's directory.
     pub output_dir: Option<PathBuf>,
 
     /// Whether to use hardware acceleration.
     pub use_hardware_accel: bool,
+
+    /// Number of times to retry a side when FFmpeg fails.
+    pub retry: u8,
+
+    /// Whether to retry with software encoding when a hardware encoder fails.
+    pub fallback_to_software: bool,
+
+    /// Whether to probe outputs after encoding to catch truncated files.
+    pub verify: bool,
+
+    /// How audio is handled in the outputs.
+    pub audio: AudioMode,
+
+    /// Whether FFmpeg runs in its own process group (Unix only), so a
+    /// terminal Ctrl-C reaches only obs-cutter and in-flight encodes can finish.
+    pub isolate_ffmpeg_signals: bool,
+
+    /// Portion of the input to encode. If None, the whole video is encoded.
+    pub trim: Option<Trim>,
+
+    /// Length in seconds of a quick sample to encode from the start of the
+    /// input, for trying out settings. Replaces `trim`, and outputs get a
+    /// `-sample` suffix (`{stem}-left-sample.mp4`) so they never overwrite
+    /// a full encode.
+    pub sample: Option<f64>,
+
+    /// Target video bitrate in kbit/s for software encoding. If None, the
+    /// quality preset's constant-quality setting is used.
+    pub target_bitrate: Option<u32>,
+
+    /// Whether software encodes with a target bitrate run in two passes.
+    pub two_pass: bool,
+
+    /// Extra metadata tags written to both outputs.
+    pub metadata: HashMap<String, String>,
+
+    /// Whether to record which side an output is in its `comment` tag,
+    /// unless `metadata` already sets one.
+    pub tag_side: bool,
+
+    /// Subtitle file to burn into one side. If None, no subtitles are burned in.
+    pub burn_subtitles: Option<PathBuf>,
+
+    /// Side that receives the burned-in subtitles.
+    pub burn_subtitles_side: Side,
+
+    /// FFmpeg muxer used when writing a side to stdout.
+    pub pipe_format: String,
+
+    /// Whether to copy chapter markers into the outputs. FFmpeg shifts and
+    /// drops them to fit a trimmed range.
+    pub preserve_chapters: bool,
+
+    /// Whether each input gets its own output subdirectory,
+    /// `{output_dir}/{stem}/{side}.{ext}`.
+    pub subdir_per_input: bool,
+
+    /// Template for output file names (without extension). Supports
+    /// `{stem}`, `{side}` and `{date}` (capture time, `YYYYMMDD-HHMMSS` UTC,
+    /// or formatted with `{date:%Y-%m-%d}`).
+    /// If None, outputs are named `{stem}-{side}`.
+    pub name_template: Option<String>,
+
+    /// Threads FFmpeg uses for decoding and software encoding. If None,
+    /// FFmpeg picks a default based on the CPU count.
+    pub threads: Option<u32>,
+
+    /// Whether to delete the input once both sides are written (and, with
+    /// `verify`, pass verification). The input is never deleted on failure.
+    pub delete_source_on_success: bool,
+
+    /// Whether NVENC encodes decode and crop on the GPU (CUDA) instead of
+    /// the CPU. Ignored for other encoders and when FFmpeg lacks the CUDA
+    /// crop filter.
+    pub gpu_pipeline: bool,
+
+    /// Frame size used to compute the crop, replacing the size FFprobe
+    /// reports. An escape hatch for streams with wrong metadata.
+    pub override_dimensions: Option<(u32, u32)>,
+
+    /// Region of the frame holding the picture, e.g. from
+    /// [`detect_black_bars`](crate::core::detect_black_bars). If set, this
+    /// region is split instead of the whole frame, dropping the black bars
+    /// around it. Takes precedence over `override_dimensions`.
+    pub content_crop: Option<CropRect>,
+
+    /// Color range of the outputs. If None, the source's range is kept;
+    /// otherwise a source in the other range is converted.
+    pub color_range: Option<ColorRange>,
+
+    /// Largest output width. Wider sides are scaled down, keeping their
+    /// aspect ratio; smaller ones are never scaled up.
+    pub max_width: Option<u32>,
+
+    /// Largest output height. Taller sides are scaled down, keeping their
+    /// aspect ratio; smaller ones are never scaled up.
+    pub max_height: Option<u32>,
+
+    /// Whether inputs that are not two 16:9 halves side by side (32:9, such
+    /// as 3840x1080) are rejected instead of being split down the middle.
+    pub strict_dimensions: bool,
+
+    /// How sides that aren't 16:9 are fitted into their output frame.
+    pub fit: FitMode,
+
+    /// Language of the audio track to keep (e.g. "eng"), matched against
+    /// the tracks' language tags. If None, or no track matches, FFmpeg's
+    /// default audio track is used.
+    pub audio_language: Option<String>,
+
+    /// Index of the video stream to split, counting video streams only
+    /// (0 is the first). If None, the first video stream is used.
+    pub video_stream: Option<usize>,
+
+    /// Flag that cancels processing when set: the running FFmpeg is killed,
+    /// its partial output removed, and [`ObsCutterError::Cancelled`]
+    /// returned without retrying.
+    pub cancel_flag: Option<Arc<AtomicBool>>,
+
+    /// Encoder speed/quality preset replacing the one the quality preset
+    /// picks (e.g. "p4" for NVENC, "fast" for x264). Ignored by encoders
+    /// that don't accept it.
+    pub encoder_preset: Option<String>,
+
+    /// Constant frame rate of the outputs. Frames are duplicated or dropped
+    /// to re-time variable frame rate recordings, which keeps audio in sync.
+    /// If None, the source's timing is kept.
+    pub target_fps: Option<f64>,
+
+    /// Scene change threshold (0 to 1) at which each side is cut into
+    /// numbered segments. The segments replace `trim`. If None, each side is
+    /// written as a single file.
+    pub segment_scenes: Option<f64>,
+
+    /// Demuxer FFmpeg reads the input with (`-f` before `-i`), e.g. "h264"
+    /// for raw elementary streams. If None, FFmpeg detects the format.
+    pub input_format: Option<String>,
+
+    /// Raw arguments passed to FFmpeg right before each output path, e.g.
+    /// `["-tune", "film"]`. An unchecked escape hatch: they can override any
+    /// option obs-cutter sets, or make FFmpeg fail.
+    pub extra_args: Vec<String>,
+
+    /// Whether MP4 and MOV outputs get their index (moov atom) at the start
+    /// so they can be played while downloading. This costs a quick second
+    /// pass over the file once it is written.
+    pub faststart: bool,
+
+    /// Length in seconds of the chunks each side is cut into, written as
+    /// `{stem}-{side}-001.{ext}`, `-002` and so on in a single encode. If
+    /// None, each side is one file.
+    pub segment_time: Option<f64>,
+
+    /// Whether re-encoded audio is downmixed to stereo, e.g. 5.1 game audio
+    /// for platforms that only play two channels. Ignored unless `audio` is
+    /// re-encoded.
+    pub downmix: bool,
+
+    /// Whether re-encoded audio is loudness-normalized (EBU R128), evening
+    /// out the volume of recordings whose game and mic mix varies. Ignored
+    /// unless `audio` is re-encoded.
+    pub normalize_audio: bool,
+
+    /// Whether a side is written as MKV instead when FFmpeg can't put its
+    /// streams in the requested container (e.g. PCM audio in MP4).
+    pub container_fallback: bool,
 }
 
 impl Default for ProcessingConfig {
     fn default() -> Self {
         Self {
             quality: Quality::default(),
+            left_quality: None,
+            right_quality: None,
             output_format: None,
+            format_map: HashMap::new(),
             output_dir: None,
             use_hardware_accel: true,
+            retry: 0,
+            fallback_to_software: false,
+            verify: false,
+            audio: AudioMode::default(),
+            isolate_ffmpeg_signals: false,
+            trim: None,
+            sample: None,
+            target_bitrate: None,
+            two_pass: false,
+            metadata: HashMap::new(),
+            tag_side: false,
+            burn_subtitles: None,
+            burn_subtitles_side: Side::Left,
+            pipe_format: "matroska".to_string(),
+            preserve_chapters: true,
+            subdir_per_input: false,
+            threads: None,
+            name_template: None,
+            delete_source_on_success: false,
+            gpu_pipeline: false,
+            override_dimensions: None,
+            content_crop: None,
+            color_range: None,
+            max_width: None,
+            max_height: None,
+            strict_dimensions: false,
+            fit: FitMode::default(),
+            audio_language: None,
+            video_stream: None,
+            cancel_flag: None,
+            encoder_preset: None,
+            target_fps: None,
+            segment_scenes: None,
+            input_format: None,
+            extra_args: Vec::new(),
+            faststart: true,
+            segment_time: None,
+            downmix: false,
+            normalize_audio: false,
+            container_fallback: false,
         }
     }
 }
@@ -142,12 +869,53 @@ impl ProcessingConfig {
         self
     }
 
+    /// Sets the quality preset for the left side only.
+    pub fn with_left_quality(mut self, quality: Option<Quality>) -> Self {
+        self.left_quality = quality;
+        self
+    }
+
+    /// Sets the quality preset for the right side only.
+    pub fn with_right_quality(mut self, quality: Option<Quality>) -> Self {
+        self.right_quality = quality;
+        self
+    }
+
+    /// Returns the quality preset used for `side`.
+    pub fn quality_for(&self, side: Side) -> Quality {
+        let side_quality = match side {
+            Side::Left => self.left_quality,
+            Side::Right => self.right_quality,
+        };
+        side_quality.unwrap_or(self.quality)
+    }
+
     /// Sets the output format.
     pub fn with_output_format(mut self, format: Option<String>) -> Self {
         self.output_format = format;
         self
     }
 
+    /// Sets the output format per input extension.
+    pub fn with_format_map(mut self, format_map: HashMap<String, String>) -> Self {
+        self.format_map = format_map;
+        self
+    }
+
+    /// Returns the output format for `input`: `output_format`, or else the
+    /// format `format_map` lists for its extension. None means the input's
+    /// own format.
+    pub fn output_format_for(&self, input: &Path) -> Option<&str> {
+        if let Some(format) = &self.output_format {
+            return Some(format);
+        }
+        let extension = input.extension()?.to_str()?;
+        self.format_map
+            .iter()
+            .find(|(from, _)| from.eq_ignore_ascii_case(extension))
+            .map(|(_, to)| to.as_str())
+    }
+
     /// Sets the output directory.
     pub fn with_output_dir(mut self, dir: Option<PathBuf>) -> Self {
         self.output_dir = dir;
@@ -159,4 +927,509 @@ impl ProcessingConfig {
         self.use_hardware_accel = enabled;
         self
     }
+
+    /// Sets the number of retries on FFmpeg failure.
+    pub fn with_retry(mut self, retry: u8) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets whether to fall back to software encoding on hardware failure.
+    pub fn with_fallback_to_software(mut self, enabled: bool) -> Self {
+        self.fallback_to_software = enabled;
+        self
+    }
+
+    /// Sets whether to verify outputs after encoding.
+    pub fn with_verify(mut self, enabled: bool) -> Self {
+        self.verify = enabled;
+        self
+    }
+
+    /// Sets the audio mode.
+    pub fn with_audio(mut self, audio: AudioMode) -> Self {
+        self.audio = audio;
+        self
+    }
+
+    /// Sets whether FFmpeg is isolated from terminal signals.
+    pub fn with_isolated_ffmpeg_signals(mut self, enabled: bool) -> Self {
+        self.isolate_ffmpeg_signals = enabled;
+        self
+    }
+
+    /// Sets the trim range.
+    pub fn with_trim(mut self, trim: Option<Trim>) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Sets the length of a sample encode, in seconds.
+    pub fn with_sample(mut self, seconds: Option<f64>) -> Self {
+        self.sample = seconds;
+        self
+    }
+
+    /// Returns the portion of the input to encode: the sample when one is
+    /// set, otherwise `trim`.
+    pub fn effective_trim(&self) -> Option<Trim> {
+        match self.sample {
+            Some(seconds) => Some(Trim {
+                duration: Some(seconds),
+                ..Trim::default()
+            }),
+            None => self.trim,
+        }
+    }
+
+    /// Sets the target bitrate for software encoding (kbit/s).
+    pub fn with_target_bitrate(mut self, kbps: Option<u32>) -> Self {
+        self.target_bitrate = kbps;
+        self
+    }
+
+    /// Sets whether bitrate-targeted software encodes use two passes.
+    pub fn with_two_pass(mut self, enabled: bool) -> Self {
+        self.two_pass = enabled;
+        self
+    }
+
+    /// Sets the metadata tags written to the outputs.
+    pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Sets whether outputs are tagged with their side.
+    pub fn with_tag_side(mut self, enabled: bool) -> Self {
+        self.tag_side = enabled;
+        self
+    }
+
+    /// Sets a subtitle file to burn into the given side.
+    pub fn with_burn_subtitles(mut self, path: Option<PathBuf>, side: Side) -> Self {
+        self.burn_subtitles = path;
+        self.burn_subtitles_side = side;
+        self
+    }
+
+    /// Sets the muxer used when writing to stdout.
+    pub fn with_pipe_format(mut self, format: String) -> Self {
+        self.pipe_format = format;
+        self
+    }
+
+    /// Sets whether chapter markers are copied into the outputs.
+    pub fn with_preserve_chapters(mut self, enabled: bool) -> Self {
+        self.preserve_chapters = enabled;
+        self
+    }
+
+    /// Sets whether each input gets its own output subdirectory.
+    pub fn with_subdir_per_input(mut self, enabled: bool) -> Self {
+        self.subdir_per_input = enabled;
+        self
+    }
+
+    /// Sets the output file name template.
+    pub fn with_name_template(mut self, template: Option<String>) -> Self {
+        self.name_template = template;
+        self
+    }
+
+    /// Sets the number of FFmpeg threads.
+    pub fn with_threads(mut self, threads: Option<u32>) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Enables or disables deleting the input after a successful split.
+    pub fn with_delete_source_on_success(mut self, enabled: bool) -> Self {
+        self.delete_source_on_success = enabled;
+        self
+    }
+
+    /// Enables or disables the all-GPU pipeline for NVENC.
+    pub fn with_gpu_pipeline(mut self, enabled: bool) -> Self {
+        self.gpu_pipeline = enabled;
+        self
+    }
+
+    /// Sets the frame size that overrides the probed dimensions.
+    pub fn with_override_dimensions(mut self, dimensions: Option<(u32, u32)>) -> Self {
+        self.override_dimensions = dimensions;
+        self
+    }
+
+    /// Sets the region of the frame that is split.
+    pub fn with_content_crop(mut self, content: Option<CropRect>) -> Self {
+        self.content_crop = content;
+        self
+    }
+
+    /// Sets the color range of the outputs.
+    pub fn with_color_range(mut self, range: Option<ColorRange>) -> Self {
+        self.color_range = range;
+        self
+    }
+
+    /// Sets the largest output width.
+    pub fn with_max_width(mut self, width: Option<u32>) -> Self {
+        self.max_width = width;
+        self
+    }
+
+    /// Sets the largest output height.
+    pub fn with_max_height(mut self, height: Option<u32>) -> Self {
+        self.max_height = height;
+        self
+    }
+
+    /// Sets whether inputs that are not 32:9 are rejected.
+    pub fn with_strict_dimensions(mut self, strict: bool) -> Self {
+        self.strict_dimensions = strict;
+        self
+    }
+
+    /// Sets how sides that aren't 16:9 are fitted into their output frame.
+    pub fn with_fit(mut self, fit: FitMode) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Sets the language of the audio track to keep.
+    pub fn with_audio_language(mut self, language: Option<String>) -> Self {
+        self.audio_language = language;
+        self
+    }
+
+    /// Sets the index of the video stream to split.
+    pub fn with_video_stream(mut self, stream: Option<usize>) -> Self {
+        self.video_stream = stream;
+        self
+    }
+
+    /// Sets the flag that cancels processing.
+    pub fn with_cancel_flag(mut self, flag: Option<Arc<AtomicBool>>) -> Self {
+        self.cancel_flag = flag;
+        self
+    }
+
+    /// Sets the encoder preset.
+    pub fn with_encoder_preset(mut self, preset: Option<String>) -> Self {
+        self.encoder_preset = preset;
+        self
+    }
+
+    /// Sets the constant frame rate of the outputs.
+    pub fn with_target_fps(mut self, fps: Option<f64>) -> Self {
+        self.target_fps = fps;
+        self
+    }
+
+    /// Sets the scene change threshold for cutting sides into segments.
+    pub fn with_segment_scenes(mut self, threshold: Option<f64>) -> Self {
+        self.segment_scenes = threshold;
+        self
+    }
+
+    /// Sets the demuxer the input is read with.
+    pub fn with_input_format(mut self, format: Option<String>) -> Self {
+        self.input_format = format;
+        self
+    }
+
+    /// Sets the raw arguments passed to FFmpeg before each output.
+    pub fn with_extra_args(mut self, args: Vec<String>) -> Self {
+        self.extra_args = args;
+        self
+    }
+
+    /// Sets whether MP4 and MOV outputs are written for fast start.
+    pub fn with_faststart(mut self, enabled: bool) -> Self {
+        self.faststart = enabled;
+        self
+    }
+
+    /// Sets the length of the chunks each side is cut into.
+    pub fn with_segment_time(mut self, seconds: Option<f64>) -> Self {
+        self.segment_time = seconds;
+        self
+    }
+
+    /// Sets whether re-encoded audio is downmixed to stereo.
+    pub fn with_downmix(mut self, enabled: bool) -> Self {
+        self.downmix = enabled;
+        self
+    }
+
+    /// Sets whether re-encoded audio is loudness-normalized.
+    pub fn with_normalize_audio(mut self, enabled: bool) -> Self {
+        self.normalize_audio = enabled;
+        self
+    }
+
+    /// Sets whether outputs fall back to MKV when the container can't hold
+    /// the streams.
+    pub fn with_container_fallback(mut self, enabled: bool) -> Self {
+        self.container_fallback = enabled;
+        self
+    }
+
+    /// Returns true if processing has been cancelled through the cancel flag.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+
+    /// Returns the frame size a side of the given size is encoded in before
+    /// any downscaling: its own size, or the padded size with
+    /// [`FitMode::Pad`].
+    pub fn fitted_size(&self, width: u32, height: u32) -> (u32, u32) {
+        self.fit
+            .padded_size(width, height)
+            .unwrap_or((width, height))
+    }
+
+    /// Returns the output size for a side of the given size, scaled down
+    /// to fit `max_width` and `max_height`.
+    ///
+    /// The aspect ratio is kept and both dimensions stay even. Sides that
+    /// already fit are returned unchanged.
+    pub fn capped_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let factor = |max: Option<u32>, size: u32| match max {
+            Some(max) if size > max => max as f64 / size as f64,
+            _ => 1.0,
+        };
+        let scale = factor(self.max_width, width).min(factor(self.max_height, height));
+        if scale >= 1.0 {
+            return (width, height);
+        }
+
+        let fit = |size: u32| (((size as f64 * scale).round() as u32) & !1).max(2);
+        (fit(width), fit(height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_round_trip() {
+        for quality in Quality::all() {
+            assert_eq!(quality.to_string().parse::<Quality>().unwrap(), *quality);
+        }
+    }
+
+    #[test]
+    fn test_color_range_parse() {
+        assert_eq!("full".parse::<ColorRange>().unwrap(), ColorRange::Full);
+        assert_eq!("PC".parse::<ColorRange>().unwrap(), ColorRange::Full);
+        assert_eq!("tv".parse::<ColorRange>().unwrap(), ColorRange::Limited);
+        assert_eq!("mpeg".parse::<ColorRange>().unwrap(), ColorRange::Limited);
+        assert!("unknown".parse::<ColorRange>().is_err());
+    }
+
+    #[test]
+    fn test_parse_dimensions() {
+        assert_eq!(parse_dimensions("3840x1080").unwrap(), (3840, 1080));
+        assert_eq!(parse_dimensions(" 5120X1440 ").unwrap(), (5120, 1440));
+        assert!(parse_dimensions("3840").is_err());
+        assert!(parse_dimensions("3840x").is_err());
+        assert!(parse_dimensions("0x1080").is_err());
+        assert!(parse_dimensions("-3840x1080").is_err());
+    }
+
+    #[test]
+    fn test_parse_fps() {
+        assert_eq!(parse_fps(" 60 ").unwrap(), 60.0);
+        assert_eq!(parse_fps("29.97").unwrap(), 29.97);
+        assert!(parse_fps("0").is_err());
+        assert!(parse_fps("-30").is_err());
+        assert!(parse_fps("inf").is_err());
+        assert!(parse_fps("sixty").is_err());
+    }
+
+    #[test]
+    fn test_parse_scene_threshold() {
+        assert_eq!(parse_scene_threshold("0.4").unwrap(), 0.4);
+        assert!(parse_scene_threshold("0").is_err());
+        assert!(parse_scene_threshold("1").is_err());
+        assert!(parse_scene_threshold("high").is_err());
+    }
+
+    #[test]
+    fn test_quality_for_side() {
+        let config = ProcessingConfig::new().with_quality(Quality::High);
+        assert_eq!(config.quality_for(Side::Left), Quality::High);
+        assert_eq!(config.quality_for(Side::Right), Quality::High);
+
+        let config = config.with_right_quality(Some(Quality::Medium));
+        assert_eq!(config.quality_for(Side::Left), Quality::High);
+        assert_eq!(config.quality_for(Side::Right), Quality::Medium);
+    }
+
+    #[test]
+    fn test_quality_aliases() {
+        assert_eq!("best".parse::<Quality>().unwrap(), Quality::Lossless);
+        assert_eq!("Archive".parse::<Quality>().unwrap(), Quality::Lossless);
+        assert_eq!("balanced".parse::<Quality>().unwrap(), Quality::High);
+        assert_eq!("small".parse::<Quality>().unwrap(), Quality::Medium);
+        assert!("tiny".parse::<Quality>().is_err());
+
+        // Aliases are input-only; Display keeps the canonical name
+        assert_eq!("best".parse::<Quality>().unwrap().to_string(), "lossless");
+    }
+
+    #[test]
+    fn test_serde_uses_canonical_names() {
+        use crate::core::HardwareEncoder;
+
+        assert_eq!(serde_json::to_string(&Quality::High).unwrap(), "\"high\"");
+        assert_eq!(serde_json::to_string(&Side::Right).unwrap(), "\"right\"");
+        assert_eq!(
+            serde_json::to_string(&HardwareEncoder::QuickSync).unwrap(),
+            "\"qsv\""
+        );
+
+        // Deserializing goes through FromStr, so aliases are accepted
+        let quality: Quality = serde_json::from_str("\"balanced\"").unwrap();
+        assert_eq!(quality, Quality::High);
+        let encoder: HardwareEncoder = serde_json::from_str("\"none\"").unwrap();
+        assert_eq!(encoder, HardwareEncoder::None);
+        assert!(serde_json::from_str::<Side>("\"up\"").is_err());
+    }
+
+    /// Parses "crop=w:h:x:y" into its four numbers.
+    fn parse_crop(filter: &str) -> [u32; 4] {
+        let values: Vec<u32> = filter
+            .strip_prefix("crop=")
+            .unwrap()
+            .split(':')
+            .map(|v| v.parse().unwrap())
+            .collect();
+        [values[0], values[1], values[2], values[3]]
+    }
+
+    #[test]
+    fn test_crop_filter_3840() {
+        assert_eq!(Side::Left.crop_filter(3840, 1080), "crop=1920:1080:0:0");
+        assert_eq!(Side::Right.crop_filter(3840, 1080), "crop=1920:1080:1920:0");
+    }
+
+    #[test]
+    fn test_crop_filter_covers_input() {
+        for (width, height) in [(3840, 1080), (5120, 1440), (7680, 2160)] {
+            let [lw, lh, lx, ly] = parse_crop(&Side::Left.crop_filter(width, height));
+            let [rw, rh, rx, ry] = parse_crop(&Side::Right.crop_filter(width, height));
+
+            assert_eq!((lx, ly, ry), (0, 0, 0), "{}x{}", width, height);
+            assert_eq!((lh, rh), (height, height), "{}x{}", width, height);
+            // Sides are adjacent without overlap and together span the input
+            assert_eq!(lx + lw, rx, "{}x{}", width, height);
+            assert_eq!(rx + rw, width, "{}x{}", width, height);
+        }
+    }
+
+    #[test]
+    fn test_crop_size_is_even() {
+        assert_eq!(Side::crop_size(3842, 1081), (1920, 1080));
+    }
+
+    #[test]
+    fn test_padded_size() {
+        // A 3440x1440 ultrawide half is pillarboxed, a 5120x1080 half letterboxed
+        assert_eq!(FitMode::Pad.padded_size(1720, 1440), Some((2560, 1440)));
+        assert_eq!(FitMode::Pad.padded_size(2560, 1080), Some((2560, 1440)));
+        assert_eq!(FitMode::Pad.padded_size(1000, 500), Some((1000, 564)));
+        assert_eq!(FitMode::Pad.padded_size(1920, 1080), None);
+        assert_eq!(FitMode::Crop.padded_size(1720, 1440), None);
+
+        let config = ProcessingConfig::new().with_fit(FitMode::Pad);
+        assert_eq!(config.fitted_size(1720, 1440), (2560, 1440));
+        assert_eq!(config.fitted_size(1920, 1080), (1920, 1080));
+    }
+
+    #[test]
+    fn test_output_format_for() {
+        let format_map = HashMap::from([("mkv".to_string(), "mp4".to_string())]);
+        let config = ProcessingConfig::new().with_format_map(format_map);
+        assert_eq!(config.output_format_for(Path::new("a.mkv")), Some("mp4"));
+        assert_eq!(config.output_format_for(Path::new("a.MKV")), Some("mp4"));
+        assert_eq!(config.output_format_for(Path::new("a.mov")), None);
+        assert_eq!(config.output_format_for(Path::new("a")), None);
+
+        // An explicit format applies to every input
+        let config = config.with_output_format(Some("mov".to_string()));
+        assert_eq!(config.output_format_for(Path::new("a.mkv")), Some("mov"));
+    }
+
+    #[test]
+    fn test_capped_size() {
+        let config = ProcessingConfig::new().with_max_height(Some(1080));
+        // 1440p ultrawide side is scaled down, 1080p is left native
+        assert_eq!(config.capped_size(2560, 1440), (1920, 1080));
+        assert_eq!(config.capped_size(1920, 1080), (1920, 1080));
+        assert_eq!(config.capped_size(1280, 720), (1280, 720));
+
+        // The tighter of the two caps wins, and odd results are rounded even
+        let config = config.with_max_width(Some(1280));
+        assert_eq!(config.capped_size(2560, 1440), (1280, 720));
+        assert_eq!(config.capped_size(1720, 1440), (1280, 1072));
+
+        assert_eq!(
+            ProcessingConfig::new().capped_size(2560, 1440),
+            (2560, 1440)
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("00:01:30").unwrap(), 90.0);
+        assert_eq!(parse_timestamp("02:05.5").unwrap(), 125.5);
+        assert_eq!(parse_timestamp("42").unwrap(), 42.0);
+        assert!(parse_timestamp("1:xx").is_err());
+        assert!(parse_timestamp("1::2").is_err());
+        assert!(parse_timestamp("-5").is_err());
+    }
+
+    #[test]
+    fn test_trim_start_and_duration() {
+        let start = parse_timestamp("00:01:00").ok();
+        let duration = parse_timestamp("00:00:30").ok();
+        let trim = Trim::new(start, None, duration).unwrap();
+
+        let resolved = trim.resolve(None).unwrap();
+        assert_eq!(
+            resolved,
+            ResolvedTrim {
+                start: 60.0,
+                length: Some(30.0)
+            }
+        );
+        assert_eq!(resolved.output_duration(Some(600.0)), Some(30.0));
+    }
+
+    #[test]
+    fn test_trim_negative_end() {
+        let end: TrimEnd = "-00:00:30".parse().unwrap();
+        assert_eq!(end, TrimEnd::BeforeEnd(30.0));
+
+        let trim = Trim::new(Some(60.0), Some(end), None).unwrap();
+        assert!(trim.needs_total_duration());
+        assert!(trim.resolve(None).is_err());
+        assert_eq!(trim.resolve(Some(300.0)).unwrap().length, Some(210.0));
+
+        let too_short = Trim::new(Some(290.0), Some(end), None).unwrap();
+        assert!(too_short.resolve(Some(300.0)).is_err());
+    }
+
+    #[test]
+    fn test_trim_rejects_contradictions() {
+        assert!(Trim::new(None, Some(TrimEnd::At(60.0)), Some(30.0)).is_err());
+        assert!(Trim::new(Some(60.0), Some(TrimEnd::At(30.0)), None).is_err());
+        assert!(Trim::new(None, None, Some(0.0)).is_err());
+    }
 }
@@ -0,0 +1,210 @@
+//! Minimal UTC date and time handling for output naming.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date and time in UTC, to the second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    /// Converts seconds since the Unix epoch to a UTC date and time.
+    pub fn from_unix(secs: i64) -> Self {
+        let days = secs.div_euclid(86_400);
+        let secs_of_day = secs.rem_euclid(86_400) as u32;
+        let (year, month, day) = civil_from_days(days);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: secs_of_day / 3600,
+            minute: secs_of_day % 3600 / 60,
+            second: secs_of_day % 60,
+        }
+    }
+
+    /// Converts a file timestamp to a UTC date and time.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => after.as_secs() as i64,
+            Err(before) => -(before.duration().as_secs() as i64),
+        };
+        Self::from_unix(secs)
+    }
+
+    /// Parses an ISO 8601 timestamp as written in FFmpeg's `creation_time`
+    /// tag, e.g. "2024-03-15T14:30:05.000000Z".
+    ///
+    /// A UTC offset such as "+02:00" is applied so the result is in UTC;
+    /// a timestamp without an offset is taken to be UTC already.
+    pub fn parse_iso8601(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (date, time) = s.split_once(['T', ' '])?;
+
+        let mut date_parts = date.splitn(3, '-');
+        let year: i32 = date_parts.next()?.parse().ok()?;
+        let month: u32 = date_parts.next()?.parse().ok()?;
+        let day: u32 = date_parts.next()?.parse().ok()?;
+
+        // Split off the offset; "Z" and no offset both mean UTC
+        let (clock, offset_secs) = if let Some(clock) = time.strip_suffix('Z') {
+            (clock, 0)
+        } else if let Some(index) = time.rfind(['+', '-']) {
+            let (clock, offset) = time.split_at(index);
+            (clock, parse_offset(offset)?)
+        } else {
+            (time, 0)
+        };
+
+        let mut clock_parts = clock.splitn(3, ':');
+        let hour: u32 = clock_parts.next()?.parse().ok()?;
+        let minute: u32 = clock_parts.next()?.parse().ok()?;
+        let second: f64 = clock_parts.next().unwrap_or("0").parse().ok()?;
+
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+            return None;
+        }
+
+        let secs = days_from_civil(year, month, day) * 86_400
+            + i64::from(hour * 3600 + minute * 60)
+            + second as i64
+            - offset_secs;
+        Some(Self::from_unix(secs))
+    }
+
+    /// Formats with a strftime-style pattern supporting `%Y`, `%m`, `%d`,
+    /// `%H`, `%M`, `%S` and `%%`. Other characters, including unknown
+    /// `%` sequences, are copied as is.
+    pub fn format(&self, pattern: &str) -> String {
+        let mut formatted = String::with_capacity(pattern.len() + 8);
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                formatted.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => formatted.push_str(&format!("{:04}", self.year)),
+                Some('m') => formatted.push_str(&format!("{:02}", self.month)),
+                Some('d') => formatted.push_str(&format!("{:02}", self.day)),
+                Some('H') => formatted.push_str(&format!("{:02}", self.hour)),
+                Some('M') => formatted.push_str(&format!("{:02}", self.minute)),
+                Some('S') => formatted.push_str(&format!("{:02}", self.second)),
+                Some('%') => formatted.push('%'),
+                Some(other) => {
+                    formatted.push('%');
+                    formatted.push(other);
+                }
+                None => formatted.push('%'),
+            }
+        }
+        formatted
+    }
+
+    /// Formats as `YYYYMMDD-HHMMSS`, which sorts chronologically.
+    pub fn format_compact(&self) -> String {
+        format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl std::fmt::Display for DateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// Parses a UTC offset such as "+02:00", "-0500" or "+01" into seconds.
+fn parse_offset(offset: &str) -> Option<i64> {
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i64>().ok()?, 0),
+        4 => (digits[..2].parse().ok()?, digits[2..].parse().ok()?),
+        _ => return None,
+    };
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+///
+/// Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year as i32, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso8601() {
+        let time = DateTime::parse_iso8601("2024-03-15T14:30:05.000000Z").unwrap();
+        assert_eq!(time.format_compact(), "20240315-143005");
+
+        // Offsets are converted to UTC, across a date boundary if needed
+        let time = DateTime::parse_iso8601("2024-03-15T01:00:00+02:00").unwrap();
+        assert_eq!(time.format_compact(), "20240314-230000");
+        let time = DateTime::parse_iso8601("2023-12-31T20:00:00-0500").unwrap();
+        assert_eq!(time.format_compact(), "20240101-010000");
+
+        assert!(DateTime::parse_iso8601("not a date").is_none());
+        assert!(DateTime::parse_iso8601("2024-13-01T00:00:00Z").is_none());
+    }
+
+    #[test]
+    fn test_format() {
+        let time = DateTime::parse_iso8601("2024-06-12T09:05:03Z").unwrap();
+        assert_eq!(time.format("%Y-%m-%d"), "2024-06-12");
+        assert_eq!(time.format("%Y/%m/%d %H:%M:%S"), "2024/06/12 09:05:03");
+        assert_eq!(time.format("100%% %q%"), "100% %q%");
+    }
+
+    #[test]
+    fn test_from_unix() {
+        assert_eq!(DateTime::from_unix(0).format_compact(), "19700101-000000");
+        // 2024 is a leap year
+        assert_eq!(
+            DateTime::from_unix(1_709_210_096).format_compact(),
+            "20240229-123456"
+        );
+        assert_eq!(
+            DateTime::from_unix(-1).to_string(),
+            "1969-12-31 23:59:59 UTC"
+        );
+    }
+}
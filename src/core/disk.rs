@@ -0,0 +1,84 @@
+//! Free disk space checks.
+
+use crate::core::error::{ObsCutterError, Result};
+use std::path::Path;
+
+/// Returns the space in bytes available to unprivileged users on the
+/// filesystem holding `path`, or None if it cannot be determined.
+///
+/// `path` does not need to exist yet; its closest existing ancestor is used.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    filesystem_free_space(existing)
+}
+
+#[cfg(unix)]
+fn filesystem_free_space(path: &Path) -> Option<u64> {
+    let stat = rustix::fs::statvfs(path).ok()?;
+    Some(stat.f_bavail.saturating_mul(stat.f_frsize))
+}
+
+#[cfg(windows)]
+fn filesystem_free_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut available = 0u64;
+    // SAFETY: `wide` is NUL-terminated and outlives the call, and the
+    // totals we don't need may be null
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn filesystem_free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Checks that `output_dir` has at least `required_bytes` of free space.
+///
+/// Passes when the free space cannot be determined.
+pub fn check_disk_space(output_dir: &Path, required_bytes: u64) -> Result<()> {
+    match available_space(output_dir) {
+        Some(available) if available < required_bytes => {
+            Err(ObsCutterError::InsufficientDiskSpace {
+                required: required_bytes,
+                available,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_disk_space() {
+        let dir = std::env::temp_dir();
+        assert!(check_disk_space(&dir, 0).is_ok());
+
+        if let Some(available) = available_space(&dir) {
+            assert!(matches!(
+                check_disk_space(&dir, available.saturating_add(1 << 40)),
+                Err(ObsCutterError::InsufficientDiskSpace { .. })
+            ));
+        }
+
+        // A directory that does not exist yet is measured on its parent
+        let missing = dir.join("obs-cutter-missing").join("nested");
+        assert_eq!(
+            available_space(&missing).is_some(),
+            available_space(&dir).is_some()
+        );
+    }
+}
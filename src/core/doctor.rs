@@ -0,0 +1,78 @@
+//! A quick end-to-end check that encoding works on this system.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::core::benchmark::{benchmark_encoder, benchmark_output_path};
+use crate::core::config::{ProcessingConfig, Quality};
+use crate::core::encoder::HardwareEncoder;
+use crate::core::error::{ObsCutterError, Result};
+use crate::core::ffmpeg::{ffmpeg_spawn_error, get_ffmpeg_path};
+
+/// Length of the generated test clip in seconds.
+const TEST_CLIP_SECS: f64 = 1.0;
+
+/// Splits one side of a generated 1-second 32:9 clip with `encoder`, the
+/// same way a recording is split.
+///
+/// Returns the encoder that produced the output. The clip and its output
+/// are written to the temporary directory and removed afterwards.
+pub fn test_encode(encoder: &HardwareEncoder) -> Result<HardwareEncoder> {
+    let clip = benchmark_output_path(Path::new("doctor.mkv"), "doctor-clip");
+    let output = benchmark_output_path(&clip, encoder.as_str());
+    let result = generate_test_clip(&clip).and_then(|()| {
+        let config = ProcessingConfig::new().with_quality(Quality::Fast);
+        benchmark_encoder(&clip, &output, &config, *encoder, TEST_CLIP_SECS)
+    });
+    let _ = std::fs::remove_file(&clip);
+    let _ = std::fs::remove_file(&output);
+    result.map(|(used, _, _)| used)
+}
+
+/// Writes a 1-second 1280x360 test pattern to `path` with FFmpeg's default
+/// codec for the container.
+fn generate_test_clip(path: &Path) -> Result<()> {
+    let output = Command::new(get_ffmpeg_path())
+        .args(test_clip_args(path))
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(ffmpeg_spawn_error)?;
+    if !output.status.success() {
+        return Err(ObsCutterError::FfmpegFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the FFmpeg arguments that generate the test clip at `path`.
+fn test_clip_args(path: &Path) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "-v",
+        "error",
+        "-y",
+        "-f",
+        "lavfi",
+        "-i",
+        &format!("testsrc=duration={}:size=1280x360:rate=30", TEST_CLIP_SECS),
+        "-pix_fmt",
+        "yuv420p",
+    ]
+    .map(String::from)
+    .to_vec();
+    args.push(path.to_string_lossy().to_string());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_args_end_with_path() {
+        let args = test_clip_args(Path::new("/tmp/clip.mkv"));
+        assert_eq!(args.last().unwrap(), "/tmp/clip.mkv");
+        assert!(args.contains(&"testsrc=duration=1:size=1280x360:rate=30".to_string()));
+    }
+}
@@ -1,7 +1,11 @@
 //! Hardware encoder detection and configuration.
 
+use crate::core::config::AudioMode;
+use crate::core::error::{ObsCutterError, Result};
 use crate::core::ffmpeg;
 use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 /// Available hardware encoders for H.264 video encoding.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -18,6 +22,9 @@ pub enum HardwareEncoder {
     /// AMF - AMD GPU hardware encoder.
     Amf,
 
+    /// VAAPI - Linux Intel/AMD GPU hardware encoder.
+    Vaapi,
+
     /// Software encoding fallback (libx264).
     #[default]
     None,
@@ -31,10 +38,35 @@ impl HardwareEncoder {
             HardwareEncoder::Nvenc => "h264_nvenc",
             HardwareEncoder::QuickSync => "h264_qsv",
             HardwareEncoder::Amf => "h264_amf",
+            HardwareEncoder::Vaapi => "h264_vaapi",
             HardwareEncoder::None => "libx264",
         }
     }
 
+    /// Returns the FFmpeg encoder name for H.265, used for 10-bit sources.
+    pub fn hevc_encoder(&self) -> &'static str {
+        match self {
+            HardwareEncoder::VideoToolbox => "hevc_videotoolbox",
+            HardwareEncoder::Nvenc => "hevc_nvenc",
+            HardwareEncoder::QuickSync => "hevc_qsv",
+            HardwareEncoder::Amf => "hevc_amf",
+            HardwareEncoder::Vaapi => "hevc_vaapi",
+            HardwareEncoder::None => "libx265",
+        }
+    }
+
+    /// Returns the short name used on the command line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HardwareEncoder::VideoToolbox => "videotoolbox",
+            HardwareEncoder::Nvenc => "nvenc",
+            HardwareEncoder::QuickSync => "qsv",
+            HardwareEncoder::Amf => "amf",
+            HardwareEncoder::Vaapi => "vaapi",
+            HardwareEncoder::None => "software",
+        }
+    }
+
     /// Returns a human-readable name for the encoder.
     pub fn name(&self) -> &'static str {
         match self {
@@ -42,107 +74,356 @@ impl HardwareEncoder {
             HardwareEncoder::Nvenc => "NVENC (NVIDIA)",
             HardwareEncoder::QuickSync => "Quick Sync (Intel)",
             HardwareEncoder::Amf => "AMF (AMD)",
+            HardwareEncoder::Vaapi => "VAAPI (Linux)",
             HardwareEncoder::None => "Software (libx264)",
         }
     }
 
+    /// Describes the encoder for reports after a run, e.g. "NVENC (NVIDIA)
+    /// hardware encoder" or "software encoder (libx264)".
+    pub fn description(&self) -> String {
+        match self {
+            HardwareEncoder::None => format!("software encoder ({})", self.h264_encoder()),
+            hardware => format!("{} hardware encoder", hardware.name()),
+        }
+    }
+
     /// Returns true if this is a hardware encoder.
     pub fn is_hardware(&self) -> bool {
         !matches!(self, HardwareEncoder::None)
     }
+
+    /// Returns FFmpeg arguments that must precede the input, such as the
+    /// hardware device to open.
+    pub fn device_args(&self) -> Vec<String> {
+        match self {
+            HardwareEncoder::Vaapi => {
+                vec!["-vaapi_device".to_string(), VAAPI_RENDER_NODE.to_string()]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the filters that move frames onto the GPU, if the encoder
+    /// needs them. These must run after any software filters such as crop.
+    pub fn upload_filter(&self, ten_bit: bool) -> Option<&'static str> {
+        match self {
+            HardwareEncoder::Vaapi if ten_bit => Some("format=p010,hwupload"),
+            HardwareEncoder::Vaapi => Some("format=nv12,hwupload"),
+            _ => None,
+        }
+    }
+
+    /// Returns the pixel format to encode 10-bit sources with, or None if
+    /// the upload filter already sets it.
+    fn ten_bit_pix_fmt(&self) -> Option<&'static str> {
+        match self {
+            HardwareEncoder::Vaapi => None,
+            // libx265 does not accept the semi-planar p010le
+            HardwareEncoder::None => Some("yuv420p10le"),
+            _ => Some("p010le"),
+        }
+    }
+
+    /// Returns the target bitrate in Mbit/s for encoders that use
+    /// bitrate-based rate control, or None for quality-based (CRF/CQ) ones.
+    pub fn target_bitrate_mbps(&self, quality: &str) -> Option<u32> {
+        match self {
+            HardwareEncoder::VideoToolbox => Some(match quality {
+                "high" => 15,
+                "medium" => 10,
+                "fast" => 8,
+                _ => 25, // lossless/highest quality
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the bitrate in Mbit/s to encode one side of a source recorded
+    /// at `source_bps` bits per second, or None to keep the fixed
+    /// [`target_bitrate_mbps`](Self::target_bitrate_mbps).
+    ///
+    /// Only VideoToolbox's lossless preset follows the source: each side
+    /// carries half of the pixels, so it gets about half the bitrate.
+    pub fn source_bitrate_mbps(&self, quality: &str, source_bps: u64) -> Option<u32> {
+        match (self, quality) {
+            (HardwareEncoder::VideoToolbox, "lossless") => {
+                u32::try_from((source_bps / 2).div_ceil(1_000_000).max(1)).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the largest frame the encoder's H.264 encoder accepts, or
+    /// None if it has no practical limit.
+    ///
+    /// These are conservative limits that hold for older GPUs too; newer
+    /// hardware may go beyond them. H.265 (used for 10-bit sources) allows
+    /// larger frames on all of them.
+    pub fn max_dimensions(&self) -> Option<(u32, u32)> {
+        match self {
+            HardwareEncoder::Nvenc | HardwareEncoder::QuickSync | HardwareEncoder::Vaapi => {
+                Some((4096, 4096))
+            }
+            HardwareEncoder::VideoToolbox => Some((4096, 2304)),
+            HardwareEncoder::Amf => Some((4096, 2160)),
+            HardwareEncoder::None => None,
+        }
+    }
+
+    /// Returns true if the encoder can encode frames of the given size.
+    pub fn can_encode(&self, width: u32, height: u32, ten_bit: bool) -> bool {
+        if ten_bit {
+            return true;
+        }
+        self.max_dimensions()
+            .is_none_or(|(max_width, max_height)| width <= max_width && height <= max_height)
+    }
+
+    /// Returns the FFmpeg option selecting the encoder's speed/quality
+    /// preset, or None if the encoder has no presets.
+    pub fn preset_option(&self) -> Option<&'static str> {
+        match self {
+            HardwareEncoder::Nvenc | HardwareEncoder::QuickSync | HardwareEncoder::None => {
+                Some("-preset")
+            }
+            HardwareEncoder::Amf => Some("-quality"),
+            HardwareEncoder::VideoToolbox | HardwareEncoder::Vaapi => None,
+        }
+    }
+
+    /// Returns the presets the encoder accepts, fastest first.
+    pub fn presets(&self) -> &'static [&'static str] {
+        match self {
+            HardwareEncoder::Nvenc => &["p1", "p2", "p3", "p4", "p5", "p6", "p7"],
+            HardwareEncoder::QuickSync => &[
+                "veryfast", "faster", "fast", "medium", "slow", "slower", "veryslow",
+            ],
+            HardwareEncoder::Amf => &["speed", "balanced", "quality"],
+            // Shared by libx264 and libx265
+            HardwareEncoder::None => &[
+                "ultrafast",
+                "superfast",
+                "veryfast",
+                "faster",
+                "fast",
+                "medium",
+                "slow",
+                "slower",
+                "veryslow",
+                "placebo",
+            ],
+            HardwareEncoder::VideoToolbox | HardwareEncoder::Vaapi => &[],
+        }
+    }
+
+    /// Checks that the encoder accepts `preset`. Encoders without presets
+    /// accept (and ignore) any.
+    pub fn check_preset(&self, preset: &str) -> Result<()> {
+        if self.preset_option().is_none() || self.presets().contains(&preset) {
+            return Ok(());
+        }
+        Err(ObsCutterError::InvalidPreset {
+            preset: preset.to_string(),
+            encoder: self.name().to_string(),
+            valid: self.presets().join(", "),
+        })
+    }
+}
+
+impl FromStr for HardwareEncoder {
+    type Err = ObsCutterError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "videotoolbox" => Ok(HardwareEncoder::VideoToolbox),
+            "nvenc" => Ok(HardwareEncoder::Nvenc),
+            "qsv" => Ok(HardwareEncoder::QuickSync),
+            "amf" => Ok(HardwareEncoder::Amf),
+            "vaapi" => Ok(HardwareEncoder::Vaapi),
+            "software" | "none" => Ok(HardwareEncoder::None),
+            _ => Err(ObsCutterError::InvalidEncoder(s.to_string())),
+        }
+    }
 }
 
+crate::core::config::impl_serde_via_str!(HardwareEncoder);
+
 impl std::fmt::Display for HardwareEncoder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
     }
 }
 
-/// Checks if a specific encoder is available in FFmpeg.
-fn check_encoder_available(encoder_name: &str) -> bool {
-    let ffmpeg_path = ffmpeg::get_ffmpeg_path();
+/// Hardware encoders in order of preference.
+const HARDWARE_ENCODERS: [HardwareEncoder; 5] = [
+    HardwareEncoder::VideoToolbox,
+    HardwareEncoder::Nvenc,
+    HardwareEncoder::QuickSync,
+    HardwareEncoder::Amf,
+    HardwareEncoder::Vaapi,
+];
 
-    let output = Command::new(ffmpeg_path)
-        .args(["-hide_banner", "-encoders"])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .output();
+/// DRM render node used for VAAPI encoding.
+pub const VAAPI_RENDER_NODE: &str = "/dev/dri/renderD128";
 
-    if let Ok(output) = output {
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            return stdout.contains(encoder_name);
-        }
+/// Cached output of `ffmpeg -encoders`, probed once per process.
+static ENCODER_LIST: OnceLock<Option<String>> = OnceLock::new();
+
+/// Returns the output of `ffmpeg -encoders`, running FFmpeg only on first use.
+fn ffmpeg_encoder_list() -> Option<&'static str> {
+    ENCODER_LIST
+        .get_or_init(|| {
+            let output = Command::new(ffmpeg::get_ffmpeg_path())
+                .args(["-hide_banner", "-encoders"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .output()
+                .ok()?;
+
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+        .as_deref()
+}
+
+/// Checks if an encoder is listed in the `ffmpeg -encoders` output.
+fn is_encoder_listed(encoder_list: &str, encoder: &HardwareEncoder) -> bool {
+    // VideoToolbox is only usable on macOS even if FFmpeg was built with it
+    if *encoder == HardwareEncoder::VideoToolbox && !cfg!(target_os = "macos") {
+        return false;
+    }
+
+    // VAAPI additionally needs a Linux render node to open
+    if *encoder == HardwareEncoder::Vaapi
+        && !(cfg!(target_os = "linux") && std::path::Path::new(VAAPI_RENDER_NODE).exists())
+    {
+        return false;
     }
-    false
+
+    let name = encoder.h264_encoder();
+    encoder_list
+        .lines()
+        .any(|line| line.split_whitespace().any(|word| word == name))
+}
+
+/// Returns every encoder available on this system, in order of preference.
+///
+/// Hardware encoders come first; software encoding is always included last.
+/// FFmpeg is queried only once per process.
+pub fn probe_all_encoders() -> Vec<HardwareEncoder> {
+    let mut encoders: Vec<HardwareEncoder> = match ffmpeg_encoder_list() {
+        Some(list) => HARDWARE_ENCODERS
+            .into_iter()
+            .filter(|encoder| is_encoder_listed(list, encoder))
+            .collect(),
+        None => Vec::new(),
+    };
+    encoders.push(HardwareEncoder::None);
+    encoders
+}
+
+/// Environment variable that, when set to anything but an empty string or
+/// `0`, makes [`detect_hardware_encoder`] pick software encoding.
+pub const FORCE_SOFTWARE_ENV: &str = "OBS_CUTTER_FORCE_SOFTWARE";
+
+/// Environment variable naming the encoder [`detect_hardware_encoder`]
+/// returns, e.g. `nvenc` (any name accepted by `--encoder`).
+pub const ENCODER_ENV: &str = "OBS_CUTTER_ENCODER";
+
+/// Returns the encoder forced by the environment variable values
+/// `force_software` and `encoder`, if any.
+///
+/// Forcing software wins over naming an encoder. Unknown encoder names are
+/// ignored, leaving detection to FFmpeg; [`encoder_env_error`] reports them.
+fn encoder_override(
+    force_software: Option<&str>,
+    encoder: Option<&str>,
+) -> Option<HardwareEncoder> {
+    if force_software.is_some_and(|value| !value.is_empty() && value != "0") {
+        return Some(HardwareEncoder::None);
+    }
+    encoder.and_then(|name| name.trim().parse().ok())
+}
+
+/// Returns the error for an [`ENCODER_ENV`] value that names no encoder,
+/// so callers can warn that detection ignores it.
+pub fn encoder_env_error() -> Option<ObsCutterError> {
+    invalid_encoder_name(std::env::var(ENCODER_ENV).ok().as_deref())
+}
+
+/// Returns the parse error for a non-empty encoder name that is not valid.
+fn invalid_encoder_name(name: Option<&str>) -> Option<ObsCutterError> {
+    let name = name.map(str::trim).filter(|name| !name.is_empty())?;
+    name.parse::<HardwareEncoder>().err()
 }
 
 /// Detects the best available hardware encoder for the current system.
 ///
-/// Checks encoders in order of preference:
+/// [`FORCE_SOFTWARE_ENV`] and [`ENCODER_ENV`] override detection, so
+/// containers and CI can pin the encoder without code or CLI changes.
+/// Otherwise checks encoders in order of preference:
 /// 1. VideoToolbox (macOS only)
 /// 2. NVENC (NVIDIA GPUs)
 /// 3. Quick Sync (Intel)
 /// 4. AMF (AMD)
-/// 5. Software fallback (libx264)
+/// 5. VAAPI (Linux, requires a render node)
+/// 6. Software fallback (libx264)
 pub fn detect_hardware_encoder() -> HardwareEncoder {
-    // macOS: VideoToolbox (works on both Apple Silicon and Intel)
-    if cfg!(target_os = "macos") && check_encoder_available("h264_videotoolbox") {
-        return HardwareEncoder::VideoToolbox;
-    }
-
-    // NVIDIA: Check for NVENC
-    if check_encoder_available("h264_nvenc") {
-        return HardwareEncoder::Nvenc;
+    let force_software = std::env::var(FORCE_SOFTWARE_ENV).ok();
+    let encoder = std::env::var(ENCODER_ENV).ok();
+    if let Some(encoder) = encoder_override(force_software.as_deref(), encoder.as_deref()) {
+        return encoder;
     }
 
-    // Intel: Check for Quick Sync
-    if check_encoder_available("h264_qsv") {
-        return HardwareEncoder::QuickSync;
-    }
-
-    // AMD: Check for AMF
-    if check_encoder_available("h264_amf") {
-        return HardwareEncoder::Amf;
-    }
-
-    // Fallback to software encoding
-    HardwareEncoder::None
+    probe_all_encoders()
+        .into_iter()
+        .next()
+        .unwrap_or(HardwareEncoder::None)
 }
 
-/// Returns FFmpeg codec arguments for the given quality and encoder.
-pub fn get_codec_args(quality: &str, encoder: &HardwareEncoder) -> Vec<String> {
-    let encoder_name = encoder.h264_encoder();
+/// Returns FFmpeg codec arguments for the given quality, encoder, and audio mode.
+///
+/// 10-bit sources are encoded as H.265 Main 10 so HDR captures keep their
+/// bit depth; most encoders have no 10-bit H.264 profile.
+pub fn get_codec_args(
+    quality: &str,
+    encoder: &HardwareEncoder,
+    audio: &AudioMode,
+    ten_bit: bool,
+) -> Vec<String> {
+    let encoder_name = if ten_bit {
+        encoder.hevc_encoder()
+    } else {
+        encoder.h264_encoder()
+    };
 
-    match encoder {
+    let mut args = match encoder {
         HardwareEncoder::VideoToolbox => {
             // VideoToolbox uses bitrate-based encoding
-            let bitrate = match quality {
-                "high" => "15M",
-                "medium" => "10M",
-                _ => "25M", // lossless/highest quality
-            };
+            let bitrate = encoder.target_bitrate_mbps(quality).unwrap_or(25);
             vec![
                 "-c:v".to_string(),
                 encoder_name.to_string(),
                 "-b:v".to_string(),
-                bitrate.to_string(),
+                format!("{}M", bitrate),
                 "-allow_sw".to_string(),
                 "1".to_string(),
-                "-c:a".to_string(),
-                "copy".to_string(),
             ]
         }
         HardwareEncoder::Nvenc => {
             // NVENC supports CRF-like quality with -cq parameter
             let cq = match quality {
                 "high" => "18",
-                "medium" => "23",
+                "medium" | "fast" => "23",
                 _ => "15", // lossless/highest quality
             };
             let preset = match quality {
                 "high" => "p7",   // Slowest, highest quality
                 "medium" => "p4", // Medium
+                "fast" => "p1",   // Fastest
                 _ => "p7",        // Maximum quality for lossless
             };
             vec![
@@ -152,36 +433,37 @@ pub fn get_codec_args(quality: &str, encoder: &HardwareEncoder) -> Vec<String> {
                 preset.to_string(),
                 "-cq".to_string(),
                 cq.to_string(),
-                "-c:a".to_string(),
-                "copy".to_string(),
             ]
         }
         HardwareEncoder::QuickSync => {
             // Quick Sync uses global_quality parameter
             let quality_param = match quality {
                 "high" => "18",
-                "medium" => "23",
+                "medium" | "fast" => "23",
                 _ => "15", // Best quality
             };
-            vec![
+            let mut args = vec![
                 "-c:v".to_string(),
                 encoder_name.to_string(),
                 "-global_quality".to_string(),
                 quality_param.to_string(),
-                "-look_ahead".to_string(),
-                "1".to_string(),
-                "-c:a".to_string(),
-                "copy".to_string(),
-            ]
+            ];
+            if quality == "fast" {
+                // Skip look-ahead and use the fastest preset
+                args.extend(["-preset".to_string(), "veryfast".to_string()]);
+            } else {
+                args.extend(["-look_ahead".to_string(), "1".to_string()]);
+            }
+            args
         }
         HardwareEncoder::Amf => {
             // AMF uses quality parameter
             let quality_param = match quality {
                 "high" => "18",
-                "medium" => "23",
+                "medium" | "fast" => "23",
                 _ => "15", // Best quality
             };
-            vec![
+            let mut args = vec![
                 "-c:v".to_string(),
                 encoder_name.to_string(),
                 "-rc".to_string(),
@@ -190,44 +472,319 @@ pub fn get_codec_args(quality: &str, encoder: &HardwareEncoder) -> Vec<String> {
                 quality_param.to_string(),
                 "-qp_p".to_string(),
                 quality_param.to_string(),
-                "-c:a".to_string(),
-                "copy".to_string(),
+            ];
+            if quality == "fast" {
+                args.extend(["-quality".to_string(), "speed".to_string()]);
+            }
+            args
+        }
+        HardwareEncoder::Vaapi => {
+            // VAAPI uses constant QP; frames are uploaded by the filter chain
+            let quality_param = match quality {
+                "high" => "18",
+                "medium" | "fast" => "23",
+                _ => "15", // Best quality
+            };
+            vec![
+                "-c:v".to_string(),
+                encoder_name.to_string(),
+                "-rc_mode".to_string(),
+                "CQP".to_string(),
+                "-qp".to_string(),
+                quality_param.to_string(),
             ]
         }
         HardwareEncoder::None => {
-            // Software encoding (libx264)
+            // Software encoding (libx264, or libx265 for 10-bit)
             match quality {
                 "high" => vec![
                     "-c:v".to_string(),
-                    "libx264".to_string(),
+                    encoder_name.to_string(),
                     "-crf".to_string(),
                     "18".to_string(),
                     "-preset".to_string(),
                     "slow".to_string(),
-                    "-c:a".to_string(),
-                    "copy".to_string(),
                 ],
                 "medium" => vec![
                     "-c:v".to_string(),
-                    "libx264".to_string(),
+                    encoder_name.to_string(),
                     "-crf".to_string(),
                     "23".to_string(),
                     "-preset".to_string(),
                     "medium".to_string(),
-                    "-c:a".to_string(),
-                    "copy".to_string(),
+                ],
+                "fast" => vec![
+                    "-c:v".to_string(),
+                    encoder_name.to_string(),
+                    "-crf".to_string(),
+                    "23".to_string(),
+                    "-preset".to_string(),
+                    "ultrafast".to_string(),
                 ],
                 _ => vec![
                     "-c:v".to_string(),
-                    "libx264".to_string(),
+                    encoder_name.to_string(),
                     "-crf".to_string(),
                     "0".to_string(),
                     "-preset".to_string(),
                     "veryslow".to_string(),
-                    "-c:a".to_string(),
-                    "copy".to_string(),
                 ], // lossless
             }
         }
+    };
+
+    if ten_bit {
+        if let Some(pix_fmt) = encoder.ten_bit_pix_fmt() {
+            args.extend(["-pix_fmt".to_string(), pix_fmt.to_string()]);
+        }
+        args.extend(["-profile:v".to_string(), "main10".to_string()]);
+    }
+
+    args.extend(audio.codec_args());
+    args
+}
+
+/// Replaces the preset chosen by [`get_codec_args`] or [`get_bitrate_args`]
+/// with `preset`.
+///
+/// Presets the encoder does not accept are ignored and the default kept, so
+/// an NVENC preset does not break a fallback to software encoding.
+pub fn apply_preset(args: &mut Vec<String>, encoder: &HardwareEncoder, preset: &str) {
+    let Some(option) = encoder.preset_option() else {
+        return;
+    };
+    if !encoder.presets().contains(&preset) {
+        return;
+    }
+    match args.iter().position(|arg| arg == option) {
+        Some(index) if index + 1 < args.len() => args[index + 1] = preset.to_string(),
+        _ => args.extend([option.to_string(), preset.to_string()]),
+    }
+}
+
+/// Returns true if `codec` (an FFmpeg codec name such as "h264" or "aac")
+/// can be stored in `container` (a file extension or muxer name).
+///
+/// Containers not listed here are assumed to accept any codec.
+pub fn is_codec_compatible(container: &str, codec: &str) -> bool {
+    let allowed: &[&str] = match container.to_ascii_lowercase().as_str() {
+        "mp4" | "m4v" => &[
+            "h264", "hevc", "av1", "mpeg4", "aac", "mp3", "ac3", "eac3", "alac", "opus", "flac",
+        ],
+        "mov" => &[
+            "h264",
+            "hevc",
+            "prores",
+            "mpeg4",
+            "aac",
+            "mp3",
+            "ac3",
+            "eac3",
+            "alac",
+            "pcm_s16le",
+            "pcm_s24le",
+            "pcm_f32le",
+        ],
+        "webm" => &["vp8", "vp9", "av1", "opus", "vorbis"],
+        // Matroska (mkv) holds practically anything
+        _ => return true,
+    };
+    allowed.contains(&codec)
+}
+
+/// Returns libx264 video arguments targeting an average bitrate (kbit/s)
+/// instead of a constant quality.
+///
+/// The encoder preset still follows the quality preset. 10-bit sources use
+/// x264's High 10 profile, which keeps two-pass encoding available.
+pub fn get_bitrate_args(quality: &str, bitrate_kbps: u32, ten_bit: bool) -> Vec<String> {
+    let preset = match quality {
+        "high" => "slow",
+        "medium" => "medium",
+        "fast" => "ultrafast",
+        _ => "veryslow",
+    };
+    let mut args = vec![
+        "-c:v".to_string(),
+        HardwareEncoder::None.h264_encoder().to_string(),
+        "-b:v".to_string(),
+        format!("{}k", bitrate_kbps),
+        "-preset".to_string(),
+        preset.to_string(),
+    ];
+    if ten_bit {
+        args.extend(["-pix_fmt", "yuv420p10le", "-profile:v", "high10"].map(String::from));
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_encoder_listed() {
+        let list = "Encoders:\n \
+            V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC\n \
+            V....D h264_nvenc           NVIDIA NVENC H.264 encoder (codec h264)\n \
+            V....D h264_qsv_extra       Not a real encoder\n";
+
+        assert!(is_encoder_listed(list, &HardwareEncoder::Nvenc));
+        assert!(is_encoder_listed(list, &HardwareEncoder::None));
+        // Substring matches must not count
+        assert!(!is_encoder_listed(list, &HardwareEncoder::QuickSync));
+        assert!(!is_encoder_listed(list, &HardwareEncoder::Amf));
+    }
+
+    #[test]
+    fn test_encoder_from_str() {
+        assert_eq!(
+            "nvenc".parse::<HardwareEncoder>().unwrap(),
+            HardwareEncoder::Nvenc
+        );
+        assert_eq!(
+            "QSV".parse::<HardwareEncoder>().unwrap(),
+            HardwareEncoder::QuickSync
+        );
+        assert_eq!(
+            "none".parse::<HardwareEncoder>().unwrap(),
+            HardwareEncoder::None
+        );
+        assert!("x265".parse::<HardwareEncoder>().is_err());
+
+        for encoder in HARDWARE_ENCODERS.iter().chain([&HardwareEncoder::None]) {
+            assert_eq!(
+                encoder.as_str().parse::<HardwareEncoder>().unwrap(),
+                *encoder
+            );
+        }
+    }
+
+    #[test]
+    fn test_ten_bit_codec_args() {
+        let args = get_codec_args("high", &HardwareEncoder::Nvenc, &AudioMode::Copy, true);
+        let joined = args.join(" ");
+        assert!(joined.starts_with("-c:v hevc_nvenc"));
+        assert!(joined.contains("-pix_fmt p010le -profile:v main10"));
+
+        let args = get_codec_args("high", &HardwareEncoder::None, &AudioMode::Copy, true);
+        assert!(args.join(" ").contains("libx265"));
+        assert!(args.join(" ").contains("-pix_fmt yuv420p10le"));
+
+        // VAAPI gets its pixel format from the upload filter
+        let args = get_codec_args("high", &HardwareEncoder::Vaapi, &AudioMode::Copy, true);
+        assert!(!args.contains(&"-pix_fmt".to_string()));
+        assert_eq!(
+            HardwareEncoder::Vaapi.upload_filter(true),
+            Some("format=p010,hwupload")
+        );
+
+        let args = get_codec_args("high", &HardwareEncoder::Nvenc, &AudioMode::Copy, false);
+        assert_eq!(args[1], "h264_nvenc");
+        assert!(!args.contains(&"-pix_fmt".to_string()));
+    }
+
+    #[test]
+    fn test_source_bitrate_mbps() {
+        let encoder = HardwareEncoder::VideoToolbox;
+        assert_eq!(
+            encoder.source_bitrate_mbps("lossless", 50_000_000),
+            Some(25)
+        );
+        assert_eq!(encoder.source_bitrate_mbps("lossless", 12_500_000), Some(7));
+        assert_eq!(encoder.source_bitrate_mbps("lossless", 100), Some(1));
+        assert_eq!(encoder.source_bitrate_mbps("high", 50_000_000), None);
+        assert_eq!(
+            HardwareEncoder::Nvenc.source_bitrate_mbps("lossless", 50_000_000),
+            None
+        );
+    }
+
+    #[test]
+    fn test_can_encode() {
+        assert!(HardwareEncoder::Nvenc.can_encode(3840, 2160, false));
+        assert!(!HardwareEncoder::Nvenc.can_encode(7680, 2160, false));
+        assert!(!HardwareEncoder::Amf.can_encode(4096, 2304, false));
+        // Software and H.265 have no practical limit
+        assert!(HardwareEncoder::None.can_encode(7680, 4320, false));
+        assert!(HardwareEncoder::Nvenc.can_encode(7680, 2160, true));
+    }
+
+    #[test]
+    fn test_encoder_override() {
+        assert_eq!(encoder_override(None, None), None);
+        assert_eq!(
+            encoder_override(Some("1"), None),
+            Some(HardwareEncoder::None)
+        );
+        assert_eq!(
+            encoder_override(Some("1"), Some("nvenc")),
+            Some(HardwareEncoder::None)
+        );
+        assert_eq!(
+            encoder_override(Some("0"), Some("NVENC")),
+            Some(HardwareEncoder::Nvenc)
+        );
+        assert_eq!(encoder_override(Some(""), Some("bogus")), None);
+    }
+
+    #[test]
+    fn test_invalid_encoder_name() {
+        assert!(invalid_encoder_name(None).is_none());
+        assert!(invalid_encoder_name(Some(" ")).is_none());
+        assert!(invalid_encoder_name(Some("NVENC")).is_none());
+        let error = invalid_encoder_name(Some("cuda")).unwrap().to_string();
+        assert!(error.contains("cuda") && error.contains("nvenc"));
+    }
+
+    #[test]
+    fn test_apply_preset() {
+        let mut args = get_codec_args("high", &HardwareEncoder::Nvenc, &AudioMode::Copy, false);
+        apply_preset(&mut args, &HardwareEncoder::Nvenc, "p1");
+        assert!(args.join(" ").contains("-preset p1 -cq 18"));
+
+        // AMF's preset is its -quality option, added when the default has none
+        let mut args = get_codec_args("high", &HardwareEncoder::Amf, &AudioMode::Copy, false);
+        apply_preset(&mut args, &HardwareEncoder::Amf, "balanced");
+        assert!(args.join(" ").ends_with("-quality balanced"));
+
+        // Presets the encoder doesn't take, and encoders without presets, keep the defaults
+        let defaults = get_codec_args("high", &HardwareEncoder::None, &AudioMode::Copy, false);
+        let mut args = defaults.clone();
+        apply_preset(&mut args, &HardwareEncoder::None, "p1");
+        assert_eq!(args, defaults);
+        let defaults = get_codec_args(
+            "high",
+            &HardwareEncoder::VideoToolbox,
+            &AudioMode::Copy,
+            false,
+        );
+        let mut args = defaults.clone();
+        apply_preset(&mut args, &HardwareEncoder::VideoToolbox, "fast");
+        assert_eq!(args, defaults);
+    }
+
+    #[test]
+    fn test_check_preset() {
+        assert!(HardwareEncoder::Nvenc.check_preset("p4").is_ok());
+        assert!(HardwareEncoder::None.check_preset("veryslow").is_ok());
+        assert!(HardwareEncoder::VideoToolbox
+            .check_preset("anything")
+            .is_ok());
+
+        let error = HardwareEncoder::Nvenc.check_preset("fast").unwrap_err();
+        assert!(error.to_string().contains("p1, p2, p3, p4, p5, p6, p7"));
+    }
+
+    #[test]
+    fn test_is_codec_compatible() {
+        assert!(is_codec_compatible("mp4", "h264"));
+        assert!(is_codec_compatible("MP4", "aac"));
+        assert!(!is_codec_compatible("mp4", "pcm_s16le"));
+        assert!(is_codec_compatible("mov", "pcm_s16le"));
+        assert!(is_codec_compatible("mkv", "pcm_s16le"));
+        assert!(!is_codec_compatible("webm", "aac"));
+        assert!(!is_codec_compatible("webm", "h264"));
+        assert!(is_codec_compatible("webm", "opus"));
     }
 }
@@ -1,5 +1,7 @@
 //! Error types for obs-cutter operations.
 
+use crate::core::encoder::HardwareEncoder;
+use crate::core::format::format_file_size;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -10,6 +12,10 @@ pub enum ObsCutterError {
     #[error("FFmpeg is not installed or not found in PATH")]
     FfmpegNotFound,
 
+    /// FFmpeg exists but cannot be executed (e.g. missing execute permission).
+    #[error("FFmpeg at {} is not executable. Try: chmod +x {}", .0.display(), .0.display())]
+    FfmpegNotExecutable(PathBuf),
+
     /// FFprobe is not installed or not found.
     #[error("FFprobe is not installed or not found in PATH")]
     FfprobeNotFound,
@@ -22,18 +28,106 @@ pub enum ObsCutterError {
     #[error("Failed to analyze video: {0}")]
     VideoAnalysisFailed(String),
 
+    /// The input kept changing size while waiting for it to be fully written.
+    #[error("{} is still being written; try again once recording has stopped", .0.display())]
+    InputStillGrowing(PathBuf),
+
+    /// The file is not a media file FFprobe can read.
+    #[error("Not a recognized video file: {}", .0.display())]
+    NotMediaFile(PathBuf),
+
     /// No video stream found in file.
     #[error("No video stream found in file")]
     NoVideoStream,
 
+    /// The requested video stream does not exist.
+    #[error("Video stream {index} not found; the file has {count} video stream(s)")]
+    VideoStreamNotFound { index: usize, count: usize },
+
     /// Invalid video dimensions.
     #[error("Invalid video dimensions: {width}x{height}")]
     InvalidDimensions { width: u32, height: u32 },
 
+    /// Invalid frame dimensions given on the command line.
+    #[error("Invalid dimensions: {0}. Expected WIDTHxHEIGHT, e.g. 3840x1080")]
+    InvalidDimensionsFormat(String),
+
+    /// Invalid frame rate given on the command line.
+    #[error("Invalid frame rate: {0}. Expected a positive number, e.g. 60 or 29.97")]
+    InvalidFrameRate(String),
+
+    /// Invalid scene change threshold given on the command line.
+    #[error("Invalid scene change threshold: {0}. Expected a number between 0 and 1, e.g. 0.4")]
+    InvalidSceneThreshold(String),
+
+    /// Invalid color range specified.
+    #[error("Invalid color range: {0}. Valid options: full (pc), limited (tv)")]
+    InvalidColorRange(String),
+
     /// Invalid quality preset.
-    #[error("Invalid quality preset: {0}. Valid options: lossless, high, medium")]
+    #[error(
+        "Invalid quality preset: {0}. Valid options: lossless (best, archive), \
+         high (balanced), medium (small), fast"
+    )]
     InvalidQuality(String),
 
+    /// Invalid audio mode.
+    #[error("Invalid audio mode: {0}. Valid options: copy, aac, none")]
+    InvalidAudioMode(String),
+
+    /// Invalid encoder name.
+    #[error("Invalid encoder: {0}. Valid options: videotoolbox, nvenc, qsv, amf, vaapi, software")]
+    InvalidEncoder(String),
+
+    /// Encoder preset not accepted by the encoder.
+    #[error("Invalid preset '{preset}' for {encoder}. Valid options: {valid}")]
+    InvalidPreset {
+        preset: String,
+        encoder: String,
+        valid: String,
+    },
+
+    /// Invalid timestamp.
+    #[error("Invalid timestamp: {0}. Expected HH:MM:SS[.mmm], MM:SS or seconds")]
+    InvalidTimestamp(String),
+
+    /// Invalid or contradictory trim range.
+    #[error("Invalid trim range: {0}")]
+    InvalidTrim(String),
+
+    /// The estimated output does not fit in the free disk space.
+    #[error(
+        "Not enough disk space: about {} needed, {} available",
+        format_file_size(*.required),
+        format_file_size(*.available)
+    )]
+    InsufficientDiskSpace { required: u64, available: u64 },
+
+    /// Both outputs were written, but the input could not be removed.
+    #[error("Outputs were written, but the source {} could not be deleted: {reason}", .path.display())]
+    DeleteSourceFailed { path: PathBuf, reason: String },
+
+    /// A file named like a chunk of the output already exists, e.g. from an
+    /// earlier run, and would be mixed up with the new chunks.
+    #[error(
+        "{} already exists; remove the chunks of the earlier run before writing {} in chunks",
+        .existing.display(),
+        .output.display()
+    )]
+    StaleChunks { output: PathBuf, existing: PathBuf },
+
+    /// Videos to join cannot be stacked in the requested layout.
+    #[error("Cannot join videos: {0}")]
+    MismatchedDimensions(String),
+
+    /// Output naming template that would not produce distinct files.
+    #[error("Invalid name template: {0}")]
+    InvalidNameTemplate(String),
+
+    /// The output container cannot hold a codec.
+    #[error("A .{container} file cannot contain {codec}")]
+    IncompatibleCodec { container: String, codec: String },
+
     /// Invalid side parameter.
     #[error("Invalid side: {0}. Valid options: left, right")]
     InvalidSide(String),
@@ -42,6 +136,24 @@ pub enum ObsCutterError {
     #[error("FFmpeg processing failed: {0}")]
     FfmpegFailed(String),
 
+    /// FFmpeg could not use the hardware encoder, e.g. because its driver
+    /// is missing or this FFmpeg build lacks it.
+    #[error("{} encoder is not available on this system", .0.name())]
+    EncoderUnavailable(HardwareEncoder),
+
+    /// The output is larger than the hardware encoder can encode.
+    #[error(
+        "{width}x{height} exceeds the {} encoder's maximum of {}x{}",
+        .encoder.name(),
+        .encoder.max_dimensions().unwrap_or_default().0,
+        .encoder.max_dimensions().unwrap_or_default().1
+    )]
+    ResolutionTooLarge {
+        encoder: HardwareEncoder,
+        width: u32,
+        height: u32,
+    },
+
     /// Failed to create output directory.
     #[error("Failed to create output directory: {0}")]
     OutputDirectoryError(String),
@@ -4,10 +4,14 @@
 //! 1. Bundled binaries (relative to executable) - for distributed applications
 //! 2. System PATH - for development and CLI usage
 
+use crate::core::encoder::HardwareEncoder;
 use crate::core::error::{ObsCutterError, Result};
+use regex::Regex;
 use std::env;
+use std::io;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::{LazyLock, OnceLock};
 
 /// Returns the path to the FFmpeg binary.
 ///
@@ -105,10 +109,24 @@ pub fn check_ffmpeg() -> Result<()> {
 
     match output {
         Ok(status) if status.success() => Ok(()),
+        Err(e) => Err(ffmpeg_spawn_error(e)),
         _ => Err(ObsCutterError::FfmpegNotFound),
     }
 }
 
+/// Maps a failure to spawn FFmpeg to the matching error.
+///
+/// A binary that exists but cannot be executed (typically a bundled copy
+/// that lost its execute bit when unzipped) is reported separately from a
+/// missing one, since installing another copy would not help.
+pub(crate) fn ffmpeg_spawn_error(error: io::Error) -> ObsCutterError {
+    match error.kind() {
+        io::ErrorKind::NotFound => ObsCutterError::FfmpegNotFound,
+        io::ErrorKind::PermissionDenied => ObsCutterError::FfmpegNotExecutable(get_ffmpeg_path()),
+        _ => ObsCutterError::FfmpegFailed(error.to_string()),
+    }
+}
+
 /// Checks if FFprobe is available and returns Ok if found.
 pub fn check_ffprobe() -> Result<()> {
     let ffprobe_path = get_ffprobe_path();
@@ -146,9 +164,266 @@ pub fn get_ffmpeg_version() -> Option<String> {
     }
 }
 
+/// A released FFmpeg version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FfmpegVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+static VERSION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"version n?(\d+)\.(\d+)(?:\.(\d+))?").unwrap());
+
+impl FfmpegVersion {
+    /// Creates a version from its components.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses the first line of `ffmpeg -version`.
+    ///
+    /// Example: "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers".
+    /// Returns None for git snapshots such as "ffmpeg version N-112345-g1234abcd".
+    pub fn parse(line: &str) -> Option<Self> {
+        let caps = VERSION_REGEX.captures(line)?;
+        Some(Self {
+            major: caps.get(1)?.as_str().parse().ok()?,
+            minor: caps.get(2)?.as_str().parse().ok()?,
+            patch: caps
+                .get(3)
+                .map_or(Some(0), |patch| patch.as_str().parse().ok())?,
+        })
+    }
+
+    /// Returns true if this version supports `feature`.
+    pub fn supports(&self, feature: FfmpegFeature) -> bool {
+        *self >= feature.min_version()
+    }
+}
+
+impl std::fmt::Display for FfmpegVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// FFmpeg features that require a minimum version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfmpegFeature {
+    /// NVENC `p1`-`p7` presets.
+    NvencPresets,
+    /// `-fps_mode`, which replaces the deprecated `-vsync`.
+    FpsMode,
+}
+
+impl FfmpegFeature {
+    /// Returns the first FFmpeg release supporting this feature.
+    pub fn min_version(&self) -> FfmpegVersion {
+        match self {
+            FfmpegFeature::NvencPresets => FfmpegVersion::new(4, 3, 0),
+            FfmpegFeature::FpsMode => FfmpegVersion::new(5, 1, 0),
+        }
+    }
+
+    /// Returns the feature the encoder's arguments depend on, if any.
+    pub fn required_by(encoder: &HardwareEncoder) -> Option<Self> {
+        match encoder {
+            HardwareEncoder::Nvenc => Some(FfmpegFeature::NvencPresets),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the installed FFmpeg version, if it can be determined.
+pub fn get_ffmpeg_version_parsed() -> Option<FfmpegVersion> {
+    get_ffmpeg_version()
+        .as_deref()
+        .and_then(FfmpegVersion::parse)
+}
+
+/// Returns true if the installed FFmpeg supports `feature`.
+///
+/// Builds whose version cannot be parsed (typically recent git snapshots)
+/// are assumed to support everything.
+pub fn supports_feature(feature: FfmpegFeature) -> bool {
+    get_ffmpeg_version_parsed().is_none_or(|version| version.supports(feature))
+}
+
+/// Cached output of `ffmpeg -filters`, probed once per process.
+static FILTER_LIST: OnceLock<Option<String>> = OnceLock::new();
+
+/// Returns true if the installed FFmpeg provides the filter `name`.
+///
+/// FFmpeg is queried only on first use; false if it cannot be run.
+pub fn has_filter(name: &str) -> bool {
+    FILTER_LIST
+        .get_or_init(|| {
+            let output = Command::new(get_ffmpeg_path())
+                .args(["-hide_banner", "-filters"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .output()
+                .ok()?;
+
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+        .as_deref()
+        .is_some_and(|list| is_filter_listed(list, name))
+}
+
+/// Checks if a filter is listed in the `ffmpeg -filters` output, whose
+/// lines read e.g. ` ... crop              V->V       Crop the input video.`
+fn is_filter_listed(filter_list: &str, name: &str) -> bool {
+    filter_list
+        .lines()
+        .any(|line| line.split_whitespace().nth(1) == Some(name))
+}
+
 /// Returns true if using bundled FFmpeg, false if using system FFmpeg.
 pub fn is_bundled() -> bool {
     let ffmpeg_path = get_ffmpeg_path();
     // If path is just "ffmpeg", it's using system PATH
     ffmpeg_path.components().count() > 1
 }
+
+/// Known FFmpeg failures, as a pattern in its stderr and the advice shown
+/// for it. Checked in order, so more specific patterns come first.
+const FRIENDLY_ERRORS: &[(&str, &str)] = &[
+    (
+        "does not support non seekable output",
+        "The chosen format can't be written to a pipe; use Matroska (mkv) instead.",
+    ),
+    (
+        "codec not currently supported in container",
+        "The chosen format can't hold this audio or video codec; try MKV or enable audio re-encoding.",
+    ),
+    (
+        "muxer does not support",
+        "The chosen format can't hold this audio; try MKV or enable audio re-encoding.",
+    ),
+    (
+        "moov atom not found",
+        "The recording was never finalized (OBS may have crashed while recording); it can't be read as is.",
+    ),
+    (
+        "Invalid data found when processing input",
+        "The input looks damaged or incomplete; try remuxing it first.",
+    ),
+    (
+        "No space left on device",
+        "The output disk is full; free up space or choose another output directory.",
+    ),
+    (
+        "Permission denied",
+        "FFmpeg couldn't read the input or write the output; check the file permissions.",
+    ),
+    (
+        "Error while opening encoder",
+        "The encoder rejected these settings; try software encoding or another quality preset.",
+    ),
+];
+
+/// Turns FFmpeg's stderr into a message a user can act on.
+///
+/// Known failures, such as a container that can't hold the audio codec, are
+/// mapped to advice; anything else is reported as FFmpeg's last line, which
+/// usually names the error.
+pub fn humanize_ffmpeg_error(stderr: &str) -> String {
+    if let Some((_, advice)) = FRIENDLY_ERRORS
+        .iter()
+        .find(|(pattern, _)| stderr.contains(pattern))
+    {
+        return advice.to_string();
+    }
+
+    stderr
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .map(|line| format!("FFmpeg failed: {}", line))
+        .unwrap_or_else(|| "FFmpeg failed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_humanize_ffmpeg_error() {
+        let stderr = "[mp4 @ 0x55d] Could not find tag for codec pcm_s16le in stream #1, \
+                      codec not currently supported in container\n\
+                      Could not write header for output file #0 (incorrect codec parameters ?)";
+        assert_eq!(
+            humanize_ffmpeg_error(stderr),
+            "The chosen format can't hold this audio or video codec; try MKV or enable audio re-encoding."
+        );
+
+        assert_eq!(
+            humanize_ffmpeg_error("frame=10\nConversion failed!\n\n"),
+            "FFmpeg failed: Conversion failed!"
+        );
+        assert_eq!(humanize_ffmpeg_error(""), "FFmpeg failed");
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_version() {
+        let line = "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers";
+        assert_eq!(
+            FfmpegVersion::parse(line),
+            Some(FfmpegVersion::new(6, 1, 1))
+        );
+
+        let line = "ffmpeg version n7.0 Copyright (c) 2000-2024 the FFmpeg developers";
+        assert_eq!(
+            FfmpegVersion::parse(line),
+            Some(FfmpegVersion::new(7, 0, 0))
+        );
+
+        let line = "ffmpeg version N-112345-g1234abcd Copyright (c) 2000-2024";
+        assert_eq!(FfmpegVersion::parse(line), None);
+    }
+
+    #[test]
+    fn test_version_supports_feature() {
+        assert!(!FfmpegVersion::new(4, 2, 7).supports(FfmpegFeature::NvencPresets));
+        assert!(FfmpegVersion::new(4, 3, 0).supports(FfmpegFeature::NvencPresets));
+        assert!(!FfmpegVersion::new(5, 0, 3).supports(FfmpegFeature::FpsMode));
+        assert!(FfmpegVersion::new(5, 1, 0).supports(FfmpegFeature::FpsMode));
+    }
+
+    #[test]
+    fn test_is_filter_listed() {
+        let list = "Filters:\n  T.. = Timeline support\n  ------\n \
+                    ... crop              V->V       Crop the input video.\n \
+                    ... crop_cuda         V->V       Crop video on the GPU.\n \
+                    ... scale_npp         V->V       NVIDIA Performance Primitives scaler.\n";
+        assert!(is_filter_listed(list, "crop_cuda"));
+        assert!(is_filter_listed(list, "crop"));
+        assert!(!is_filter_listed(list, "scale_cuda"));
+        // Descriptions do not count as filter names
+        assert!(!is_filter_listed(list, "Crop"));
+    }
+
+    #[test]
+    fn test_spawn_error_distinguishes_permission_denied() {
+        let not_found = io::Error::from(io::ErrorKind::NotFound);
+        assert!(matches!(
+            ffmpeg_spawn_error(not_found),
+            ObsCutterError::FfmpegNotFound
+        ));
+
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            ffmpeg_spawn_error(denied),
+            ObsCutterError::FfmpegNotExecutable(_)
+        ));
+    }
+}
@@ -0,0 +1,163 @@
+//! Reading batch input lists and checking inputs are ready to read.
+
+use crate::core::error::{ObsCutterError, Result};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long a file's size must stay unchanged before it counts as fully
+/// written by [`wait_for_stable`].
+pub const STABLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Extensions of the video files the frontends offer and pick up,
+/// including the `.ts` and `.flv` recordings OBS can write.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "mp4", "mov", "mkv", "avi", "webm", "ts", "flv", "m4v", "mpg",
+];
+
+/// Returns true if `path` has one of the [`SUPPORTED_EXTENSIONS`],
+/// compared case-insensitively.
+pub fn is_supported_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Reads a newline-delimited list of video paths from `path`.
+///
+/// Blank lines and lines starting with `#` are skipped, and surrounding
+/// whitespace is trimmed. Relative paths are returned as written, so they
+/// resolve against the working directory like paths given as arguments.
+pub fn read_input_list(path: &Path) -> Result<Vec<PathBuf>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(parse_input_list(&contents))
+}
+
+/// Parses the contents of an input list; see [`read_input_list`].
+fn parse_input_list(contents: &str) -> Vec<PathBuf> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Waits until `path` has stopped growing, i.e. its size has been non-zero
+/// and unchanged for [`STABLE_INTERVAL`].
+///
+/// Guards against splitting a recording OBS is still writing. Fails with
+/// [`ObsCutterError::InputStillGrowing`] if the file is still changing
+/// after `timeout`.
+pub fn wait_for_stable(path: &Path, timeout: Duration) -> Result<()> {
+    wait_for_stable_for(path, STABLE_INTERVAL, timeout)
+}
+
+/// [`wait_for_stable`] with a configurable stable interval.
+fn wait_for_stable_for(path: &Path, stable_for: Duration, timeout: Duration) -> Result<()> {
+    let poll_interval = (stable_for / 4).min(Duration::from_millis(500));
+    let started = Instant::now();
+    let file_size = || {
+        std::fs::metadata(path)
+            .map(|metadata| metadata.len())
+            .map_err(|_| ObsCutterError::VideoNotFound(path.to_path_buf()))
+    };
+
+    let mut last_size = file_size()?;
+    let mut last_change = Instant::now();
+    loop {
+        if last_size > 0 && last_change.elapsed() >= stable_for {
+            return Ok(());
+        }
+        if started.elapsed() >= timeout {
+            return Err(ObsCutterError::InputStillGrowing(path.to_path_buf()));
+        }
+        std::thread::sleep(poll_interval);
+
+        let size = file_size()?;
+        if size != last_size {
+            last_size = size;
+            last_change = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_input_list() {
+        let contents = "# recorded on stream\n\
+                        first.mov\r\n\
+                        \n   \n\
+                        \t/videos/second take.mkv  \n\
+                        #skipped.mov\n";
+        assert_eq!(
+            parse_input_list(contents),
+            vec![
+                PathBuf::from("first.mov"),
+                PathBuf::from("/videos/second take.mkv"),
+            ]
+        );
+        assert!(parse_input_list("").is_empty());
+    }
+
+    #[test]
+    fn test_is_supported_extension() {
+        assert!(is_supported_extension(Path::new("stream.ts")));
+        assert!(is_supported_extension(Path::new("/videos/Stream.FLV")));
+        assert!(is_supported_extension(Path::new("clip.mkv")));
+        assert!(!is_supported_extension(Path::new("notes.txt")));
+        assert!(!is_supported_extension(Path::new("mkv")));
+    }
+
+    #[test]
+    fn test_wait_for_stable() {
+        let dir = std::env::temp_dir().join(format!("obs-cutter-stable-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stable_for = Duration::from_millis(40);
+        let timeout = Duration::from_millis(400);
+
+        let finished = dir.join("finished.mp4");
+        std::fs::write(&finished, b"video").unwrap();
+        assert!(wait_for_stable_for(&finished, stable_for, timeout).is_ok());
+
+        // An empty file is not a finished recording
+        let empty = dir.join("empty.mp4");
+        std::fs::write(&empty, b"").unwrap();
+        assert!(matches!(
+            wait_for_stable_for(&empty, stable_for, timeout),
+            Err(ObsCutterError::InputStillGrowing(_))
+        ));
+
+        // A file appended to faster than the stable interval never settles
+        let growing = dir.join("growing.mp4");
+        std::fs::write(&growing, b"v").unwrap();
+        let writer = {
+            let growing = growing.clone();
+            std::thread::spawn(move || {
+                use std::io::Write;
+                let mut file = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(growing)
+                    .unwrap();
+                for _ in 0..60 {
+                    file.write_all(b"v").unwrap();
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            })
+        };
+        assert!(matches!(
+            wait_for_stable_for(&growing, stable_for, timeout),
+            Err(ObsCutterError::InputStillGrowing(_))
+        ));
+        writer.join().unwrap();
+
+        assert!(matches!(
+            wait_for_stable_for(&dir.join("missing.mp4"), stable_for, timeout),
+            Err(ObsCutterError::VideoNotFound(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+//! Journal of completed batch inputs, for resuming interrupted batches.
+
+use crate::core::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// File name of the journal, written to the batch's output directory.
+pub const JOURNAL_FILE_NAME: &str = ".obs-cutter-journal.json";
+
+/// Records which inputs of a batch have been split successfully.
+///
+/// Inputs are recorded with their size when they completed, so an input
+/// that has since been replaced by a different file is not skipped. The
+/// journal is saved after every completion, so it survives a crash.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BatchJournal {
+    /// Where the journal is saved.
+    #[serde(skip)]
+    path: PathBuf,
+    /// Completed inputs, by canonical path, with their size in bytes.
+    completed: BTreeMap<PathBuf, u64>,
+}
+
+impl BatchJournal {
+    /// Creates an empty journal that is saved to `path`.
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            completed: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the journal at `path`, or returns an empty one if there is no
+    /// journal yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new(path)),
+            Err(e) => return Err(e.into()),
+        };
+        let mut journal: Self = serde_json::from_str(&contents)?;
+        journal.path = path.to_path_buf();
+        Ok(journal)
+    }
+
+    /// Returns the path the journal is saved to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if `input` completed in an earlier run and has not
+    /// changed size since.
+    pub fn is_completed(&self, input: &Path) -> bool {
+        let Some(size) = std::fs::metadata(input).ok().map(|m| m.len()) else {
+            return false;
+        };
+        self.completed.get(&journal_key(input)) == Some(&size)
+    }
+
+    /// Records `input` as completed and saves the journal.
+    ///
+    /// An input that no longer exists (deleted after splitting) is not
+    /// recorded, since a resumed batch has nothing to skip.
+    pub fn mark_completed(&mut self, input: &Path) -> Result<()> {
+        let Ok(metadata) = std::fs::metadata(input) else {
+            return Ok(());
+        };
+        self.completed.insert(journal_key(input), metadata.len());
+        self.save()
+    }
+
+    /// Writes the journal to its path, replacing it atomically.
+    pub fn save(&self) -> Result<()> {
+        let partial = self.path.with_extension("json.partial");
+        std::fs::write(&partial, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&partial, &self.path)?;
+        Ok(())
+    }
+
+    /// Deletes the journal file, e.g. once the whole batch has succeeded.
+    pub fn remove(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the key `input` is recorded under, so the same file matches
+/// whichever way its path was written.
+fn journal_key(input: &Path) -> PathBuf {
+    std::fs::canonicalize(input).unwrap_or_else(|_| input.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_round_trip() {
+        let dir = std::env::temp_dir().join(format!("obs-cutter-journal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("recording.mkv");
+        let other = dir.join("other.mkv");
+        std::fs::write(&input, b"video").unwrap();
+        std::fs::write(&other, b"video").unwrap();
+        let path = dir.join(JOURNAL_FILE_NAME);
+
+        let mut journal = BatchJournal::load(&path).unwrap();
+        assert!(!journal.is_completed(&input));
+        journal.mark_completed(&input).unwrap();
+
+        // A relative spelling of the same path still matches
+        let journal = BatchJournal::load(&path).unwrap();
+        assert!(journal.is_completed(&dir.join(".").join("recording.mkv")));
+        assert!(!journal.is_completed(&other));
+
+        // A replaced input is encoded again
+        std::fs::write(&input, b"a longer video").unwrap();
+        assert!(!journal.is_completed(&input));
+
+        journal.remove().unwrap();
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
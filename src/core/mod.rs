@@ -4,21 +4,56 @@
 //! encoder detection, and configuration that is used by both
 //! the CLI and GUI interfaces.
 
+pub mod benchmark;
 pub mod config;
+pub mod datetime;
+pub mod disk;
+pub mod doctor;
 pub mod encoder;
 pub mod error;
 pub mod ffmpeg;
+pub mod format;
+pub mod input;
+pub mod journal;
 pub mod progress;
+pub mod scenes;
 pub mod video;
 
 // Re-export commonly used types
-pub use config::{ProcessingConfig, Quality, Side};
-pub use encoder::{detect_hardware_encoder, get_codec_args, HardwareEncoder};
+pub use benchmark::benchmark;
+pub use config::{
+    parse_dimensions, parse_fps, parse_scene_threshold, parse_timestamp, AudioMode, ColorRange,
+    CropRect, FitMode, JoinLayout, ProcessingConfig, Quality, ResolvedTrim, Side, Trim, TrimEnd,
+};
+pub use datetime::DateTime;
+pub use disk::{available_space, check_disk_space};
+pub use doctor::test_encode;
+pub use encoder::{
+    apply_preset, detect_hardware_encoder, encoder_env_error, get_bitrate_args, get_codec_args,
+    is_codec_compatible, probe_all_encoders, HardwareEncoder, ENCODER_ENV, FORCE_SOFTWARE_ENV,
+};
 pub use error::{ObsCutterError, Result};
-pub use ffmpeg::{check_ffmpeg, check_ffprobe, get_ffmpeg_path, get_ffprobe_path};
-pub use progress::{EncodingProgress, FfmpegProgressParser};
+pub use ffmpeg::{
+    check_ffmpeg, check_ffprobe, get_ffmpeg_path, get_ffmpeg_version_parsed, get_ffprobe_path,
+    has_filter, humanize_ffmpeg_error, supports_feature, FfmpegFeature, FfmpegVersion,
+};
+pub use format::{format_duration, format_file_size};
+pub use input::{
+    is_supported_extension, read_input_list, wait_for_stable, STABLE_INTERVAL, SUPPORTED_EXTENSIONS,
+};
+pub use journal::{BatchJournal, JOURNAL_FILE_NAME};
+pub use progress::{EncodingProgress, FfmpegProgressParser, MachineProgressParser};
+pub use scenes::{detect_scene_changes, scene_segments, segment_output_path};
 pub use video::{
-    format_duration, format_file_size, get_video_duration, get_video_info, process_video,
-    process_video_side, process_video_side_with_progress, ProcessingProgress, ProcessingResult,
-    VideoInfo,
+    batch_output_dir, build_side_args, detect_black_bars, encode_with_container_fallback,
+    estimate_output_size, expand_output_dir, get_creation_time, get_input_info, get_video_duration,
+    get_video_info, get_video_stream_info, is_media_file, is_side_output_name, is_stdout_output,
+    join_videos, join_videos_with_layout, output_dir_root, output_part_number, process_side,
+    process_side_with_progress, process_video_with_config, process_video_with_progress, remux,
+    resolve_output_dir, side_output_files, side_output_path, split_preview, verify_side_output,
+    AudioTrack, ColorInfo, ContainerFallback, ProcessingProgress, ProcessingResult, Retry,
+    SideProgress, VideoInfo,
 };
+// Positional versions kept for existing callers
+#[allow(deprecated)]
+pub use video::{process_video, process_video_side, process_video_side_with_progress};
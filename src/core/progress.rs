@@ -1,10 +1,12 @@
 //! FFmpeg progress parsing for real-time encoding updates.
 
 use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::sync::LazyLock;
 
 /// Real-time encoding progress from FFmpeg.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct EncodingProgress {
     /// Current position in the video (seconds).
     pub current_time_secs: f64,
@@ -16,21 +18,43 @@ pub struct EncodingProgress {
     pub fps: f64,
     /// Speed multiplier (e.g., 1.5 means 1.5x realtime).
     pub speed: f64,
+    /// Speed averaged over recent progress lines, used for the ETA.
+    pub smoothed_speed: f64,
     /// Encoding progress as percentage (0.0 - 100.0).
     pub percentage: f32,
 }
 
 impl EncodingProgress {
     /// Calculate estimated time remaining in seconds.
+    ///
+    /// Uses the smoothed speed when available, so the estimate does not jump
+    /// around with FFmpeg's instantaneous speed.
     pub fn eta_secs(&self) -> Option<f64> {
-        if self.speed <= 0.0 || self.total_duration_secs <= 0.0 {
+        let speed = if self.smoothed_speed > 0.0 {
+            self.smoothed_speed
+        } else {
+            self.speed
+        };
+        if speed <= 0.0 || self.total_duration_secs <= 0.0 {
             return None;
         }
         let remaining_secs = self.total_duration_secs - self.current_time_secs;
         if remaining_secs <= 0.0 {
             return Some(0.0);
         }
-        Some(remaining_secs / self.speed)
+        Some(remaining_secs / speed)
+    }
+
+    /// Returns this progress as of a finished encode: at 100%, with the
+    /// current position at the end of the video.
+    pub fn completed(mut self) -> Self {
+        if self.total_duration_secs > 0.0 {
+            self.current_time_secs = self.total_duration_secs;
+        } else {
+            self.total_duration_secs = self.current_time_secs;
+        }
+        self.percentage = 100.0;
+        self
     }
 
     /// Format ETA as human-readable string.
@@ -131,10 +155,14 @@ pub fn parse_progress_line(line: &str, total_duration: f64) -> Option<EncodingPr
         current_frame: frame,
         fps,
         speed,
+        smoothed_speed: speed,
         percentage,
     })
 }
 
+/// Number of recent speed samples averaged for the smoothed speed.
+const SPEED_WINDOW: usize = 10;
+
 /// FFmpeg progress parser that maintains state across lines.
 #[derive(Debug, Default)]
 pub struct FfmpegProgressParser {
@@ -142,6 +170,8 @@ pub struct FfmpegProgressParser {
     pub total_duration: f64,
     /// Whether we've found the duration line.
     pub duration_found: bool,
+    /// Recent speed samples, for the smoothed speed.
+    speed_window: SpeedWindow,
 }
 
 impl FfmpegProgressParser {
@@ -155,6 +185,7 @@ impl FfmpegProgressParser {
         Self {
             total_duration: duration_secs,
             duration_found: true,
+            ..Self::default()
         }
     }
 
@@ -169,8 +200,106 @@ impl FfmpegProgressParser {
             }
         }
 
-        // Try to parse as a progress line
-        parse_progress_line(line, self.total_duration)
+        // Only real progress lines carry frame/fps counters; this avoids matching
+        // "time=" inside echoed paths or error messages
+        if !line.contains("frame=") && !line.contains("fps=") {
+            return None;
+        }
+
+        let mut progress = parse_progress_line(line, self.total_duration)?;
+        progress.smoothed_speed = self.speed_window.record(progress.speed);
+        Some(progress)
+    }
+}
+
+/// Most recent non-zero speed samples, oldest first.
+#[derive(Debug, Default)]
+struct SpeedWindow {
+    samples: VecDeque<f64>,
+}
+
+impl SpeedWindow {
+    /// Records a speed sample and returns the average over the window.
+    ///
+    /// Zero speeds (FFmpeg reports "N/A" before it has a measurement) are
+    /// not recorded.
+    fn record(&mut self, speed: f64) -> f64 {
+        if speed > 0.0 {
+            if self.samples.len() == SPEED_WINDOW {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(speed);
+        }
+
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f64>() / self.samples.len() as f64
+    }
+}
+
+/// Parser for FFmpeg's machine-readable `-progress` output.
+///
+/// With `-progress pipe:1 -nostats`, FFmpeg writes blocks of `key=value`
+/// lines, each ending with `progress=continue` (or `progress=end` for the
+/// last one). Unlike the stderr status line this format does not depend on
+/// the FFmpeg version or locale, but it carries no input duration, so the
+/// duration has to be known up front.
+#[derive(Debug, Default)]
+pub struct MachineProgressParser {
+    /// Total duration of the input video.
+    pub total_duration: f64,
+    /// Values of the block being read.
+    current: EncodingProgress,
+    /// Recent speed samples, for the smoothed speed.
+    speed_window: SpeedWindow,
+}
+
+impl MachineProgressParser {
+    /// Create a parser for an encode of the given duration.
+    pub fn with_duration(duration_secs: f64) -> Self {
+        Self {
+            total_duration: duration_secs,
+            ..Self::default()
+        }
+    }
+
+    /// Parse one `key=value` line of `-progress` output.
+    /// Returns Some(EncodingProgress) when a block is complete.
+    pub fn parse_line(&mut self, line: &str) -> Option<EncodingProgress> {
+        let (key, value) = line.trim().split_once('=')?;
+        let value = value.trim();
+
+        match key {
+            "frame" => self.current.current_frame = value.parse().unwrap_or(0),
+            "fps" => self.current.fps = value.parse().unwrap_or(0.0),
+            // Despite its name, out_time_ms is in microseconds as well
+            "out_time_us" | "out_time_ms" => {
+                if let Ok(micros) = value.parse::<i64>() {
+                    self.current.current_time_secs = micros.max(0) as f64 / 1_000_000.0;
+                }
+            }
+            // "N/A" until FFmpeg has a measurement
+            "speed" => {
+                self.current.speed = value.trim_end_matches('x').parse().unwrap_or(0.0);
+            }
+            "progress" => return Some(self.finish_block()),
+            _ => {}
+        }
+        None
+    }
+
+    /// Completes the current block and returns it as progress.
+    fn finish_block(&mut self) -> EncodingProgress {
+        let mut progress = std::mem::take(&mut self.current);
+        progress.total_duration_secs = self.total_duration;
+        progress.percentage = if self.total_duration > 0.0 {
+            ((progress.current_time_secs / self.total_duration) * 100.0).min(100.0) as f32
+        } else {
+            0.0
+        };
+        progress.smoothed_speed = self.speed_window.record(progress.speed);
+        progress
     }
 }
 
@@ -215,6 +344,137 @@ mod tests {
         assert!((progress.percentage - 33.33).abs() < 0.1); // 30 / 90 * 100
     }
 
+    #[test]
+    fn test_parser_ignores_time_in_paths() {
+        let mut parser = FfmpegProgressParser::with_duration(90.0);
+
+        let input_line =
+            "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from '/recordings/clip time=00:00:45.00 café.mov':";
+        assert!(parser.parse_line(input_line).is_none());
+
+        let error_line = "/recordings/time=00:01:00.00 ñ.mov: No such file or directory";
+        assert!(parser.parse_line(error_line).is_none());
+
+        let progress_line = "frame=  500 fps= 30.0 size=   1024kB time=00:00:30.00 speed=2.00x";
+        assert!(parser.parse_line(progress_line).is_some());
+    }
+
+    #[test]
+    fn test_smoothed_speed_converges() {
+        let mut parser = FfmpegProgressParser::with_duration(600.0);
+        let line = |secs: u32, speed: f64| {
+            format!(
+                "frame= {} fps= 30.0 time=00:00:{:02}.00 speed={:.2}x",
+                secs * 30,
+                secs,
+                speed
+            )
+        };
+
+        // Wildly varying speeds at the start are damped
+        let mut smoothed = Vec::new();
+        for (secs, speed) in [(1, 8.0), (2, 0.5), (3, 4.0), (4, 1.0)] {
+            smoothed.push(
+                parser
+                    .parse_line(&line(secs, speed))
+                    .unwrap()
+                    .smoothed_speed,
+            );
+        }
+        assert!((smoothed[1] - 4.25).abs() < 0.01);
+        assert!(smoothed[3] < 8.0 && smoothed[3] > 0.5);
+
+        // Once the window is filled with a steady speed, the average matches it
+        let mut last = None;
+        for secs in 5..5 + SPEED_WINDOW as u32 {
+            last = parser.parse_line(&line(secs, 2.0));
+        }
+        let last = last.unwrap();
+        assert!((last.smoothed_speed - 2.0).abs() < 0.001);
+        assert!((last.eta_secs().unwrap() - (600.0 - 14.0) / 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_machine_progress_parser() {
+        // Captured from `ffmpeg -progress pipe:1 -nostats` (FFmpeg 6.1)
+        let output = "frame=0\n\
+                      fps=0.00\n\
+                      stream_0_0_q=0.0\n\
+                      bitrate=N/A\n\
+                      total_size=48\n\
+                      out_time_us=N/A\n\
+                      out_time_ms=N/A\n\
+                      out_time=N/A\n\
+                      dup_frames=0\n\
+                      drop_frames=0\n\
+                      speed=N/A\n\
+                      progress=continue\n\
+                      frame=1350\n\
+                      fps=89.97\n\
+                      stream_0_0_q=28.0\n\
+                      bitrate=3265.5kbits/s\n\
+                      total_size=18671616\n\
+                      out_time_us=45670000\n\
+                      out_time_ms=45670000\n\
+                      out_time=00:00:45.670000\n\
+                      dup_frames=0\n\
+                      drop_frames=0\n\
+                      speed=3.04x\n\
+                      progress=continue\n\
+                      frame=2700\n\
+                      fps=90.01\n\
+                      out_time_us=90000000\n\
+                      speed=3.01x\n\
+                      progress=end\n";
+
+        let mut parser = MachineProgressParser::with_duration(90.0);
+        let updates: Vec<_> = output
+            .lines()
+            .filter_map(|line| parser.parse_line(line))
+            .collect();
+        assert_eq!(updates.len(), 3);
+
+        // Values FFmpeg reports as N/A read as zero
+        assert_eq!(updates[0].current_frame, 0);
+        assert_eq!(updates[0].speed, 0.0);
+        assert_eq!(updates[0].percentage, 0.0);
+
+        let middle = &updates[1];
+        assert_eq!(middle.current_frame, 1350);
+        assert!((middle.fps - 89.97).abs() < 0.001);
+        assert!((middle.current_time_secs - 45.67).abs() < 0.001);
+        assert!((middle.speed - 3.04).abs() < 0.001);
+        assert!((middle.percentage - 50.74).abs() < 0.01);
+        assert!((middle.eta_secs().unwrap() - 44.33 / 3.04).abs() < 0.01);
+
+        let last = &updates[2];
+        assert_eq!(last.percentage, 100.0);
+        assert!((last.smoothed_speed - 3.025).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_completed_progress() {
+        // Stalled just short of a slightly overestimated duration
+        let stalled = EncodingProgress {
+            current_time_secs: 59.4,
+            total_duration_secs: 60.0,
+            speed: 2.0,
+            percentage: 99.0,
+            ..EncodingProgress::default()
+        };
+        let done = stalled.completed();
+        assert_eq!(done.percentage, 100.0);
+        assert_eq!(done.current_time_secs, 60.0);
+        assert_eq!(done.eta_secs(), Some(0.0));
+
+        // Without a known duration, the position reached is the duration
+        let unknown = EncodingProgress {
+            current_time_secs: 42.0,
+            ..EncodingProgress::default()
+        };
+        assert_eq!(unknown.completed().total_duration_secs, 42.0);
+    }
+
     #[test]
     fn test_eta_calculation() {
         let progress = EncodingProgress {
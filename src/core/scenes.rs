@@ -0,0 +1,175 @@
+//! Scene change detection, for cutting recordings into segments.
+
+use crate::core::config::{ProcessingConfig, Trim};
+use crate::core::error::{ObsCutterError, Result};
+use crate::core::ffmpeg;
+use crate::core::video::{ffmpeg_command, wait_for_exit};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Segments shorter than this are merged into the previous one, so that a
+/// burst of cuts (e.g. a flashing overlay) doesn't produce tiny clips.
+const MIN_SEGMENT_SECS: f64 = 1.0;
+
+/// Returns the positions (in seconds) where the scene changes by more than
+/// `threshold`, from 0.0 (any change) to 1.0 (a completely different frame).
+///
+/// The whole input is decoded, using FFmpeg's `select` filter to score each
+/// frame against the previous one. Scores are computed on the full frame, so
+/// a cut on either side counts.
+pub fn detect_scene_changes(
+    input: &Path,
+    threshold: f64,
+    config: &ProcessingConfig,
+) -> Result<Vec<f64>> {
+    let mut child = ffmpeg_command(config)
+        .args(["-hide_banner", "-nostats"])
+        .args(config.input_format.iter().flat_map(|f| ["-f", f.as_str()]))
+        .arg("-i")
+        .arg(input)
+        .args(["-map", &format!("0:v:{}", config.video_stream.unwrap_or(0))])
+        .args(["-vf", &scene_filter(threshold), "-an", "-f", "null", "-"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(ffmpeg::ffmpeg_spawn_error)?;
+
+    // showinfo logs one line per selected frame; keep the last other line
+    // for the error message
+    let stderr = child.stderr.take().map(|stderr| {
+        std::thread::spawn(move || {
+            let mut cuts = Vec::new();
+            let mut last_line = String::new();
+            for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+                match parse_showinfo_time(&line) {
+                    Some(time) => cuts.push(time),
+                    None => last_line = line,
+                }
+            }
+            (cuts, last_line)
+        })
+    });
+
+    let status = wait_for_exit(&mut child, config)?;
+    let (cuts, last_line) = stderr
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
+
+    if !status.success() {
+        return Err(ObsCutterError::VideoAnalysisFailed(format!(
+            "Scene detection failed: {}",
+            last_line.trim()
+        )));
+    }
+    Ok(cuts)
+}
+
+/// Builds the filter that logs the frames starting a new scene.
+fn scene_filter(threshold: f64) -> String {
+    format!("select='gt(scene,{})',showinfo", threshold)
+}
+
+/// Extracts the timestamp from a showinfo line such as
+/// `[Parsed_showinfo_1 @ 0x...] n:   0 pts: 12800 pts_time:12.8 ...`.
+fn parse_showinfo_time(line: &str) -> Option<f64> {
+    if !line.contains("Parsed_showinfo") {
+        return None;
+    }
+    let (_, rest) = line.split_once("pts_time:")?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Turns scene cuts into the trim ranges of consecutive segments covering
+/// the whole input.
+///
+/// Cuts closer than [`MIN_SEGMENT_SECS`] to the previous cut, the start or
+/// (when `total_duration` is known) the end are dropped. The last segment
+/// runs to the end of the input.
+pub fn scene_segments(cuts: &[f64], total_duration: Option<f64>) -> Vec<Trim> {
+    let mut starts = vec![0.0];
+    for &cut in cuts {
+        let after_previous = starts
+            .last()
+            .is_some_and(|&start| cut - start >= MIN_SEGMENT_SECS);
+        let before_end = total_duration.is_none_or(|total| total - cut >= MIN_SEGMENT_SECS);
+        if after_previous && before_end {
+            starts.push(cut);
+        }
+    }
+
+    let ends = starts.iter().skip(1).map(|&end| Some(end)).chain([None]);
+    starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| Trim {
+            start: Some(start).filter(|&s| s > 0.0),
+            end: None,
+            duration: end.map(|end| end - start),
+        })
+        .collect()
+}
+
+/// Returns the path of the `index`th segment (counting from 1) of a side
+/// output, e.g. `clip-left-003.mp4` for `clip-left.mp4`.
+pub fn segment_output_path(output: &Path, index: usize) -> PathBuf {
+    let mut name = output.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!("-{:03}", index));
+    if let Some(extension) = output.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    output.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_showinfo_time() {
+        let line = "[Parsed_showinfo_1 @ 0x5581] n:   2 pts: 153600 pts_time:12.5    duration:512";
+        assert_eq!(parse_showinfo_time(line), Some(12.5));
+        assert_eq!(
+            parse_showinfo_time("[Parsed_showinfo_1 @ 0x5581] config in time_base: 1/12800"),
+            None
+        );
+        assert_eq!(parse_showinfo_time("frame=  100 pts_time:4.0"), None);
+    }
+
+    #[test]
+    fn test_scene_segments() {
+        let segments = scene_segments(&[0.2, 10.0, 10.5, 30.0, 59.5], Some(60.0));
+        assert_eq!(
+            segments,
+            vec![
+                Trim {
+                    start: None,
+                    end: None,
+                    duration: Some(10.0),
+                },
+                Trim {
+                    start: Some(10.0),
+                    end: None,
+                    duration: Some(20.0),
+                },
+                Trim {
+                    start: Some(30.0),
+                    end: None,
+                    duration: None,
+                },
+            ]
+        );
+
+        // Without cuts the whole input is one segment
+        assert_eq!(scene_segments(&[], None), vec![Trim::default()]);
+    }
+
+    #[test]
+    fn test_segment_output_path() {
+        assert_eq!(
+            segment_output_path(Path::new("out/clip-left.mp4"), 3),
+            PathBuf::from("out/clip-left-003.mp4")
+        );
+    }
+}
@@ -1,12 +1,21 @@
 //! Video processing and analysis.
 
-use crate::core::config::{Quality, Side};
-use crate::core::encoder::{get_codec_args, HardwareEncoder};
+use crate::core::config::{
+    AudioMode, ColorRange, CropRect, JoinLayout, ProcessingConfig, Quality, ResolvedTrim, Side,
+    Trim,
+};
+use crate::core::datetime::DateTime;
+use crate::core::encoder::{
+    apply_preset, get_bitrate_args, get_codec_args, is_codec_compatible, HardwareEncoder,
+};
 use crate::core::error::{ObsCutterError, Result};
-use crate::core::ffmpeg;
-use crate::core::progress::{EncodingProgress, FfmpegProgressParser};
+use crate::core::ffmpeg::{self, FfmpegFeature};
+use crate::core::progress::{EncodingProgress, FfmpegProgressParser, MachineProgressParser};
+use crate::core::scenes;
 use serde::Deserialize;
-use std::io::{BufRead, BufReader};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::Duration;
@@ -21,12 +30,72 @@ pub struct StreamInfo {
     pub codec_name: String,
     #[serde(default)]
     pub codec_type: Option<String>,
+    #[serde(default)]
+    pub avg_frame_rate: Option<String>,
+    #[serde(default)]
+    pub pix_fmt: Option<String>,
+    #[serde(default)]
+    pub color_primaries: Option<String>,
+    #[serde(default)]
+    pub color_transfer: Option<String>,
+    #[serde(default)]
+    pub color_space: Option<String>,
+    #[serde(default)]
+    pub color_range: Option<String>,
+    #[serde(default)]
+    pub bit_rate: Option<String>,
+    #[serde(default)]
+    pub side_data_list: Vec<StreamSideData>,
+    #[serde(default)]
+    pub tags: StreamTags,
+}
+
+/// Per-stream side data from FFprobe, such as the display matrix.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamSideData {
+    #[serde(default)]
+    pub rotation: Option<i32>,
+}
+
+/// Per-stream tags from FFprobe.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StreamTags {
+    /// Legacy rotation tag written by older muxers.
+    #[serde(default)]
+    pub rotate: Option<String>,
+    /// Language of the stream (e.g. "eng"), usually an ISO 639-2 code.
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl StreamInfo {
+    /// Returns the stream's rotation in degrees (0-359), or None if it is
+    /// not rotated. The display matrix takes precedence over the legacy tag.
+    fn rotation(&self) -> Option<i32> {
+        self.side_data_list
+            .iter()
+            .find_map(|data| data.rotation)
+            .or_else(|| self.tags.rotate.as_deref()?.trim().parse().ok())
+            .map(|degrees: i32| degrees.rem_euclid(360))
+            .filter(|degrees| *degrees != 0)
+    }
+}
+
+/// Container-level information from FFprobe.
+#[derive(Debug, Default, Deserialize)]
+struct FormatInfo {
+    #[serde(default)]
+    duration: Option<String>,
+    #[serde(default)]
+    bit_rate: Option<String>,
 }
 
 /// FFprobe output structure.
 #[derive(Debug, Deserialize)]
 struct ProbeOutput {
     streams: Vec<StreamInfo>,
+    #[serde(default)]
+    format: FormatInfo,
 }
 
 /// Video file information.
@@ -42,12 +111,108 @@ pub struct VideoInfo {
     pub codec: String,
     /// File size in bytes (if available).
     pub file_size: Option<u64>,
+    /// Duration in seconds (if available).
+    pub duration: Option<f64>,
+    /// Average frame rate (if available).
+    pub fps: Option<f64>,
+    /// Pixel format and color metadata.
+    pub color: ColorInfo,
+    /// Rotation in degrees (0-359) from the stream's rotation metadata, if
+    /// any. FFmpeg applies it when decoding.
+    pub rotation: Option<i32>,
+    /// Codec of the first audio stream (e.g., "aac"), if there is one.
+    pub audio_codec: Option<String>,
+    /// All audio streams, in stream order.
+    pub audio_tracks: Vec<AudioTrack>,
+    /// Bitrate in bits per second of the video stream, or of the whole file
+    /// when the container doesn't record per-stream bitrates (e.g. MKV).
+    pub bitrate: Option<u64>,
+}
+
+/// An audio stream of a video file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioTrack {
+    /// Codec name (e.g., "aac").
+    pub codec: String,
+    /// Language tag (e.g., "eng"), if the stream has one.
+    pub language: Option<String>,
+}
+
+/// Pixel format and color metadata of a video stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColorInfo {
+    /// Pixel format (e.g., "yuv420p", "p010le").
+    pub pix_fmt: Option<String>,
+    /// Color primaries (e.g., "bt2020").
+    pub primaries: Option<String>,
+    /// Transfer characteristics (e.g., "smpte2084" for HDR10).
+    pub transfer: Option<String>,
+    /// Color space / matrix coefficients (e.g., "bt2020nc").
+    pub space: Option<String>,
+    /// Value range as reported by FFprobe ("pc" or "tv").
+    pub range: Option<String>,
+}
+
+impl ColorInfo {
+    /// Returns true if the pixel format carries 10 bits per component,
+    /// as HDR captures do.
+    pub fn is_ten_bit(&self) -> bool {
+        self.pix_fmt
+            .as_deref()
+            .is_some_and(|f| f.starts_with("p010") || f.contains("p10"))
+    }
+
+    /// Returns the source's value range, if FFprobe reported one.
+    pub fn color_range(&self) -> Option<ColorRange> {
+        self.range
+            .as_deref()
+            .filter(|r| *r != "unknown")
+            .and_then(|r| r.parse().ok())
+    }
+
+    /// Returns FFmpeg output arguments that copy the source's color
+    /// metadata, skipping values FFprobe reports as unknown. The range is
+    /// not included, since the output range can be overridden.
+    pub fn metadata_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for (flag, value) in [
+            ("-color_primaries", &self.primaries),
+            ("-color_trc", &self.transfer),
+            ("-colorspace", &self.space),
+        ] {
+            if let Some(value) = value.as_deref().filter(|v| *v != "unknown") {
+                args.push(flag.to_string());
+                args.push(value.to_string());
+            }
+        }
+        args
+    }
 }
 
 impl VideoInfo {
-    /// Returns true if the video has the expected 32:9 dimensions (3840x1080).
+    /// Returns true if the video displays at the expected 32:9 dimensions (3840x1080).
     pub fn is_valid_dimensions(&self) -> bool {
-        self.width == 3840 && self.height == 1080
+        self.display_size() == (3840, 1080)
+    }
+
+    /// Returns the first audio track tagged with `language`, compared
+    /// case-insensitively.
+    pub fn audio_track(&self, language: &str) -> Option<&AudioTrack> {
+        self.audio_tracks.iter().find(|track| {
+            track
+                .language
+                .as_deref()
+                .is_some_and(|l| l.eq_ignore_ascii_case(language))
+        })
+    }
+
+    /// Returns the size frames have after FFmpeg applies the rotation, which
+    /// is what the crop filter sees. Quarter turns swap width and height.
+    pub fn display_size(&self) -> (u32, u32) {
+        match self.rotation {
+            Some(90 | 270) => (self.height, self.width),
+            _ => (self.width, self.height),
+        }
     }
 
     /// Returns the aspect ratio as a string.
@@ -57,6 +222,24 @@ impl VideoInfo {
     }
 }
 
+/// Parses an FFprobe frame rate such as "30000/1001" or "60".
+///
+/// Returns None for the "0/0" FFprobe reports when the rate is unknown.
+fn parse_frame_rate(rate: &str) -> Option<f64> {
+    let fps = match rate.trim().split_once('/') {
+        Some((num, den)) => {
+            let den: f64 = den.parse().ok()?;
+            if den == 0.0 {
+                return None;
+            }
+            num.parse::<f64>().ok()? / den
+        }
+        None => rate.trim().parse().ok()?,
+    };
+
+    (fps > 0.0).then_some(fps)
+}
+
 /// Calculates the greatest common divisor.
 fn gcd(a: u32, b: u32) -> u32 {
     if b == 0 {
@@ -75,14 +258,56 @@ pub struct ProcessingResult {
     pub left_output: PathBuf,
     /// Right output video path.
     pub right_output: PathBuf,
+    /// Every output, left side first, when the sides were cut into scene
+    /// segments or chunks (`left_output` and `right_output` are then the
+    /// first ones). Empty otherwise.
+    pub segments: Vec<PathBuf>,
     /// Left output file size in bytes.
     pub left_size: u64,
     /// Right output file size in bytes.
     pub right_size: u64,
     /// Processing duration.
     pub duration: Duration,
-    /// Encoder used for processing.
+    /// Time spent encoding the left side (all of its segments or chunks).
+    pub left_duration: Duration,
+    /// Time spent encoding the right side (all of its segments or chunks).
+    pub right_duration: Duration,
+    /// Encoder actually used for processing (software if a fallback occurred).
     pub encoder_used: HardwareEncoder,
+    /// Outputs written as MKV because their container couldn't hold the
+    /// streams (see [`ProcessingConfig::container_fallback`]).
+    pub container_fallbacks: Vec<ContainerFallback>,
+}
+
+/// An output written as MKV instead of its requested container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerFallback {
+    /// Side the output belongs to.
+    pub side: Side,
+    /// The requested output, which was not written.
+    pub requested: PathBuf,
+    /// The MKV output written instead.
+    pub output: PathBuf,
+    /// Why the requested container failed.
+    pub reason: String,
+}
+
+impl ProcessingResult {
+    /// Returns the combined size of both outputs in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.left_size + self.right_size
+    }
+
+    /// Returns the combined output size relative to `input_size`.
+    ///
+    /// A ratio of 1.8 means the outputs take 1.8× the space of the original;
+    /// below 1.0 space was saved. Returns 0.0 if `input_size` is zero.
+    pub fn compression_ratio(&self, input_size: u64) -> f32 {
+        if input_size == 0 {
+            return 0.0;
+        }
+        (self.total_size() as f64 / input_size as f64) as f32
+    }
 }
 
 /// Progress information during video processing.
@@ -101,6 +326,20 @@ pub enum ProcessingProgress {
         side: Side,
         path: PathBuf,
     },
+    /// FFmpeg progress while a side is encoded.
+    Encoding {
+        video_index: usize,
+        total: usize,
+        side: Side,
+        progress: EncodingProgress,
+    },
+    /// A side's encode failed and is about to be retried.
+    Retrying {
+        video_index: usize,
+        total: usize,
+        side: Side,
+        retry: Retry,
+    },
     /// A video has been completed.
     Completed {
         video_index: usize,
@@ -114,6 +353,34 @@ pub enum ProcessingProgress {
         path: PathBuf,
         error: String,
     },
+    /// A video was not processed because its outputs already exist.
+    Skipped {
+        video_index: usize,
+        total: usize,
+        path: PathBuf,
+    },
+}
+
+/// Progress of a single side, reported by
+/// [`process_side_with_progress`].
+#[derive(Debug, Clone)]
+pub enum SideProgress {
+    /// FFmpeg progress while the side is encoded.
+    Encoding(EncodingProgress),
+    /// An encode failed and is about to be retried (see
+    /// [`ProcessingConfig::retry`]).
+    Retrying(Retry),
+}
+
+/// A failed encode attempt that is about to be retried.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Retry {
+    /// Number of the attempt that failed, counting from 1.
+    pub attempt: u8,
+    /// Number of attempts allowed in total.
+    pub attempts: u16,
+    /// The last line of FFmpeg's error output.
+    pub error: String,
 }
 
 /// Trait for receiving progress updates during processing.
@@ -121,18 +388,73 @@ pub trait ProgressCallback: Send + Sync {
     fn on_progress(&self, progress: ProcessingProgress);
 }
 
+/// Returns true if FFprobe recognizes `path` as a media container.
+///
+/// A quick check to run before [`get_video_info`], so that e.g. a text file
+/// renamed to `.mp4` is reported plainly instead of as a probe failure.
+pub fn is_media_file(path: &Path) -> bool {
+    let output = Command::new(ffmpeg::get_ffprobe_path())
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=format_name",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ])
+        .arg(path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+
+    output.is_ok_and(|output| {
+        output.status.success() && is_media_format(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+/// Checks a format name detected by FFprobe. Plain text can be picked up
+/// by the `tty` demuxer, which does not make it a video.
+fn is_media_format(format_name: &str) -> bool {
+    let format_name = format_name.trim();
+    !format_name.is_empty() && format_name != "tty"
+}
+
 /// Gets video information using FFprobe.
 pub fn get_video_info(video_path: &Path) -> Result<VideoInfo> {
+    get_video_stream_info(video_path, None)
+}
+
+/// Gets video information like [`get_video_info`], describing the video
+/// stream at `stream` (counting video streams only, from 0) if given.
+pub fn get_video_stream_info(video_path: &Path, stream: Option<usize>) -> Result<VideoInfo> {
+    probe_video_info(video_path, stream, None)
+}
+
+/// Gets video information about an input the way `config` reads it: the
+/// stream `config.video_stream` selects, probed with the demuxer
+/// `config.input_format` forces.
+pub fn get_input_info(input: &Path, config: &ProcessingConfig) -> Result<VideoInfo> {
+    probe_video_info(input, config.video_stream, config.input_format.as_deref())
+}
+
+/// Runs FFprobe on `video_path` for [`get_video_stream_info`], with
+/// `format` as the demuxer if given.
+fn probe_video_info(
+    video_path: &Path,
+    stream: Option<usize>,
+    format: Option<&str>,
+) -> Result<VideoInfo> {
     let ffprobe_path = ffmpeg::get_ffprobe_path();
 
     let output = Command::new(ffprobe_path)
+        .args(format.iter().flat_map(|f| ["-f", f]))
         .args([
             "-v",
             "error",
-            "-select_streams",
-            "v:0",
             "-show_entries",
-            "stream=width,height,codec_name,codec_type",
+            "stream=width,height,codec_name,codec_type,avg_frame_rate,pix_fmt,\
+             color_primaries,color_transfer,color_space,color_range,bit_rate:\
+             stream_side_data=rotation:stream_tags=rotate,language:format=duration,bit_rate",
             "-of",
             "json",
         ])
@@ -148,13 +470,7 @@ pub fn get_video_info(video_path: &Path) -> Result<VideoInfo> {
 
     let probe_output: ProbeOutput = serde_json::from_slice(&output.stdout)?;
 
-    let stream = probe_output
-        .streams
-        .iter()
-        .find(|s| {
-            s.codec_type.as_deref() == Some("video") && s.width.is_some() && s.height.is_some()
-        })
-        .ok_or(ObsCutterError::NoVideoStream)?;
+    let stream = select_video_stream(&probe_output.streams, stream)?;
 
     let width = stream.width.ok_or(ObsCutterError::NoVideoStream)?;
     let height = stream.height.ok_or(ObsCutterError::NoVideoStream)?;
@@ -162,246 +478,3978 @@ pub fn get_video_info(video_path: &Path) -> Result<VideoInfo> {
     // Get file size
     let file_size = std::fs::metadata(video_path).ok().map(|m| m.len());
 
+    let duration = probe_output
+        .format
+        .duration
+        .as_deref()
+        .and_then(|d| d.trim().parse::<f64>().ok());
+
+    let fps = stream.avg_frame_rate.as_deref().and_then(parse_frame_rate);
+
+    let bitrate = [&stream.bit_rate, &probe_output.format.bit_rate]
+        .into_iter()
+        .find_map(|rate| rate.as_deref()?.trim().parse::<u64>().ok());
+
     Ok(VideoInfo {
         path: video_path.to_path_buf(),
         width,
         height,
         codec: stream.codec_name.clone(),
         file_size,
+        duration,
+        fps,
+        color: ColorInfo {
+            pix_fmt: stream.pix_fmt.clone(),
+            primaries: stream.color_primaries.clone(),
+            transfer: stream.color_transfer.clone(),
+            space: stream.color_space.clone(),
+            range: stream.color_range.clone(),
+        },
+        rotation: stream.rotation(),
+        audio_codec: probe_output
+            .streams
+            .iter()
+            .find(|s| s.codec_type.as_deref() == Some("audio"))
+            .map(|s| s.codec_name.clone()),
+        audio_tracks: probe_output
+            .streams
+            .iter()
+            .filter(|s| s.codec_type.as_deref() == Some("audio"))
+            .map(|s| AudioTrack {
+                codec: s.codec_name.clone(),
+                language: s.tags.language.clone(),
+            })
+            .collect(),
+        bitrate,
     })
 }
 
-/// Processes a video to extract one side (left or right).
-pub fn process_video_side(
-    input: &Path,
-    output: &Path,
-    side: Side,
+/// Returns the video stream at `index` among the video streams, or the
+/// first one with a frame size if no index is given.
+fn select_video_stream(streams: &[StreamInfo], index: Option<usize>) -> Result<&StreamInfo> {
+    let mut video = streams
+        .iter()
+        .filter(|s| s.codec_type.as_deref() == Some("video"));
+    match index {
+        Some(index) => {
+            video
+                .clone()
+                .nth(index)
+                .ok_or_else(|| ObsCutterError::VideoStreamNotFound {
+                    index,
+                    count: video.count(),
+                })
+        }
+        None => video
+            .find(|s| s.width.is_some() && s.height.is_some())
+            .ok_or(ObsCutterError::NoVideoStream),
+    }
+}
+
+/// Estimates the size in bytes of a single output side.
+///
+/// Bitrate-driven encoders (VideoToolbox) use their target bitrate; quality-driven
+/// encoders scale half the source bitrate by a per-preset heuristic factor.
+/// This is a rough guide only. Returns None when the duration (or, for
+/// quality-driven encoders, the source size) is unknown.
+pub fn estimate_output_size(
+    info: &VideoInfo,
     quality: Quality,
     encoder: &HardwareEncoder,
-) -> Result<()> {
-    let ffmpeg_path = ffmpeg::get_ffmpeg_path();
-    let crop_filter = side.crop_filter();
-    let codec_args = get_codec_args(quality.as_str(), encoder);
-
-    let mut args: Vec<String> = vec![
-        "-i".to_string(),
-        input.to_string_lossy().to_string(),
-        "-vf".to_string(),
-        crop_filter.to_string(),
-    ];
-    args.extend(codec_args);
-    args.push("-y".to_string());
-    args.push(output.to_string_lossy().to_string());
-
-    let output_result = Command::new(ffmpeg_path)
-        .args(&args)
-        .stdout(Stdio::null())
-        .stderr(Stdio::piped())
-        .output()
-        .map_err(|e| ObsCutterError::FfmpegFailed(e.to_string()))?;
+) -> Option<u64> {
+    let duration = info.duration.filter(|d| *d > 0.0)?;
 
-    if !output_result.status.success() {
-        let error = String::from_utf8_lossy(&output_result.stderr);
-        return Err(ObsCutterError::FfmpegFailed(error.to_string()));
+    let source_mbps = info
+        .bitrate
+        .and_then(|bps| encoder.source_bitrate_mbps(quality.as_str(), bps));
+    if let Some(mbps) = source_mbps.or_else(|| encoder.target_bitrate_mbps(quality.as_str())) {
+        let bytes_per_sec = f64::from(mbps) * 1_000_000.0 / 8.0;
+        return Some((bytes_per_sec * duration) as u64);
     }
 
-    Ok(())
+    // Each side carries roughly half of the source's pixels
+    let half_source = info.file_size? as f64 / 2.0;
+    let factor = match (quality, encoder.is_hardware()) {
+        // True lossless x264 (CRF 0) is far larger than typical OBS recordings
+        (Quality::Lossless, false) => 4.0,
+        (Quality::Lossless, true) => 1.5,
+        (Quality::High, _) => 1.0,
+        (Quality::Medium, _) => 0.5,
+        // The fastest presets compress less efficiently at the same CRF
+        (Quality::Fast, _) => 0.7,
+    };
+    Some((half_source * factor) as u64)
 }
 
-/// Processes a video to extract one side with real-time progress callbacks.
+/// Delay before the first retry; later retries wait proportionally longer.
+const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Runs an encode attempt, retrying FFmpeg failures up to `retries` times.
 ///
-/// This version uses `.spawn()` instead of `.output()` to stream FFmpeg's
-/// stderr and parse progress information in real-time.
-pub fn process_video_side_with_progress<F>(
+/// Only `FfmpegFailed` errors are retried; anything else (missing input,
+/// missing FFmpeg, an unavailable encoder, cancellation) is returned
+/// immediately. Each failure that is retried is passed to `on_retry` first,
+/// then retry `n` waits `backoff * n` (encodes use [`RETRY_BACKOFF`]).
+fn with_retry<F, R>(
+    retries: u8,
+    backoff: Duration,
     input: &Path,
-    output: &Path,
-    side: Side,
-    quality: Quality,
-    encoder: &HardwareEncoder,
-    total_duration: Option<f64>,
-    mut progress_callback: F,
+    mut on_retry: R,
+    mut attempt: F,
 ) -> Result<()>
 where
-    F: FnMut(EncodingProgress),
+    F: FnMut() -> Result<()>,
+    R: FnMut(Retry),
 {
-    let ffmpeg_path = ffmpeg::get_ffmpeg_path();
-    let crop_filter = side.crop_filter();
-    let codec_args = get_codec_args(quality.as_str(), encoder);
-
-    let mut args: Vec<String> = vec![
-        "-i".to_string(),
-        input.to_string_lossy().to_string(),
-        "-vf".to_string(),
-        crop_filter.to_string(),
-    ];
-    args.extend(codec_args);
-    args.push("-y".to_string());
-    args.push(output.to_string_lossy().to_string());
+    if !input.exists() {
+        return Err(ObsCutterError::VideoNotFound(input.to_path_buf()));
+    }
 
-    // Spawn the process instead of waiting for output
-    let mut child = Command::new(ffmpeg_path)
-        .args(&args)
-        .stdout(Stdio::null())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| ObsCutterError::FfmpegFailed(e.to_string()))?;
+    let mut attempt_number: u8 = 0;
+    loop {
+        match attempt() {
+            Err(ObsCutterError::FfmpegFailed(error)) if attempt_number < retries => {
+                attempt_number += 1;
+                on_retry(Retry {
+                    attempt: attempt_number,
+                    attempts: u16::from(retries) + 1,
+                    error: error.lines().last().unwrap_or_default().to_string(),
+                });
+                std::thread::sleep(backoff * u32::from(attempt_number));
+            }
+            result => return result,
+        }
+    }
+}
 
-    // Set up the progress parser
-    let mut parser = if let Some(duration) = total_duration {
-        FfmpegProgressParser::with_duration(duration)
-    } else {
-        FfmpegProgressParser::new()
-    };
+/// Runs an encode with the given encoder, falling back to software if enabled.
+///
+/// Returns the encoder that actually produced the output, which is how a
+/// fallback is reported to the caller.
+fn with_fallback<F>(
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    mut encode: F,
+) -> Result<HardwareEncoder>
+where
+    F: FnMut(&HardwareEncoder) -> Result<()>,
+{
+    match encode(encoder) {
+        Ok(()) => Ok(*encoder),
+        Err(
+            ObsCutterError::FfmpegFailed(_)
+            | ObsCutterError::EncoderUnavailable(_)
+            | ObsCutterError::ResolutionTooLarge { .. },
+        ) if config.fallback_to_software && encoder.is_hardware() => {
+            encode(&HardwareEncoder::None)?;
+            Ok(HardwareEncoder::None)
+        }
+        Err(e) => Err(e),
+    }
+}
 
-    // Read stderr line by line for progress updates
-    if let Some(stderr) = child.stderr.take() {
-        let reader = BufReader::new(stderr);
+/// FFmpeg messages meaning the encoder cannot be used at all, as opposed
+/// to an encode that failed part way.
+const ENCODER_UNAVAILABLE_PATTERNS: &[&str] = &[
+    "Cannot load nvcuda",
+    "Cannot load libcuda",
+    "Cannot load libnvidia-encode",
+    "No NVENC capable devices found",
+    "Unknown encoder",
+];
 
-        // FFmpeg outputs progress on the same line using \r, so we need to
-        // handle both newlines and carriage returns
-        let mut buffer = String::new();
-        let mut buf_reader = BufReader::new(reader.into_inner());
+/// Returns true if FFmpeg's stderr shows the encoder is unusable.
+fn is_encoder_unavailable(stderr: &str) -> bool {
+    ENCODER_UNAVAILABLE_PATTERNS
+        .iter()
+        .any(|pattern| stderr.contains(pattern))
+}
 
-        loop {
-            buffer.clear();
-            match buf_reader.read_line(&mut buffer) {
-                Ok(0) => break, // EOF
-                Ok(_) => {
-                    // FFmpeg uses \r for progress updates on the same line
-                    for line in buffer.split(['\r', '\n']) {
-                        if !line.is_empty() {
-                            if let Some(progress) = parser.parse_line(line) {
-                                progress_callback(progress);
-                            }
-                        }
-                    }
-                }
-                Err(_) => break,
-            }
+/// Turns an FFmpeg failure caused by an unusable hardware encoder into
+/// [`ObsCutterError::EncoderUnavailable`]; other errors are returned as is.
+fn classify_encoder_failure(error: ObsCutterError, encoder: &HardwareEncoder) -> ObsCutterError {
+    match error {
+        ObsCutterError::FfmpegFailed(stderr)
+            if encoder.is_hardware() && is_encoder_unavailable(&stderr) =>
+        {
+            ObsCutterError::EncoderUnavailable(*encoder)
         }
+        other => other,
     }
+}
 
-    // Wait for the process to complete
-    let status = child
-        .wait()
-        .map_err(|e| ObsCutterError::FfmpegFailed(e.to_string()))?;
+/// Creates an FFmpeg command configured according to `config`.
+pub(crate) fn ffmpeg_command(config: &ProcessingConfig) -> Command {
+    let mut command = Command::new(ffmpeg::get_ffmpeg_path());
+    command.stdin(Stdio::null());
 
-    if !status.success() {
-        return Err(ObsCutterError::FfmpegFailed(
-            "FFmpeg process exited with error".to_string(),
-        ));
+    #[cfg(unix)]
+    if config.isolate_ffmpeg_signals {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
     }
+    #[cfg(not(unix))]
+    let _ = config;
 
-    Ok(())
+    command
 }
 
-/// Get video duration using FFprobe.
-pub fn get_video_duration(video_path: &Path) -> Result<f64> {
-    let ffprobe_path = ffmpeg::get_ffprobe_path();
+/// Probes the input before encoding a side, reporting a missing file as such.
+fn probe_input(input: &Path, stream: Option<usize>, format: Option<&str>) -> Result<VideoInfo> {
+    if !input.exists() {
+        return Err(ObsCutterError::VideoNotFound(input.to_path_buf()));
+    }
+    probe_video_info(input, stream, format)
+}
 
-    let output = Command::new(ffprobe_path)
-        .args([
-            "-v",
-            "error",
-            "-show_entries",
-            "format=duration",
-            "-of",
-            "default=noprint_wrappers=1:nokey=1",
-        ])
-        .arg(video_path)
-        .output()
-        .map_err(|e| ObsCutterError::VideoAnalysisFailed(e.to_string()))?;
+/// Returns the frame size the crop is computed from: the content region,
+/// the configured override, or else the probed size with rotation applied.
+fn frame_size(info: &VideoInfo, config: &ProcessingConfig) -> (u32, u32) {
+    if let Some(content) = config.content_crop {
+        return (content.width, content.height);
+    }
+    config
+        .override_dimensions
+        .unwrap_or_else(|| info.display_size())
+}
 
-    if !output.status.success() {
-        return Err(ObsCutterError::VideoAnalysisFailed(
-            String::from_utf8_lossy(&output.stderr).to_string(),
-        ));
+/// Returns the region of the input frame one side is cropped to.
+fn side_crop(input: &SideInput, side: Side, config: &ProcessingConfig) -> CropRect {
+    let crop = side.crop_rect(input.size.0, input.size.1);
+    match config.content_crop {
+        Some(content) => crop.within(content),
+        None => crop,
     }
+}
 
-    let duration_str = String::from_utf8_lossy(&output.stdout);
-    duration_str
-        .trim()
-        .parse::<f64>()
-        .map_err(|_| ObsCutterError::VideoAnalysisFailed("Failed to parse duration".to_string()))
+/// Returns the audio track matching `config.audio_language`, if any.
+fn selected_audio_track<'a>(
+    info: &'a VideoInfo,
+    config: &ProcessingConfig,
+) -> Option<&'a AudioTrack> {
+    info.audio_track(config.audio_language.as_deref()?)
 }
 
-/// Processes a single video, extracting both left and right sides.
-pub fn process_video(
-    input: &Path,
-    output_dir: &Path,
-    output_format: Option<&str>,
-    quality: Quality,
+/// Returns true if a frame of this size is two 16:9 halves side by side.
+fn is_splittable((width, height): (u32, u32)) -> bool {
+    height > 0 && u64::from(width) * 9 == u64::from(height) * 32
+}
+
+/// Checks that the output container can hold the codecs this encode will
+/// produce, so an invalid combination fails before FFmpeg is spawned.
+fn check_container(
+    info: &VideoInfo,
+    output: &Path,
+    config: &ProcessingConfig,
     encoder: &HardwareEncoder,
-) -> Result<ProcessingResult> {
-    let start_time = std::time::Instant::now();
+) -> Result<()> {
+    let container = if is_stdout_output(output) {
+        config.pipe_format.clone()
+    } else {
+        match output.extension() {
+            Some(extension) => extension.to_string_lossy().to_string(),
+            None => return Ok(()),
+        }
+    };
 
-    // Prepare output paths
-    let input_name = input
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .ok_or_else(|| ObsCutterError::VideoNotFound(input.to_path_buf()))?;
+    // 10-bit sources become H.265, except on the x264 bitrate path
+    let bitrate_path = config.target_bitrate.is_some() && !encoder.is_hardware();
+    let video_codec = if info.color.is_ten_bit() && !bitrate_path {
+        "hevc"
+    } else {
+        "h264"
+    };
+    let audio_codec = match &config.audio {
+        AudioMode::Copy => selected_audio_track(info, config)
+            .map(|track| track.codec.as_str())
+            .or(info.audio_codec.as_deref()),
+        AudioMode::Aac { .. } => Some("aac"),
+        AudioMode::None => None,
+    };
 
-    let input_ext = input.extension().and_then(|s| s.to_str()).unwrap_or("mp4");
+    for codec in std::iter::once(video_codec).chain(audio_codec) {
+        if !is_codec_compatible(&container, codec) {
+            return Err(ObsCutterError::IncompatibleCodec {
+                container,
+                codec: codec.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
 
-    let ext = output_format.unwrap_or(input_ext);
+/// Resolves the portion of the input to encode (see
+/// [`ProcessingConfig::effective_trim`]) against the input duration.
+fn resolve_trim(
+    config: &ProcessingConfig,
+    total_duration: Option<f64>,
+) -> Result<Option<ResolvedTrim>> {
+    config
+        .effective_trim()
+        .map(|trim| trim.resolve(total_duration))
+        .transpose()
+}
 
-    let output_left = output_dir.join(format!("{}-left.{}", input_name, ext));
-    let output_right = output_dir.join(format!("{}-right.{}", input_name, ext));
+/// The input of a side encode, probed once up front.
+struct SideInput<'a> {
+    path: &'a Path,
+    size: (u32, u32),
+    /// Duration of the whole input, if known.
+    duration: Option<f64>,
+    trim: Option<ResolvedTrim>,
+    /// Language of the audio track to map, if one matches the config.
+    audio_language: Option<String>,
+    color: ColorInfo,
+    /// Bitrate of the source in bits per second, if known.
+    bitrate: Option<u64>,
+    /// Whether the GPU pipeline was requested and FFmpeg can crop on the GPU.
+    cuda_crop: bool,
+    /// Whether FFmpeg takes `-fps_mode` rather than the deprecated `-vsync`.
+    fps_mode: bool,
+}
 
-    // Process left side
-    process_video_side(input, &output_left, Side::Left, quality, encoder)?;
+impl<'a> SideInput<'a> {
+    /// Probes `input` and checks that `output` can hold the encode.
+    ///
+    /// `duration` replaces the probed duration when given.
+    fn probe(
+        input: &'a Path,
+        output: &Path,
+        config: &ProcessingConfig,
+        encoder: &HardwareEncoder,
+        duration: Option<f64>,
+    ) -> Result<Self> {
+        let info = probe_input(input, config.video_stream, config.input_format.as_deref())?;
+        check_container(&info, output, config, encoder)?;
+        let size = frame_size(&info, config);
+        if config.strict_dimensions && !is_splittable(size) {
+            return Err(ObsCutterError::InvalidDimensions {
+                width: size.0,
+                height: size.1,
+            });
+        }
 
-    // Process right side
-    process_video_side(input, &output_right, Side::Right, quality, encoder)?;
+        let duration = duration.or(info.duration);
+        Ok(Self {
+            path: input,
+            size,
+            duration,
+            trim: resolve_trim(config, duration)?,
+            audio_language: selected_audio_track(&info, config)
+                .and_then(|track| track.language.clone()),
+            color: info.color,
+            bitrate: info.bitrate,
+            cuda_crop: cuda_crop_available(config),
+            // Only looked up when a frame rate is forced
+            fps_mode: config.target_fps.is_some()
+                && ffmpeg::supports_feature(FfmpegFeature::FpsMode),
+        })
+    }
+}
 
-    // Get output file sizes
-    let left_size = std::fs::metadata(&output_left)
-        .map(|m| m.len())
-        .unwrap_or(0);
-    let right_size = std::fs::metadata(&output_right)
-        .map(|m| m.len())
-        .unwrap_or(0);
+/// Formats a frame rate for `-r`. NTSC rates like 29.97 are written as
+/// the exact fraction they stand for (`30000/1001`), so frames aren't
+/// drifted by the rounded decimal.
+fn frame_rate_arg(fps: f64) -> String {
+    let ntsc = (fps * 1.001).round();
+    if fps.fract() != 0.0 && ntsc > 0.0 && (fps * 1.001 - ntsc).abs() < 0.005 {
+        format!("{}/1001", ntsc * 1000.0)
+    } else {
+        fps.to_string()
+    }
+}
 
-    Ok(ProcessingResult {
-        input: input.to_path_buf(),
-        left_output: output_left,
-        right_output: output_right,
-        left_size,
-        right_size,
-        duration: start_time.elapsed(),
-        encoder_used: *encoder,
+/// FFmpeg filter that crops CUDA frames without leaving the GPU.
+const CUDA_CROP_FILTER: &str = "crop_cuda";
+
+/// Returns true if `config` asks for the GPU pipeline and the installed
+/// FFmpeg supports it; otherwise NVENC encodes crop on the CPU.
+fn cuda_crop_available(config: &ProcessingConfig) -> bool {
+    config.gpu_pipeline && ffmpeg::has_filter(CUDA_CROP_FILTER)
+}
+
+/// Returns true if this side is decoded, cropped and encoded on the GPU.
+///
+/// Burned-in subtitles need CPU frames, and 10-bit sources keep the CPU
+/// path so their pixel format conversion still applies.
+fn uses_gpu_pipeline(
+    input: &SideInput,
+    side: Side,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+) -> bool {
+    let burns_subtitles = config.burn_subtitles.is_some() && config.burn_subtitles_side == side;
+    input.cuda_crop
+        && *encoder == HardwareEncoder::Nvenc
+        && !input.color.is_ten_bit()
+        && !burns_subtitles
+        && range_conversion(input, config).is_none()
+        && downscaled_size(input, config).is_none()
+        && pad_filter(input, config).is_none()
+}
+
+/// Returns the size of the encoded frames of a side.
+fn output_size(input: &SideInput, config: &ProcessingConfig) -> (u32, u32) {
+    downscaled_size(input, config).unwrap_or_else(|| fitted_size(input, config))
+}
+
+/// Returns the size of a side's frames once padded, before any downscale.
+fn fitted_size(input: &SideInput, config: &ProcessingConfig) -> (u32, u32) {
+    let (width, height) = Side::crop_size(input.size.0, input.size.1);
+    config.fitted_size(width, height)
+}
+
+/// Returns the pad filter fitting a side into a 16:9 frame, centered, if
+/// `config.fit` pads it.
+fn pad_filter(input: &SideInput, config: &ProcessingConfig) -> Option<String> {
+    let (width, height) = Side::crop_size(input.size.0, input.size.1);
+    let (padded_width, padded_height) = config.fit.padded_size(width, height)?;
+    Some(format!(
+        "pad={}:{}:{}:{}",
+        padded_width,
+        padded_height,
+        ((padded_width - width) / 2) & !1,
+        ((padded_height - height) / 2) & !1
+    ))
+}
+
+/// Fails before FFmpeg runs if the encoder can't encode frames this large,
+/// which would otherwise surface as a cryptic codec level error. Software
+/// encoding has no size limit.
+fn check_encoder_size(
+    input: &SideInput,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+) -> Result<()> {
+    let (width, height) = output_size(input, config);
+    if encoder.can_encode(width, height, input.color.is_ten_bit()) {
+        return Ok(());
+    }
+    Err(ObsCutterError::ResolutionTooLarge {
+        encoder: *encoder,
+        width,
+        height,
     })
 }
 
-/// Formats a byte count as a human-readable string.
-pub fn format_file_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
+/// Returns the size a side is scaled down to when it exceeds the
+/// configured maximum output size.
+fn downscaled_size(input: &SideInput, config: &ProcessingConfig) -> Option<(u32, u32)> {
+    let (width, height) = fitted_size(input, config);
+    let capped = config.capped_size(width, height);
+    (capped != (width, height)).then_some(capped)
+}
+
+/// Returns the source and target range when the output range differs from
+/// the source's and the frames need converting.
+fn range_conversion(
+    input: &SideInput,
+    config: &ProcessingConfig,
+) -> Option<(ColorRange, ColorRange)> {
+    let source = input.color.color_range()?;
+    let target = config.color_range?;
+    (source != target).then_some((source, target))
+}
+
+/// Returns the range the output is tagged with: the configured one, or
+/// else the source's.
+fn output_color_range(input: &SideInput, config: &ProcessingConfig) -> Option<ColorRange> {
+    config.color_range.or_else(|| input.color.color_range())
+}
+
+/// One FFmpeg run of a two-pass encode.
+#[derive(Debug, Clone, Copy)]
+struct EncodePass<'a> {
+    /// Pass number, 1 or 2.
+    number: u8,
+    /// Prefix FFmpeg uses for its pass log files.
+    log_prefix: &'a Path,
+}
+
+/// Null output used by the analysis pass.
+const NULL_DEVICE: &str = if cfg!(windows) { "NUL" } else { "/dev/null" };
+
+/// Builds the FFmpeg arguments extracting one side of `input`.
+///
+/// With `pass` set, the arguments are for that pass of a two-pass encode;
+/// the first pass discards its output.
+fn side_args(
+    input: &SideInput,
+    output: &Path,
+    side: Side,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    pass: Option<EncodePass>,
+) -> Vec<String> {
+    let mut args = encoder.device_args();
+
+    // Decoded frames stay in GPU memory for the CUDA crop and NVENC
+    if uses_gpu_pipeline(input, side, config, encoder) {
+        args.extend(["-hwaccel", "cuda", "-hwaccel_output_format", "cuda"].map(String::from));
+    }
+
+    // Seeking before -i is fast and makes -t count from the seek point
+    if let Some(trim) = input.trim.filter(|t| t.start > 0.0) {
+        args.push("-ss".to_string());
+        args.push(format!("{:.3}", trim.start));
+    }
+
+    // As an input option, -threads sets the decoder's thread count
+    if let Some(threads) = config.threads {
+        args.push("-threads".to_string());
+        args.push(threads.to_string());
+    }
+
+    if let Some(ref format) = config.input_format {
+        args.push("-f".to_string());
+        args.push(format.clone());
+    }
+
+    args.push("-i".to_string());
+    args.push(input.path.to_string_lossy().to_string());
+
+    if let Some(length) = input.trim.and_then(|t| t.length) {
+        args.push("-t".to_string());
+        args.push(format!("{:.3}", length));
+    }
+
+    args.push("-vf".to_string());
+    args.push(video_filter(input, side, config, encoder));
+
+    args.extend(stream_maps(input, config));
+
+    // Re-times variable frame rate input by duplicating or dropping frames
+    if let Some(fps) = config.target_fps {
+        args.extend(["-r".to_string(), frame_rate_arg(fps)]);
+        let option = if input.fps_mode {
+            "-fps_mode"
+        } else {
+            "-vsync"
+        };
+        args.extend([option, "cfr"].map(String::from));
+    }
+
+    match config.target_bitrate {
+        Some(kbps) if !encoder.is_hardware() => {
+            args.extend(get_bitrate_args(
+                config.quality_for(side).as_str(),
+                kbps,
+                input.color.is_ten_bit(),
+            ));
+            args.extend(config.audio.codec_args());
+        }
+        _ => {
+            let quality = config.quality_for(side);
+            args.extend(get_codec_args(
+                quality.as_str(),
+                encoder,
+                &config.audio,
+                input.color.is_ten_bit(),
+            ));
+            let source_mbps = input
+                .bitrate
+                .and_then(|bps| encoder.source_bitrate_mbps(quality.as_str(), bps));
+            if let Some(mbps) = source_mbps {
+                if let Some(index) = args.iter().position(|arg| arg == "-b:v") {
+                    args[index + 1] = format!("{}M", mbps);
+                }
+            }
+        }
+    }
+    args.extend(config.audio.downmix_args(config.downmix));
+    args.extend(config.audio.normalize_args(config.normalize_audio));
+    if let Some(preset) = &config.encoder_preset {
+        apply_preset(&mut args, encoder, preset);
+    }
+    args.extend(input.color.metadata_args());
+    if let Some(range) = output_color_range(input, config) {
+        args.push("-color_range".to_string());
+        args.push(range.as_str().to_string());
+    }
+
+    // Hardware encoders do not use CPU encoding threads
+    if let Some(threads) = config.threads.filter(|_| !encoder.is_hardware()) {
+        args.push("-threads".to_string());
+        args.push(threads.to_string());
+    }
+
+    // Keep source metadata such as the creation time, then apply custom tags
+    args.extend(["-map_metadata", "0"].map(String::from));
+    // Chapters are mapped explicitly since not every muxer copies them by
+    // default. With -ss before -i, FFmpeg rebases them on the trim start and
+    // drops those outside the trimmed range.
+    let chapters = if config.preserve_chapters { "0" } else { "-1" };
+    args.extend(["-map_chapters".to_string(), chapters.to_string()]);
+    let mut tags: Vec<_> = config.metadata.iter().collect();
+    tags.sort();
+    for (key, value) in tags {
+        args.push("-metadata".to_string());
+        args.push(format!("{}={}", key, value));
+    }
+    if config.tag_side && !config.metadata.contains_key("comment") {
+        args.push("-metadata".to_string());
+        args.push(format!("comment=obs-cutter {} side", side));
+    }
+
+    if let Some(pass) = pass {
+        args.push("-pass".to_string());
+        args.push(pass.number.to_string());
+        args.push("-passlogfile".to_string());
+        args.push(pass.log_prefix.to_string_lossy().to_string());
+
+        if pass.number == 1 {
+            args.extend(["-an", "-f", "null"].map(String::from));
+            args.extend(config.extra_args.iter().cloned());
+            args.extend(["-y", NULL_DEVICE].map(String::from));
+            return args;
+        }
+    }
+
+    // The user's own arguments come last, right before the output, so they
+    // can override any option set above
+    if is_stdout_output(output) {
+        args.extend(["-f".to_string(), config.pipe_format.clone()]);
+        args.extend(config.extra_args.iter().cloned());
+        args.push("-".to_string());
+    } else if let Some(seconds) = config.segment_time {
+        // Keyframes forced at every boundary make each chunk exactly as long
+        args.push("-force_key_frames".to_string());
+        args.push(format!("expr:gte(t,n_forced*{})", seconds));
+        args.extend(["-f", "segment", "-segment_time"].map(String::from));
+        args.push(seconds.to_string());
+        args.extend(["-segment_start_number", "1", "-reset_timestamps", "1"].map(String::from));
+        if config.faststart && is_faststart_container(output) {
+            args.extend(["-segment_format_options", "movflags=+faststart"].map(String::from));
+        }
+        args.extend(config.extra_args.iter().cloned());
+        args.push("-y".to_string());
+        args.push(chunk_pattern(output).to_string_lossy().to_string());
+    } else {
+        if config.faststart && is_faststart_container(output) {
+            args.extend(["-movflags", "+faststart"].map(String::from));
+        }
+        args.extend(config.extra_args.iter().cloned());
+        args.push("-y".to_string());
+        args.push(output.to_string_lossy().to_string());
+    }
+
+    args
+}
+
+/// Returns true if `output`'s container supports moving its index to the
+/// front (`-movflags +faststart`).
+fn is_faststart_container(output: &Path) -> bool {
+    output
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ["mp4", "mov", "m4v"].contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Builds the FFmpeg arguments that extract one side of `input` into
+/// `output`, as [`process_side`] runs them.
+///
+/// The input is probed for its size, duration and color properties, and
+/// every option in `config` (trim, scaling, color, metadata, subtitles) is
+/// applied. This is a single encode; a two-pass encode runs these arguments
+/// twice with `-pass` and `-passlogfile` added.
+pub fn build_side_args(
+    input: &Path,
+    output: &Path,
+    side: Side,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+) -> Result<Vec<String>> {
+    let config = &*config_for_output(config, output);
+    let side_input = SideInput::probe(input, output, config, encoder, None)?;
+    Ok(side_args(&side_input, output, side, config, encoder, None))
+}
+
+/// Returns the `-map` arguments selecting the streams to encode, if the
+/// config asks for anything but FFmpeg's default selection.
+///
+/// Any explicit `-map` replaces the default selection entirely, so the
+/// video and audio streams are then both mapped.
+fn stream_maps(input: &SideInput, config: &ProcessingConfig) -> Vec<String> {
+    let language = input
+        .audio_language
+        .as_ref()
+        .filter(|_| config.audio != AudioMode::None);
+    if config.video_stream.is_none() && language.is_none() {
+        return Vec::new();
+    }
+
+    let mut maps = vec![
+        "-map".to_string(),
+        format!("0:v:{}", config.video_stream.unwrap_or(0)),
+    ];
+    match language {
+        Some(language) => maps.extend(["-map".to_string(), format!("0:a:m:language:{}", language)]),
+        None if config.audio != AudioMode::None => {
+            maps.extend(["-map".to_string(), "0:a:0?".to_string()])
+        }
+        None => {}
+    }
+    maps
+}
+
+/// Returns true if `output` is `-`, meaning the encode is written to stdout.
+pub fn is_stdout_output(output: &Path) -> bool {
+    output == Path::new("-")
+}
+
+/// Returns the stdout FFmpeg should use when writing to `output`.
+fn output_stdio(output: &Path) -> Stdio {
+    if is_stdout_output(output) {
+        Stdio::inherit()
+    } else {
+        Stdio::null()
+    }
+}
 
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
+/// Returns the config to encode to `output` with.
+///
+/// Data already written to a pipe cannot be taken back, so retries and
+/// software fallback are disabled when writing to stdout.
+fn config_for_output<'a>(config: &'a ProcessingConfig, output: &Path) -> Cow<'a, ProcessingConfig> {
+    if is_stdout_output(output) {
+        Cow::Owned(
+            config
+                .clone()
+                .with_retry(0)
+                .with_fallback_to_software(false),
+        )
     } else {
-        format!("{} B", bytes)
+        Cow::Borrowed(config)
+    }
+}
+
+/// Builds the `-vf` filter chain for one side.
+///
+/// Software filters (crop, any padding or downscale, then subtitles so they
+/// are positioned within the final frame) run before any hardware upload.
+fn video_filter(
+    input: &SideInput,
+    side: Side,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+) -> String {
+    let crop = side_crop(input, side, config);
+    if uses_gpu_pipeline(input, side, config, encoder) {
+        return format!(
+            "{}=w={}:h={}:x={}:y={}",
+            CUDA_CROP_FILTER, crop.width, crop.height, crop.x, crop.y
+        );
+    }
+
+    let mut filters = vec![crop.filter()];
+    filters.extend(pad_filter(input, config));
+
+    // Scaled before subtitles so they are rendered at the output size
+    if let Some((width, height)) = downscaled_size(input, config) {
+        filters.push(format!("scale={}:{}", width, height));
+    }
+
+    if let Some(subtitles) = &config.burn_subtitles {
+        if config.burn_subtitles_side == side {
+            filters.push(format!("subtitles={}", escape_filter_path(subtitles)));
+        }
+    }
+
+    // Without this, the encoder would tag the new range onto unconverted values
+    if let Some((source, target)) = range_conversion(input, config) {
+        filters.push(format!(
+            "scale=in_range={}:out_range={}",
+            source.filter_name(),
+            target.filter_name()
+        ));
+    }
+
+    if let Some(upload) = encoder.upload_filter(input.color.is_ten_bit()) {
+        filters.push(upload.to_string());
+    }
+
+    filters.join(",")
+}
+
+/// Escapes a path for use as a filter option inside a filtergraph.
+///
+/// FFmpeg unescapes twice: once when splitting the filtergraph and once when
+/// parsing the filter's options, so special characters (notably the colon in
+/// Windows drive letters and backslash separators) are escaped at both levels.
+fn escape_filter_path(path: &Path) -> String {
+    fn escape(value: &str, special: &[char]) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if special.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    let option = escape(&path.to_string_lossy(), &['\\', '\'', ':']);
+    escape(&option, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// A temporary directory for FFmpeg's two-pass log files, removed on drop.
+struct PassLogDir(PathBuf);
+
+impl PassLogDir {
+    fn create(side: Side) -> Result<Self> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let dir = std::env::temp_dir().join(format!(
+            "obs-cutter-{}-{}-{}",
+            std::process::id(),
+            side,
+            nanos
+        ));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self(dir))
+    }
+
+    fn log_prefix(&self) -> PathBuf {
+        self.0.join("ffmpeg2pass")
     }
 }
 
-/// Formats a duration as a human-readable string.
-pub fn format_duration(duration: Duration) -> String {
-    let total_secs = duration.as_secs();
-    let hours = total_secs / 3600;
-    let minutes = (total_secs % 3600) / 60;
-    let seconds = total_secs % 60;
+impl Drop for PassLogDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
 
-    if hours > 0 {
-        format!("{}h {}m {}s", hours, minutes, seconds)
-    } else if minutes > 0 {
-        format!("{}m {}s", minutes, seconds)
+/// Returns the number of FFmpeg passes an encode with `encoder` takes.
+///
+/// Two-pass encoding only applies to software encoding with a bitrate target.
+fn pass_count(config: &ProcessingConfig, encoder: &HardwareEncoder) -> u8 {
+    if config.two_pass && config.target_bitrate.is_some() && !encoder.is_hardware() {
+        2
     } else {
-        format!("{}s", seconds)
+        1
+    }
+}
+
+/// Runs `run` once per pass, managing the pass log for two-pass encodes.
+fn run_passes<F>(
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    side: Side,
+    mut run: F,
+) -> Result<()>
+where
+    F: FnMut(Option<EncodePass>) -> Result<()>,
+{
+    if config.is_cancelled() {
+        return Err(ObsCutterError::Cancelled);
+    }
+    if pass_count(config, encoder) == 1 {
+        return run(None);
+    }
+
+    let log_dir = PassLogDir::create(side)?;
+    let log_prefix = log_dir.log_prefix();
+    for number in 1..=2 {
+        run(Some(EncodePass {
+            number,
+            log_prefix: &log_prefix,
+        }))?;
+    }
+    Ok(())
+}
+
+/// Rescales the progress of one pass so that it spans all passes.
+fn span_passes(
+    mut progress: EncodingProgress,
+    pass: Option<EncodePass>,
+    passes: u8,
+) -> EncodingProgress {
+    let pass_duration = progress.total_duration_secs;
+    let completed = pass.map_or(0, |p| p.number - 1);
+
+    progress.total_duration_secs = pass_duration * f64::from(passes);
+    progress.current_time_secs += pass_duration * f64::from(completed);
+    if progress.total_duration_secs > 0.0 {
+        progress.percentage =
+            ((progress.current_time_secs / progress.total_duration_secs) * 100.0).min(100.0) as f32;
+    }
+    progress
+}
+
+/// Processes a video to extract one side (left or right).
+///
+/// FFmpeg failures are retried up to `config.retry` times, without
+/// reporting them (see [`process_side_with_progress`]), then retried
+/// once in software if `config.fallback_to_software` is set. Returns the encoder
+/// that was actually used, which differs from `encoder` only after that
+/// fallback.
+///
+/// VideoToolbox runs with `-allow_sw 1` and may use Apple's software encoder
+/// on its own; FFmpeg doesn't log that, so it is still reported as
+/// VideoToolbox.
+///
+/// The side is encoded to a temporary `.partial` file that is moved to
+/// `output` only on success, so an interrupted run never leaves a
+/// complete-looking output behind.
+///
+/// An `output` of `-` writes the side to stdout in `config.pipe_format`;
+/// retries and fallback are then disabled.
+pub fn process_side(
+    input: &Path,
+    output: &Path,
+    side: Side,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+) -> Result<HardwareEncoder> {
+    let config = &*config_for_output(config, output);
+    let side_input = SideInput::probe(input, output, config, encoder, None)?;
+
+    write_side_output(output, config, |output| {
+        with_fallback(config, encoder, |encoder| {
+            check_encoder_size(&side_input, config, encoder)?;
+            with_retry(
+                config.retry,
+                RETRY_BACKOFF,
+                input,
+                |_| {},
+                || {
+                    run_passes(config, encoder, side, |pass| {
+                        let args = side_args(&side_input, output, side, config, encoder, pass);
+                        run_video_side(&args, output, config)
+                            .map_err(|e| classify_encoder_failure(e, encoder))
+                    })
+                },
+            )
+        })
+    })
+}
+
+/// Runs `encode` against a temporary path next to `output`, then moves the
+/// result into place. The temporary file is removed if `encode` fails.
+///
+/// The output's directory is created if needed.
+fn write_atomically<T>(output: &Path, encode: impl FnOnce(&Path) -> Result<T>) -> Result<T> {
+    if is_stdout_output(output) {
+        return encode(output);
+    }
+
+    create_output_dir(output)?;
+    let partial = partial_path(output);
+    let result = encode(&partial).and_then(|value| {
+        persist(&partial, output)?;
+        Ok(value)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Writes a side like [`write_atomically`], or as chunks when
+/// `config.segment_time` is set.
+///
+/// Chunks are written next to the temporary path and moved into place once
+/// the encode succeeds. Fails with [`ObsCutterError::StaleChunks`] before
+/// encoding if files named like chunks of `output` already exist, since
+/// they can't be told apart from the new ones.
+fn write_side_output<T>(
+    output: &Path,
+    config: &ProcessingConfig,
+    encode: impl FnOnce(&Path) -> Result<T>,
+) -> Result<T> {
+    if config.segment_time.is_none() || is_stdout_output(output) {
+        return write_atomically(output, encode);
+    }
+
+    if let Some((_, existing)) = chunk_paths(output).into_iter().next() {
+        return Err(ObsCutterError::StaleChunks {
+            output: output.to_path_buf(),
+            existing,
+        });
+    }
+
+    create_output_dir(output)?;
+    let partial = partial_path(output);
+    let result = encode(&partial).and_then(|value| {
+        for (number, chunk) in chunk_paths(&partial) {
+            persist(&chunk, &scenes::segment_output_path(output, number))?;
+        }
+        Ok(value)
+    });
+    if result.is_err() {
+        for (_, chunk) in chunk_paths(&partial) {
+            let _ = std::fs::remove_file(chunk);
+        }
+    }
+    result
+}
+
+/// Creates the directory `output` is written to, if needed.
+fn create_output_dir(output: &Path) -> Result<()> {
+    if let Some(dir) = output.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            ObsCutterError::OutputDirectoryError(format!("{}: {}", dir.display(), e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Returns the segment muxer's file name pattern for the chunks of
+/// `output`, e.g. `clip-left-%03d.mp4`.
+fn chunk_pattern(output: &Path) -> PathBuf {
+    let mut name = output.file_stem().unwrap_or_default().to_os_string();
+    name.push("-%03d");
+    if let Some(extension) = output.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    output.with_file_name(name)
+}
+
+/// Returns the existing chunks of `output` with their numbers, in order.
+fn chunk_paths(output: &Path) -> Vec<(usize, PathBuf)> {
+    let dir = output
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut chunks: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let chunk = output.with_file_name(entry.file_name());
+            Some((output_part_number(output, &chunk)?, chunk))
+        })
+        .collect();
+    chunks.sort();
+    chunks
+}
+
+/// Returns the number of `path` if it is one of the numbered files `output`
+/// is written as with scene segments or chunks, e.g. 3 for
+/// `clip-left-003.mp4` next to `clip-left.mp4`.
+pub fn output_part_number(output: &Path, path: &Path) -> Option<usize> {
+    if path.parent() != output.parent() || path.extension() != output.extension() {
+        return None;
+    }
+    let stem = output.file_stem()?.to_str()?;
+    let number = path
+        .file_stem()?
+        .to_str()?
+        .strip_prefix(stem)?
+        .strip_prefix('-')?;
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    number.parse().ok()
+}
+
+/// Returns the files a side encoded to `output` was written as: `output`
+/// itself, or its chunks (`{stem}-001.{ext}`, ...) with
+/// `config.segment_time`.
+pub fn side_output_files(output: &Path, config: &ProcessingConfig) -> Vec<PathBuf> {
+    if config.segment_time.is_none() || is_stdout_output(output) {
+        return vec![output.to_path_buf()];
+    }
+    chunk_paths(output)
+        .into_iter()
+        .map(|(_, chunk)| chunk)
+        .collect()
+}
+
+/// Returns the temporary path an output is written to, e.g.
+/// `clip-left.partial.mp4` for `clip-left.mp4`.
+///
+/// The original extension is kept last because FFmpeg picks the container
+/// from it.
+fn partial_path(output: &Path) -> PathBuf {
+    let mut name = output.file_stem().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    if let Some(extension) = output.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    output.with_file_name(name)
+}
+
+/// Moves `from` to `to`, copying and deleting when they are on different
+/// filesystems.
+fn persist(from: &Path, to: &Path) -> Result<()> {
+    match std::fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            std::fs::copy(from, to)?;
+            std::fs::remove_file(from)?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Runs a single FFmpeg invocation extracting one side.
+fn run_video_side(args: &[String], output: &Path, config: &ProcessingConfig) -> Result<()> {
+    let mut child = ffmpeg_command(config)
+        .args(args)
+        .stdout(output_stdio(output))
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(ffmpeg::ffmpeg_spawn_error)?;
+
+    // Read on its own thread while the process is polled for cancellation
+    let stderr = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut error = Vec::new();
+            let _ = stderr.read_to_end(&mut error);
+            String::from_utf8_lossy(&error).to_string()
+        })
+    });
+
+    let status = wait_for_exit(&mut child, config)?;
+    if !status.success() {
+        let error = stderr
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        return Err(ObsCutterError::FfmpegFailed(error));
+    }
+
+    Ok(())
+}
+
+/// How often a running FFmpeg is checked for cancellation.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Waits for an FFmpeg process to exit, killing it if processing is
+/// cancelled first.
+///
+/// Returns [`ObsCutterError::Cancelled`] if processing was cancelled, even
+/// when FFmpeg exited on its own (Ctrl-C reaches FFmpeg too).
+pub(crate) fn wait_for_exit(
+    child: &mut std::process::Child,
+    config: &ProcessingConfig,
+) -> Result<std::process::ExitStatus> {
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| ObsCutterError::FfmpegFailed(e.to_string()))?
+        {
+            break status;
+        }
+        if config.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(ObsCutterError::Cancelled);
+        }
+        std::thread::sleep(CANCEL_POLL_INTERVAL);
+    };
+
+    if config.is_cancelled() {
+        return Err(ObsCutterError::Cancelled);
+    }
+    Ok(status)
+}
+
+/// Processes a video to extract one side with real-time progress callbacks.
+///
+/// This version uses `.spawn()` instead of `.output()` to stream FFmpeg's
+/// stderr and parse progress information in real-time. Retry and software
+/// fallback behave as in [`process_side`], as do the temporary
+/// `.partial` output and the returned encoder; each retry is reported as
+/// [`SideProgress::Retrying`].
+///
+/// Once FFmpeg exits successfully, a final update at 100% is reported even
+/// if the last parsed position fell short of the probed duration.
+pub fn process_side_with_progress<F>(
+    input: &Path,
+    output: &Path,
+    side: Side,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    total_duration: Option<f64>,
+    progress_callback: F,
+) -> Result<HardwareEncoder>
+where
+    F: FnMut(SideProgress),
+{
+    // Shared by the encode and the retries between its attempts
+    let progress_callback = RefCell::new(progress_callback);
+    let report = |progress| (progress_callback.borrow_mut())(progress);
+
+    let config = &*config_for_output(config, output);
+    let side_input = SideInput::probe(input, output, config, encoder, total_duration)?;
+
+    // Progress is measured against the trimmed length
+    let total_duration = match side_input.trim {
+        Some(trim) => trim.output_duration(side_input.duration),
+        None => side_input.duration,
+    };
+
+    write_side_output(output, config, |output| {
+        with_fallback(config, encoder, |encoder| {
+            check_encoder_size(&side_input, config, encoder)?;
+            let passes = pass_count(config, encoder);
+            let on_retry = |retry| report(SideProgress::Retrying(retry));
+            with_retry(config.retry, RETRY_BACKOFF, input, on_retry, || {
+                run_passes(config, encoder, side, |pass| {
+                    let args = side_args(&side_input, output, side, config, encoder, pass);
+                    let mut last = EncodingProgress {
+                        total_duration_secs: total_duration.unwrap_or(0.0),
+                        ..EncodingProgress::default()
+                    };
+                    run_video_side_with_progress(
+                        &args,
+                        output,
+                        config,
+                        total_duration,
+                        &mut |progress| {
+                            last = progress.clone();
+                            report(SideProgress::Encoding(span_passes(progress, pass, passes)))
+                        },
+                    )
+                    .map_err(|e| classify_encoder_failure(e, encoder))?;
+
+                    // A mis-probed duration can leave the last update short of 100%
+                    report(SideProgress::Encoding(span_passes(
+                        last.completed(),
+                        pass,
+                        passes,
+                    )));
+                    Ok(())
+                })
+            })
+        })
+    })
+}
+
+/// Runs a single FFmpeg invocation extracting one side, streaming progress.
+fn run_video_side_with_progress<F>(
+    args: &[String],
+    output: &Path,
+    config: &ProcessingConfig,
+    total_duration: Option<f64>,
+    progress_callback: &mut F,
+) -> Result<()>
+where
+    F: FnMut(EncodingProgress),
+{
+    // Prefer the machine-readable -progress format; it needs stdout, so it
+    // is only used when the video isn't written there, and it reports no
+    // duration, so only when the duration is known
+    if let Some(duration) = total_duration.filter(|_| !is_stdout_output(output)) {
+        let mut child = ffmpeg_command(config)
+            .args(["-progress", "pipe:1", "-nostats"])
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(ffmpeg::ffmpeg_spawn_error)?;
+
+        // Drained on its own thread so a chatty FFmpeg can't fill the pipe
+        let stderr = child.stderr.take().map(|stderr| {
+            std::thread::spawn(move || {
+                let mut tail = StderrTail::default();
+                for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+                    tail.push(&line);
+                }
+                tail
+            })
+        });
+
+        let mut parser = MachineProgressParser::with_duration(duration);
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+                if config.is_cancelled() {
+                    let _ = child.kill();
+                    break;
+                }
+                if let Some(progress) = parser.parse_line(&line) {
+                    progress_callback(progress);
+                }
+            }
+        }
+        let status = wait_for_exit(&mut child, config)?;
+        let tail = stderr
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        return check_exit_status(status, tail);
+    }
+
+    // Spawn the process instead of waiting for output
+    let mut child = ffmpeg_command(config)
+        .args(args)
+        .stdout(output_stdio(output))
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(ffmpeg::ffmpeg_spawn_error)?;
+
+    // Fall back to parsing the status line FFmpeg writes to stderr
+    let mut parser = if let Some(duration) = total_duration {
+        FfmpegProgressParser::with_duration(duration)
+    } else {
+        FfmpegProgressParser::new()
+    };
+
+    // Read stderr line by line for progress updates, keeping the other
+    // lines in case FFmpeg fails
+    let mut tail = StderrTail::default();
+    if let Some(stderr) = child.stderr.take() {
+        let reader = BufReader::new(stderr);
+
+        // FFmpeg outputs progress on the same line using \r, so we need to
+        // handle both newlines and carriage returns
+        let mut buffer = String::new();
+        let mut buf_reader = BufReader::new(reader.into_inner());
+
+        loop {
+            if config.is_cancelled() {
+                let _ = child.kill();
+                break;
+            }
+            buffer.clear();
+            match buf_reader.read_line(&mut buffer) {
+                Ok(0) => break, // EOF
+                Ok(_) => {
+                    // FFmpeg uses \r for progress updates on the same line
+                    for line in buffer.split(['\r', '\n']) {
+                        if !line.is_empty() {
+                            match parser.parse_line(line) {
+                                Some(progress) => progress_callback(progress),
+                                None => tail.push(line),
+                            }
+                        }
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    }
+
+    let status = wait_for_exit(&mut child, config)?;
+    check_exit_status(status, tail)
+}
+
+/// Number of stderr lines kept to explain a failed encode.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// The last lines FFmpeg wrote to stderr.
+#[derive(Debug, Default)]
+struct StderrTail {
+    lines: std::collections::VecDeque<String>,
+}
+
+impl StderrTail {
+    fn push(&mut self, line: &str) {
+        if self.lines.len() == STDERR_TAIL_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.to_string());
+    }
+}
+
+/// Fails with the tail of FFmpeg's stderr if it exited with an error.
+fn check_exit_status(status: std::process::ExitStatus, stderr: StderrTail) -> Result<()> {
+    if !status.success() {
+        let message = if stderr.lines.is_empty() {
+            "FFmpeg process exited with error".to_string()
+        } else {
+            Vec::from(stderr.lines).join("\n")
+        };
+        return Err(ObsCutterError::FfmpegFailed(message));
+    }
+
+    Ok(())
+}
+
+/// Copies the streams of `input` into a new container at `output` without
+/// re-encoding or cropping.
+///
+/// The container is chosen from the output extension, so this is a fast,
+/// lossless way to change e.g. `.mov` to `.mp4`.
+pub fn remux(input: &Path, output: &Path) -> Result<()> {
+    if !input.exists() {
+        return Err(ObsCutterError::VideoNotFound(input.to_path_buf()));
+    }
+
+    let output_result = Command::new(ffmpeg::get_ffmpeg_path())
+        .arg("-i")
+        .arg(input)
+        .args(["-map", "0", "-c", "copy", "-map_metadata", "0", "-y"])
+        .arg(output)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(ffmpeg::ffmpeg_spawn_error)?;
+
+    if !output_result.status.success() {
+        let error = String::from_utf8_lossy(&output_result.stderr);
+        return Err(ObsCutterError::FfmpegFailed(error.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Stacks `left` and `right` side by side into `output`, the inverse of a
+/// split. Useful for checking that the two halves still line up.
+///
+/// Both inputs must have the same height.
+pub fn join_videos(left: &Path, right: &Path, output: &Path) -> Result<()> {
+    join_videos_with_layout(left, right, output, JoinLayout::Horizontal)
+}
+
+/// Like [`join_videos`], but stacks the inputs in the given layout.
+///
+/// Vertical stacking requires both inputs to have the same width. The
+/// audio of `left` is kept.
+pub fn join_videos_with_layout(
+    left: &Path,
+    right: &Path,
+    output: &Path,
+    layout: JoinLayout,
+) -> Result<()> {
+    let left_info = probe_input(left, None, None)?;
+    let right_info = probe_input(right, None, None)?;
+    check_join_dimensions(left_info.display_size(), right_info.display_size(), layout)?;
+
+    write_atomically(output, |partial| {
+        let output_result = Command::new(ffmpeg::get_ffmpeg_path())
+            .args(join_args(left, right, partial, layout))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(ffmpeg::ffmpeg_spawn_error)?;
+
+        if !output_result.status.success() {
+            let error = String::from_utf8_lossy(&output_result.stderr);
+            return Err(ObsCutterError::FfmpegFailed(error.to_string()));
+        }
+        Ok(())
+    })
+}
+
+/// Checks that inputs of the given display sizes can be stacked: equal
+/// heights side by side, equal widths one above the other.
+fn check_join_dimensions(left: (u32, u32), right: (u32, u32), layout: JoinLayout) -> Result<()> {
+    let (dimension, matches) = match layout {
+        JoinLayout::Horizontal => ("heights", left.1 == right.1),
+        JoinLayout::Vertical => ("widths", left.0 == right.0),
+    };
+    if matches {
+        return Ok(());
+    }
+    Err(ObsCutterError::MismatchedDimensions(format!(
+        "{} must match, but the inputs are {}x{} and {}x{}",
+        dimension, left.0, left.1, right.0, right.1
+    )))
+}
+
+/// Builds the FFmpeg arguments for [`join_videos_with_layout`].
+///
+/// The stacked video is re-encoded in software at high quality; audio is
+/// copied from the first input if it has any.
+fn join_args(left: &Path, right: &Path, output: &Path, layout: JoinLayout) -> Vec<String> {
+    let mut args = vec![
+        "-i".to_string(),
+        left.to_string_lossy().to_string(),
+        "-i".to_string(),
+        right.to_string_lossy().to_string(),
+        "-filter_complex".to_string(),
+        format!("[0:v][1:v]{}=inputs=2[v]", layout.filter_name()),
+        "-map".to_string(),
+        "[v]".to_string(),
+        "-map".to_string(),
+        "0:a?".to_string(),
+    ];
+    args.extend(get_codec_args(
+        Quality::High.as_str(),
+        &HardwareEncoder::None,
+        &AudioMode::Copy,
+        false,
+    ));
+    args.extend(["-y".to_string(), output.to_string_lossy().to_string()]);
+    args
+}
+
+/// Seconds of video [`detect_black_bars`] samples.
+const BLACK_BAR_SAMPLE_SECS: f64 = 5.0;
+
+/// Smallest share of the frame width or height black bars must take to be
+/// reported, so that a few dark rows at the edge of a game don't count.
+const MIN_BLACK_BAR_FRACTION: f64 = 0.02;
+
+/// Detects black bars around the picture (letterboxing or pillarboxing),
+/// returning the rectangle holding the content.
+///
+/// Samples a few seconds from the middle of the recording with FFmpeg's
+/// `cropdetect` filter, so a black intro doesn't count. Returns None if the
+/// content fills the frame, or if detection fails.
+///
+/// A 16:9 capture pillarboxed into a 32:9 canvas, for example, reports the
+/// centered 1920x1080 region: splitting the whole frame would put half of
+/// the picture and a black bar on each side.
+///
+/// The video stream and demuxer are the ones `config` selects. Fails only
+/// with [`ObsCutterError::Cancelled`] when processing is cancelled.
+pub fn detect_black_bars(path: &Path, config: &ProcessingConfig) -> Result<Option<CropRect>> {
+    let Ok(info) = get_input_info(path, config) else {
+        return Ok(None);
+    };
+    let start = info
+        .duration
+        .map_or(0.0, |duration| (duration - BLACK_BAR_SAMPLE_SECS) / 2.0)
+        .max(0.0);
+
+    let Ok(mut child) = ffmpeg_command(config)
+        .args(black_bar_args(path, start, config))
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+    else {
+        return Ok(None);
+    };
+
+    // cropdetect logs a line per frame, widening the region as it goes
+    let stderr = child.stderr.take().map(|stderr| {
+        std::thread::spawn(move || {
+            BufReader::new(stderr)
+                .lines()
+                .map_while(|line| line.ok())
+                .filter_map(|line| parse_cropdetect(&line))
+                .last()
+        })
+    });
+
+    let status = wait_for_exit(&mut child, config)?;
+    let content = stderr.and_then(|reader| reader.join().ok()).flatten();
+    if !status.success() {
+        return Ok(None);
+    }
+    Ok(content.filter(|&content| has_black_bars(content, info.display_size())))
+}
+
+/// Builds the FFmpeg arguments for [`detect_black_bars`].
+fn black_bar_args(input: &Path, start: f64, config: &ProcessingConfig) -> Vec<String> {
+    let mut args = vec![
+        "-hide_banner".to_string(),
+        "-nostats".to_string(),
+        "-ss".to_string(),
+        format!("{:.3}", start),
+        "-t".to_string(),
+        BLACK_BAR_SAMPLE_SECS.to_string(),
+    ];
+    if let Some(format) = &config.input_format {
+        args.extend(["-f".to_string(), format.clone()]);
+    }
+    args.extend([
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        "-map".to_string(),
+        format!("0:v:{}", config.video_stream.unwrap_or(0)),
+        // round=2 keeps even sizes without shaving real content like the
+        // default of 16 would; reset=0 keeps the union over all frames
+        "-vf".to_string(),
+        "cropdetect=limit=24:round=2:reset=0".to_string(),
+        "-an".to_string(),
+        "-f".to_string(),
+        "null".to_string(),
+        "-".to_string(),
+    ]);
+    args
+}
+
+/// Extracts the region from a cropdetect line such as
+/// `[Parsed_cropdetect_0 @ 0x...] x1:960 x2:2879 ... crop=1920:1080:960:0`.
+fn parse_cropdetect(line: &str) -> Option<CropRect> {
+    if !line.contains("Parsed_cropdetect") {
+        return None;
+    }
+    let (_, crop) = line.rsplit_once("crop=")?;
+    let mut values = crop.split_whitespace().next()?.split(':');
+    let mut next = || values.next()?.parse::<u32>().ok();
+    let rect = CropRect {
+        width: next()?,
+        height: next()?,
+        x: next()?,
+        y: next()?,
+    };
+    (rect.width > 0 && rect.height > 0).then_some(rect)
+}
+
+/// Returns true if `content` leaves bars of at least
+/// [`MIN_BLACK_BAR_FRACTION`] of a frame of size `frame`.
+fn has_black_bars(content: CropRect, (width, height): (u32, u32)) -> bool {
+    let bars = |content: u32, frame: u32| {
+        f64::from(frame.saturating_sub(content)) >= f64::from(frame) * MIN_BLACK_BAR_FRACTION
+    };
+    bars(content.width, width) || bars(content.height, height)
+}
+
+/// Extracts a single cropped frame of `side` at `at_secs` as PNG bytes.
+///
+/// Intended for previews: only one frame is decoded, so this is fast enough
+/// to call synchronously.
+pub fn split_preview(input: &Path, side: Side, at_secs: f64) -> Result<Vec<u8>> {
+    let info = probe_input(input, None, None)?;
+
+    let output = Command::new(ffmpeg::get_ffmpeg_path())
+        .args(preview_args(input, side, at_secs, info.display_size()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(ffmpeg::ffmpeg_spawn_error)?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(ObsCutterError::FfmpegFailed(error.to_string()));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Builds the FFmpeg arguments for [`split_preview`].
+fn preview_args(input: &Path, side: Side, at_secs: f64, size: (u32, u32)) -> Vec<String> {
+    vec![
+        "-ss".to_string(),
+        format!("{:.3}", at_secs.max(0.0)),
+        "-i".to_string(),
+        input.to_string_lossy().to_string(),
+        "-vf".to_string(),
+        side.crop_filter(size.0, size.1),
+        "-vframes".to_string(),
+        "1".to_string(),
+        "-f".to_string(),
+        "image2pipe".to_string(),
+        "-vcodec".to_string(),
+        "png".to_string(),
+        "-".to_string(),
+    ]
+}
+
+/// Get video duration using FFprobe.
+pub fn get_video_duration(video_path: &Path) -> Result<f64> {
+    let ffprobe_path = ffmpeg::get_ffprobe_path();
+
+    let output = Command::new(ffprobe_path)
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ])
+        .arg(video_path)
+        .output()
+        .map_err(|e| ObsCutterError::VideoAnalysisFailed(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(ObsCutterError::VideoAnalysisFailed(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    let duration_str = String::from_utf8_lossy(&output.stdout);
+    duration_str
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| ObsCutterError::VideoAnalysisFailed("Failed to parse duration".to_string()))
+}
+
+/// Maximum allowed difference between input and output durations when verifying.
+const VERIFY_DURATION_TOLERANCE_SECS: f64 = 1.0;
+
+/// Checks that an encoded output has the expected dimensions and duration.
+///
+/// On failure the output is deleted, since it is most likely truncated.
+fn verify_output(
+    output: &Path,
+    expected_width: u32,
+    expected_height: u32,
+    expected_duration: Option<f64>,
+) -> Result<()> {
+    let check = || -> std::result::Result<(), String> {
+        let info = get_video_info(output).map_err(|e| e.to_string())?;
+
+        if info.width != expected_width || info.height != expected_height {
+            return Err(format!(
+                "expected {}x{}, got {}x{}",
+                expected_width, expected_height, info.width, info.height
+            ));
+        }
+
+        if let Some(expected) = expected_duration {
+            let actual = info.duration.unwrap_or(0.0);
+            let tolerance = VERIFY_DURATION_TOLERANCE_SECS.max(expected * 0.01);
+            if (expected - actual).abs() > tolerance {
+                return Err(format!(
+                    "expected duration {:.2}s, got {:.2}s",
+                    expected, actual
+                ));
+            }
+        }
+
+        Ok(())
+    };
+
+    check().map_err(|details| {
+        let _ = std::fs::remove_file(output);
+        ObsCutterError::VideoAnalysisFailed(format!(
+            "Verification of {} failed: {}",
+            output.display(),
+            details
+        ))
+    })
+}
+
+/// Returns the directory `input`'s outputs go to: `output_dir` when given,
+/// with its date placeholders filled in (see [`expand_output_dir`]),
+/// otherwise the directory containing `input`.
+pub fn resolve_output_dir(input: &Path, output_dir: Option<&Path>) -> PathBuf {
+    match output_dir {
+        Some(dir) => expand_output_dir(input, dir),
+        None => input
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf(),
+    }
+}
+
+/// Fills in the `{date}` and `{date:FORMAT}` placeholders of an output
+/// directory with `input`'s capture time, e.g. `out/{date:%Y-%m-%d}` becomes
+/// `out/2024-06-12` to group outputs in date folders.
+///
+/// `FORMAT` is a strftime-style pattern (see [`DateTime::format`]); a plain
+/// `{date}` is `YYYYMMDD-HHMMSS`. The capture time is looked up only when
+/// the path uses it, and is `undated` if it can't be found.
+pub fn expand_output_dir(input: &Path, output_dir: &Path) -> PathBuf {
+    let dir = output_dir.to_string_lossy();
+    if !dir.contains("{date") {
+        return output_dir.to_path_buf();
+    }
+    PathBuf::from(expand_date_placeholders(
+        &dir,
+        get_creation_time(input).as_ref(),
+    ))
+}
+
+/// Returns the part of an output directory that is the same for every
+/// input: the components before the first one with a date placeholder, e.g.
+/// `out` for `out/{date:%Y-%m-%d}`, or `.` if the first one has one.
+///
+/// Files that belong to the whole batch, like its journal, go here rather
+/// than into a literal `{date...}` directory.
+pub fn output_dir_root(output_dir: &Path) -> PathBuf {
+    let root: PathBuf = output_dir
+        .components()
+        .take_while(|component| !component.as_os_str().to_string_lossy().contains("{date"))
+        .collect();
+    if root.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        root
+    }
+}
+
+/// Returns the directory files that belong to a whole batch of `inputs`,
+/// like its journal, go in: the [`output_dir_root`] of `output_dir` when
+/// given, otherwise the deepest directory containing every input, since
+/// their outputs are written next to them.
+pub fn batch_output_dir(inputs: &[PathBuf], output_dir: Option<&Path>) -> PathBuf {
+    if let Some(dir) = output_dir {
+        return output_dir_root(dir);
+    }
+    let common = inputs
+        .iter()
+        .map(|input| resolve_output_dir(input, None))
+        .reduce(|common, dir| {
+            common
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect()
+        })
+        .unwrap_or_default();
+    if common.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        common
+    }
+}
+
+/// Replaces `{date}` and `{date:FORMAT}` in `text` with `date`, or with
+/// `undated` when there is none.
+fn expand_date_placeholders(text: &str, date: Option<&DateTime>) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{date") {
+        let after = &rest[start + "{date".len()..];
+        let (pattern, len) = if after.starts_with('}') {
+            (None, 0)
+        } else if let Some(close) = after.strip_prefix(':').and_then(|p| p.find('}')) {
+            (Some(&after[1..=close]), close + 1)
+        } else {
+            // Not a placeholder, e.g. "{dates}"
+            expanded.push_str(&rest[..start + "{date".len()]);
+            rest = after;
+            continue;
+        };
+
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&match (date, pattern) {
+            (Some(date), Some(pattern)) => date.format(pattern),
+            (Some(date), None) => date.format_compact(),
+            (None, _) => "undated".to_string(),
+        });
+        rest = &after[len + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Returns the output path for one side of `input` inside `output_dir`.
+///
+/// Outputs are named `{stem}-{side}.{ext}`, or `{stem}/{side}.{ext}` with
+/// `config.subdir_per_input`, unless `config.name_template` is set. Sample
+/// encodes (`config.sample`) add `-sample` to the name. The extension comes
+/// from [`ProcessingConfig::output_format_for`], or else the input.
+pub fn side_output_path(
+    input: &Path,
+    output_dir: &Path,
+    side: Side,
+    config: &ProcessingConfig,
+) -> Result<PathBuf> {
+    let input_name = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| ObsCutterError::VideoNotFound(input.to_path_buf()))?;
+
+    let input_ext = input.extension().and_then(|s| s.to_str()).unwrap_or("mp4");
+    let ext = config.output_format_for(input).unwrap_or(input_ext);
+
+    let mut name = match &config.name_template {
+        Some(template) => expand_name_template(template, input, input_name, side)?,
+        None if config.subdir_per_input => side.to_string(),
+        None => format!("{}-{}", input_name, side),
+    };
+    if config.sample.is_some() {
+        name.push_str("-sample");
+    }
+
+    if config.subdir_per_input {
+        Ok(output_dir
+            .join(input_name)
+            .join(format!("{}.{}", name, ext)))
+    } else {
+        Ok(output_dir.join(format!("{}.{}", name, ext)))
+    }
+}
+
+/// Returns true if `path` is named like an output of [`side_output_path`]
+/// without a name template: `{stem}-{side}`, optionally followed by
+/// `-sample` and a scene segment or chunk number, e.g. `clip-left-003.mp4`.
+pub fn is_side_output_name(path: &Path) -> bool {
+    let Some(mut stem) = path.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    if let Some((rest, number)) = stem.rsplit_once('-') {
+        if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) {
+            stem = rest;
+        }
+    }
+    let stem = stem.strip_suffix("-sample").unwrap_or(stem);
+    Side::ALL
+        .iter()
+        .any(|side| stem.ends_with(&format!("-{}", side)))
+}
+
+/// Fills in an output name template's `{stem}`, `{side}` and `{date}` (or
+/// `{date:FORMAT}`) placeholders. The date is looked up only when the
+/// template uses it.
+fn expand_name_template(template: &str, input: &Path, stem: &str, side: Side) -> Result<String> {
+    if !template.contains("{side}") {
+        return Err(ObsCutterError::InvalidNameTemplate(format!(
+            "'{}' must contain {{side}} so both sides get different names",
+            template
+        )));
+    }
+
+    let mut name = template
+        .replace("{stem}", stem)
+        .replace("{side}", side.as_str());
+    if name.contains("{date") {
+        name = expand_date_placeholders(&name, get_creation_time(input).as_ref());
+    }
+    Ok(name)
+}
+
+/// Returns when `path` was recorded, in UTC.
+///
+/// Uses the container's `creation_time` tag, which OBS writes in UTC, and
+/// falls back to the file's modification time when the tag is missing.
+pub fn get_creation_time(path: &Path) -> Option<DateTime> {
+    let tagged = Command::new(ffmpeg::get_ffprobe_path())
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format_tags=creation_time",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ])
+        .arg(path)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| DateTime::parse_iso8601(&String::from_utf8_lossy(&output.stdout)));
+
+    tagged.or_else(|| {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        Some(DateTime::from_system_time(modified))
+    })
+}
+
+/// Checks an encoded side of `input` against the expected crop size and
+/// (trimmed) duration.
+///
+/// On failure the output is deleted, since it is most likely truncated.
+pub fn verify_side_output(input: &Path, output: &Path, config: &ProcessingConfig) -> Result<()> {
+    let info = get_input_info(input, config)?;
+    let (frame_width, frame_height) = frame_size(&info, config);
+    let (crop_width, crop_height) = Side::crop_size(frame_width, frame_height);
+    let (fitted_width, fitted_height) = config.fitted_size(crop_width, crop_height);
+    let (width, height) = config.capped_size(fitted_width, fitted_height);
+    let expected_duration = match resolve_trim(config, info.duration)? {
+        Some(trim) => trim.output_duration(info.duration),
+        None => info.duration,
+    };
+
+    // Chunk lengths only add up to the expected duration, so each chunk's
+    // frame size is checked
+    if config.segment_time.is_some() && !is_stdout_output(output) {
+        let chunks = side_output_files(output, config);
+        if chunks.is_empty() {
+            return Err(ObsCutterError::VideoAnalysisFailed(format!(
+                "Verification of {} failed: no chunks were written",
+                output.display()
+            )));
+        }
+        return chunks
+            .iter()
+            .try_for_each(|chunk| verify_output(chunk, width, height, None));
+    }
+    verify_output(output, width, height, expected_duration)
+}
+
+/// Processes a single video, extracting both left and right sides, taking
+/// every option, including the output directory, from `config`.
+///
+/// Outputs go to `config.output_dir` (with its date placeholders filled in),
+/// or next to `input` when it is None. When `config.verify` is set, both
+/// outputs are probed after encoding.
+pub fn process_video_with_config(
+    input: &Path,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+) -> Result<ProcessingResult> {
+    let output_dir = resolve_output_dir(input, config.output_dir.as_deref());
+    split_video(
+        input,
+        &output_dir,
+        config,
+        encoder,
+        |side, output, config, encoder| process_side(input, output, side, config, encoder),
+    )
+}
+
+/// Processes a single video into `output_dir`, extracting both left and
+/// right sides at `quality`, in `output_format` or the input's container.
+#[deprecated(
+    note = "use `process_video_with_config`, which takes every option from a `ProcessingConfig`"
+)]
+pub fn process_video(
+    input: &Path,
+    output_dir: &Path,
+    output_format: Option<&str>,
+    quality: Quality,
+    encoder: &HardwareEncoder,
+) -> Result<ProcessingResult> {
+    let config = ProcessingConfig::new()
+        .with_quality(quality)
+        .with_output_format(output_format.map(str::to_string))
+        .with_output_dir(Some(output_dir.to_path_buf()));
+    process_video_with_config(input, &config, encoder)
+}
+
+/// Processes a video to extract one side at `quality`.
+#[deprecated(note = "use `process_side`, which takes a `ProcessingConfig`")]
+pub fn process_video_side(
+    input: &Path,
+    output: &Path,
+    side: Side,
+    quality: Quality,
+    encoder: &HardwareEncoder,
+) -> Result<()> {
+    let config = ProcessingConfig::new().with_quality(quality);
+    process_side(input, output, side, &config, encoder).map(|_| ())
+}
+
+/// Processes a video to extract one side at `quality`, reporting FFmpeg's
+/// progress to `progress_callback`.
+#[deprecated(note = "use `process_side_with_progress`, which takes a `ProcessingConfig`")]
+pub fn process_video_side_with_progress<F>(
+    input: &Path,
+    output: &Path,
+    side: Side,
+    quality: Quality,
+    encoder: &HardwareEncoder,
+    total_duration: Option<f64>,
+    mut progress_callback: F,
+) -> Result<()>
+where
+    F: FnMut(EncodingProgress),
+{
+    let config = ProcessingConfig::new().with_quality(quality);
+    process_side_with_progress(
+        input,
+        output,
+        side,
+        &config,
+        encoder,
+        total_duration,
+        |progress| {
+            if let SideProgress::Encoding(progress) = progress {
+                progress_callback(progress);
+            }
+        },
+    )
+    .map(|_| ())
+}
+
+/// Processes video `video_index` of a batch of `total` into `output_dir`
+/// like [`process_video_with_config`], reporting each phase to
+/// `progress_callback`.
+///
+/// [`ProcessingProgress::Analyzing`] is sent while the input is probed,
+/// [`ProcessingProgress::Processing`] as each side starts, `Encoding` with
+/// FFmpeg's progress while it runs (and `Retrying` before a failed encode is
+/// retried), then `Completed` or `Failed` once the video is done.
+pub fn process_video_with_progress<F>(
+    input: &Path,
+    output_dir: &Path,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    video_index: usize,
+    total: usize,
+    mut progress_callback: F,
+) -> Result<ProcessingResult>
+where
+    F: FnMut(ProcessingProgress),
+{
+    progress_callback(ProcessingProgress::Analyzing {
+        video_index,
+        total,
+        path: input.to_path_buf(),
+    });
+    let total_duration = get_input_info(input, config)
+        .ok()
+        .and_then(|info| info.duration);
+
+    let result = split_video(
+        input,
+        output_dir,
+        config,
+        encoder,
+        |side, output, config, encoder| {
+            progress_callback(ProcessingProgress::Processing {
+                video_index,
+                total,
+                side,
+                path: input.to_path_buf(),
+            });
+            process_side_with_progress(
+                input,
+                output,
+                side,
+                config,
+                encoder,
+                total_duration,
+                |progress| {
+                    progress_callback(match progress {
+                        SideProgress::Encoding(progress) => ProcessingProgress::Encoding {
+                            video_index,
+                            total,
+                            side,
+                            progress,
+                        },
+                        SideProgress::Retrying(retry) => ProcessingProgress::Retrying {
+                            video_index,
+                            total,
+                            side,
+                            retry,
+                        },
+                    })
+                },
+            )
+        },
+    );
+
+    progress_callback(match &result {
+        Ok(result) => ProcessingProgress::Completed {
+            video_index,
+            total,
+            result: result.clone(),
+        },
+        Err(e) => ProcessingProgress::Failed {
+            video_index,
+            total,
+            path: input.to_path_buf(),
+            error: e.to_string(),
+        },
+    });
+    result
+}
+
+/// FFmpeg messages meaning the output container can't hold a stream.
+const CONTAINER_FAILURE_PATTERNS: &[&str] = &[
+    "codec not currently supported in container",
+    "Could not find tag for codec",
+    "muxer does not support",
+];
+
+/// Encodes one side's `output` with `encode`. With
+/// `config.container_fallback`, an output FFmpeg can't write in the
+/// requested container is encoded again as MKV, with `output_format` set to
+/// `mkv` in the config passed to `encode`.
+///
+/// Returns the encode's result and the fallback, if one was needed.
+pub fn encode_with_container_fallback<T, F>(
+    side: Side,
+    output: &Path,
+    config: &ProcessingConfig,
+    mut encode: F,
+) -> Result<(T, Option<ContainerFallback>)>
+where
+    F: FnMut(&Path, &ProcessingConfig) -> Result<T>,
+{
+    match encode(output, config) {
+        Err(e) if config.container_fallback && can_fall_back_to_mkv(&e, output) => {
+            let fallback = ContainerFallback {
+                side,
+                requested: output.to_path_buf(),
+                output: output.with_extension("mkv"),
+                reason: match &e {
+                    ObsCutterError::FfmpegFailed(error) => {
+                        error.lines().last().unwrap_or_default().to_string()
+                    }
+                    other => other.to_string(),
+                },
+            };
+            let mkv_config = config.clone().with_output_format(Some("mkv".to_string()));
+            let value = encode(&fallback.output, &mkv_config)?;
+            Ok((value, Some(fallback)))
+        }
+        result => Ok((result?, None)),
+    }
+}
+
+/// Returns true if `error` means the streams don't fit the container of
+/// `output`, so writing MKV instead could help.
+fn can_fall_back_to_mkv(error: &ObsCutterError, output: &Path) -> bool {
+    let is_mkv = output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mkv"));
+    if is_mkv || is_stdout_output(output) {
+        return false;
+    }
+    match error {
+        ObsCutterError::IncompatibleCodec { .. } => true,
+        ObsCutterError::FfmpegFailed(stderr) => CONTAINER_FAILURE_PATTERNS
+            .iter()
+            .any(|pattern| stderr.contains(pattern)),
+        _ => false,
+    }
+}
+
+/// Encodes both sides of `input` with `encode_side`, then verifies and
+/// collects the outputs.
+///
+/// With `config.container_fallback`, a side FFmpeg can't write in the
+/// requested container is encoded again as MKV (see
+/// [`encode_with_container_fallback`]).
+///
+/// With `config.segment_scenes`, each side is encoded once per scene
+/// segment, with the segment as the trim range.
+fn split_video<F>(
+    input: &Path,
+    output_dir: &Path,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    mut encode_side: F,
+) -> Result<ProcessingResult>
+where
+    F: FnMut(Side, &Path, &ProcessingConfig, &HardwareEncoder) -> Result<HardwareEncoder>,
+{
+    let start_time = std::time::Instant::now();
+
+    let segments = match config.segment_scenes {
+        Some(threshold) => {
+            let cuts = scenes::detect_scene_changes(input, threshold, config)?;
+            let duration = get_input_info(input, config)
+                .ok()
+                .and_then(|info| info.duration);
+            scenes::scene_segments(&cuts, duration)
+        }
+        None => Vec::new(),
+    };
+
+    // A no-op when the caller already resolved the directory
+    let output_dir = &expand_output_dir(input, output_dir);
+    let mut jobs = split_jobs(input, output_dir, config, &segments)?;
+
+    // Stay in software once an encode fell back
+    let mut encoder_used = *encoder;
+    let mut left_duration = Duration::ZERO;
+    let mut right_duration = Duration::ZERO;
+    let mut container_fallbacks = Vec::new();
+    for (side, output, job_config) in &mut jobs {
+        let side_start = std::time::Instant::now();
+        let (used, fallback) =
+            encode_with_container_fallback(*side, output, job_config, |output, job_config| {
+                encode_side(*side, output, job_config, &encoder_used)
+            })?;
+        encoder_used = used;
+        if let Some(fallback) = fallback {
+            *output = fallback.output.clone();
+            *job_config = Cow::Owned(
+                job_config
+                    .as_ref()
+                    .clone()
+                    .with_output_format(Some("mkv".to_string())),
+            );
+            container_fallbacks.push(fallback);
+        }
+        match side {
+            Side::Left => left_duration += side_start.elapsed(),
+            Side::Right => right_duration += side_start.elapsed(),
+        }
+    }
+
+    if config.verify {
+        for (_, output, config) in &jobs {
+            verify_side_output(input, output, config)?;
+        }
+    }
+
+    // Chunked sides were written as several files
+    let split_into_files = !segments.is_empty() || config.segment_time.is_some();
+    let jobs: Vec<_> = jobs
+        .into_iter()
+        .flat_map(|(side, output, config)| {
+            side_output_files(&output, &config)
+                .into_iter()
+                .map(move |file| (side, file, config.clone()))
+        })
+        .collect();
+
+    // Get output file sizes before the input can go away
+    let side_size = |side: Side| -> u64 {
+        jobs.iter()
+            .filter(|(s, _, _)| *s == side)
+            .filter_map(|(_, output, _)| std::fs::metadata(output).ok())
+            .map(|m| m.len())
+            .sum()
+    };
+    let left_size = side_size(Side::Left);
+    let right_size = side_size(Side::Right);
+    let first_output = |side: Side| -> PathBuf {
+        jobs.iter()
+            .find(|(s, _, _)| *s == side)
+            .map(|(_, output, _)| output.clone())
+            .unwrap_or_default()
+    };
+    let output_left = first_output(Side::Left);
+    let output_right = first_output(Side::Right);
+
+    // Only reached once both sides succeeded (and verified, if enabled)
+    if config.delete_source_on_success {
+        std::fs::remove_file(input).map_err(|e| ObsCutterError::DeleteSourceFailed {
+            path: input.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+    }
+
+    Ok(ProcessingResult {
+        input: input.to_path_buf(),
+        left_output: output_left,
+        right_output: output_right,
+        segments: if split_into_files {
+            jobs.into_iter().map(|(_, output, _)| output).collect()
+        } else {
+            Vec::new()
+        },
+        left_size,
+        right_size,
+        duration: start_time.elapsed(),
+        left_duration,
+        right_duration,
+        encoder_used,
+        container_fallbacks,
+    })
+}
+
+/// Returns the outputs [`split_video`] encodes, in order, each with the
+/// config it is encoded with: one per side, or one per side and scene
+/// segment, trimmed to the segment.
+fn split_jobs<'a>(
+    input: &Path,
+    output_dir: &Path,
+    config: &'a ProcessingConfig,
+    segments: &[Trim],
+) -> Result<Vec<(Side, PathBuf, Cow<'a, ProcessingConfig>)>> {
+    let mut jobs = Vec::new();
+    for side in Side::ALL {
+        let output = side_output_path(input, output_dir, side, config)?;
+        if segments.is_empty() {
+            jobs.push((side, output, Cow::Borrowed(config)));
+            continue;
+        }
+        for (index, trim) in segments.iter().enumerate() {
+            let segment_config = config.clone().with_trim(Some(*trim));
+            let segment = scenes::segment_output_path(&output, index + 1);
+            jobs.push((side, segment, Cow::Owned(segment_config)));
+        }
+    }
+    Ok(jobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs [`with_retry`] without backoff on an attempt that fails its first
+    /// `failures` times with `error`. Returns the result, the number of
+    /// attempts made and the retries reported.
+    fn retry_stub(
+        retries: u8,
+        failures: u32,
+        error: fn() -> ObsCutterError,
+    ) -> (Result<()>, u32, Vec<Retry>) {
+        let input = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut attempts = 0;
+        let mut retried = Vec::new();
+        let result = with_retry(
+            retries,
+            Duration::ZERO,
+            input,
+            |retry| retried.push(retry),
+            || {
+                attempts += 1;
+                if attempts <= failures {
+                    Err(error())
+                } else {
+                    Ok(())
+                }
+            },
+        );
+        (result, attempts, retried)
+    }
+
+    fn ffmpeg_failed() -> ObsCutterError {
+        ObsCutterError::FfmpegFailed("frame=12\nConversion failed!".to_string())
+    }
+
+    #[test]
+    fn test_with_retry_retries_ffmpeg_failures() {
+        let (result, attempts, retried) = retry_stub(2, 2, ffmpeg_failed);
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+        assert_eq!(
+            retried,
+            [1, 2].map(|attempt| Retry {
+                attempt,
+                attempts: 3,
+                error: "Conversion failed!".to_string(),
+            })
+        );
+
+        // Gives up once the retries are spent
+        let (result, attempts, retried) = retry_stub(2, 5, ffmpeg_failed);
+        assert!(matches!(result, Err(ObsCutterError::FfmpegFailed(_))));
+        assert_eq!((attempts, retried.len()), (3, 2));
+
+        let (result, attempts, _) = retry_stub(0, 1, ffmpeg_failed);
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    /// Runs [`with_fallback`] on an encode that fails with `error` for
+    /// every encoder in `failing`. Returns the result and the encoders tried.
+    fn fallback_stub(
+        fallback: bool,
+        encoder: HardwareEncoder,
+        failing: &[HardwareEncoder],
+        error: fn() -> ObsCutterError,
+    ) -> (Result<HardwareEncoder>, Vec<HardwareEncoder>) {
+        let config = ProcessingConfig::new().with_fallback_to_software(fallback);
+        let mut tried = Vec::new();
+        let result = with_fallback(&config, &encoder, |encoder| {
+            tried.push(*encoder);
+            if failing.contains(encoder) {
+                Err(error())
+            } else {
+                Ok(())
+            }
+        });
+        (result, tried)
+    }
+
+    #[test]
+    fn test_with_fallback_retries_once_in_software() {
+        use HardwareEncoder::{None as Software, Nvenc};
+
+        let (result, tried) = fallback_stub(true, Nvenc, &[Nvenc], ffmpeg_failed);
+        assert_eq!(result.unwrap(), Software);
+        assert_eq!(tried, [Nvenc, Software]);
+
+        let (result, tried) = fallback_stub(true, Nvenc, &[Nvenc], || {
+            ObsCutterError::EncoderUnavailable(HardwareEncoder::Nvenc)
+        });
+        assert_eq!(result.unwrap(), Software);
+        assert_eq!(tried, [Nvenc, Software]);
+
+        // Software failing too is not retried again
+        let (result, tried) = fallback_stub(true, Nvenc, &[Nvenc, Software], ffmpeg_failed);
+        assert!(matches!(result, Err(ObsCutterError::FfmpegFailed(_))));
+        assert_eq!(tried, [Nvenc, Software]);
+
+        // A successful encode reports the requested encoder
+        let (result, tried) = fallback_stub(true, Nvenc, &[], ffmpeg_failed);
+        assert_eq!(result.unwrap(), Nvenc);
+        assert_eq!(tried, [Nvenc]);
+    }
+
+    #[test]
+    fn test_with_fallback_only_falls_back_from_hardware() {
+        use HardwareEncoder::{None as Software, Nvenc};
+
+        let (result, tried) = fallback_stub(true, Software, &[Software], ffmpeg_failed);
+        assert!(result.is_err());
+        assert_eq!(tried, [Software]);
+
+        // Disabled unless requested
+        let (result, tried) = fallback_stub(false, Nvenc, &[Nvenc], ffmpeg_failed);
+        assert!(result.is_err());
+        assert_eq!(tried, [Nvenc]);
+
+        // Errors unrelated to the encoder are not retried
+        let (result, tried) = fallback_stub(true, Nvenc, &[Nvenc], || ObsCutterError::Cancelled);
+        assert!(matches!(result, Err(ObsCutterError::Cancelled)));
+        assert_eq!(tried, [Nvenc]);
+    }
+
+    #[test]
+    fn test_with_retry_fails_other_errors_immediately() {
+        let (result, attempts, retried) = retry_stub(3, 1, || {
+            ObsCutterError::VideoNotFound(PathBuf::from("gone.mov"))
+        });
+        assert!(matches!(result, Err(ObsCutterError::VideoNotFound(_))));
+        assert_eq!(attempts, 1);
+        assert!(retried.is_empty());
+
+        let (result, attempts, _) = retry_stub(3, 1, || ObsCutterError::Cancelled);
+        assert!(matches!(result, Err(ObsCutterError::Cancelled)));
+        assert_eq!(attempts, 1);
+
+        // A missing input is reported before any attempt
+        let mut attempts = 0;
+        let result = with_retry(
+            3,
+            Duration::ZERO,
+            Path::new("/nonexistent/recording.mov"),
+            |_| {},
+            || {
+                attempts += 1;
+                Ok(())
+            },
+        );
+        assert!(matches!(result, Err(ObsCutterError::VideoNotFound(_))));
+        assert_eq!(attempts, 0);
+    }
+
+    fn sample_info(duration: Option<f64>, file_size: Option<u64>) -> VideoInfo {
+        VideoInfo {
+            path: PathBuf::from("sample.mov"),
+            width: 3840,
+            height: 1080,
+            codec: "h264".to_string(),
+            file_size,
+            duration,
+            fps: Some(60.0),
+            color: ColorInfo::default(),
+            rotation: None,
+            audio_codec: Some("aac".to_string()),
+            audio_tracks: vec![AudioTrack {
+                codec: "aac".to_string(),
+                language: None,
+            }],
+            bitrate: None,
+        }
+    }
+
+    #[test]
+    fn test_frame_size_override() {
+        let info = VideoInfo {
+            width: 1920,
+            height: 1080,
+            ..sample_info(None, None)
+        };
+        let config = ProcessingConfig::new();
+        assert_eq!(frame_size(&info, &config), (1920, 1080));
+
+        let config = config.with_override_dimensions(Some((3840, 1080)));
+        assert_eq!(frame_size(&info, &config), (3840, 1080));
+    }
+
+    #[test]
+    fn test_processing_result_sizes() {
+        let result = ProcessingResult {
+            input: PathBuf::from("in.mov"),
+            left_output: PathBuf::from("in-left.mov"),
+            right_output: PathBuf::from("in-right.mov"),
+            segments: Vec::new(),
+            left_size: 900,
+            right_size: 900,
+            duration: Duration::from_secs(1),
+            left_duration: Duration::from_millis(500),
+            right_duration: Duration::from_millis(500),
+            encoder_used: HardwareEncoder::None,
+            container_fallbacks: Vec::new(),
+        };
+
+        assert_eq!(result.total_size(), 1800);
+        assert!((result.compression_ratio(1000) - 1.8).abs() < f32::EPSILON);
+        assert_eq!(result.compression_ratio(0), 0.0);
+    }
+
+    #[test]
+    fn test_estimate_output_size_bitrate_encoder() {
+        let info = sample_info(Some(60.0), None);
+        let size = estimate_output_size(&info, Quality::High, &HardwareEncoder::VideoToolbox);
+
+        // 15 Mbit/s for 60 seconds
+        assert_eq!(size, Some(15_000_000 / 8 * 60));
+    }
+
+    #[test]
+    fn test_estimate_output_size_source_bitrate() {
+        let info = VideoInfo {
+            bitrate: Some(40_000_000),
+            ..sample_info(Some(60.0), None)
+        };
+        let lossless =
+            estimate_output_size(&info, Quality::Lossless, &HardwareEncoder::VideoToolbox);
+
+        // Half of the 40 Mbit/s source for 60 seconds
+        assert_eq!(lossless, Some(20_000_000 / 8 * 60));
+    }
+
+    #[test]
+    fn test_side_args_source_bitrate() {
+        let input = SideInput {
+            bitrate: Some(60_000_000),
+            ..sample_input(None)
+        };
+        let args = side_args(
+            &input,
+            Path::new("out.mp4"),
+            Side::Left,
+            &ProcessingConfig::new(),
+            &HardwareEncoder::VideoToolbox,
+            None,
+        );
+
+        let bitrate = args.iter().position(|a| a == "-b:v").unwrap() + 1;
+        assert_eq!(args[bitrate], "30M");
+    }
+
+    #[test]
+    fn test_estimate_output_size_quality_encoder() {
+        let info = sample_info(Some(60.0), Some(1_000_000));
+
+        let high = estimate_output_size(&info, Quality::High, &HardwareEncoder::None).unwrap();
+        let medium = estimate_output_size(&info, Quality::Medium, &HardwareEncoder::None).unwrap();
+        assert_eq!(high, 500_000);
+        assert!(medium < high);
+    }
+
+    #[test]
+    fn test_estimate_output_size_unknown_duration() {
+        let info = sample_info(None, Some(1_000_000));
+        assert!(estimate_output_size(&info, Quality::High, &HardwareEncoder::None).is_none());
+    }
+
+    fn sample_input(trim: Option<ResolvedTrim>) -> SideInput<'static> {
+        SideInput {
+            path: Path::new("in.mov"),
+            size: (3840, 1080),
+            duration: Some(60.0),
+            trim,
+            audio_language: None,
+            color: ColorInfo::default(),
+            bitrate: None,
+            cuda_crop: false,
+            fps_mode: true,
+        }
+    }
+
+    #[test]
+    fn test_side_args_gpu_pipeline() {
+        let input = SideInput {
+            cuda_crop: true,
+            ..sample_input(None)
+        };
+        let config = ProcessingConfig::new().with_gpu_pipeline(true);
+        let args = side_args(
+            &input,
+            Path::new("out.mp4"),
+            Side::Right,
+            &config,
+            &HardwareEncoder::Nvenc,
+            None,
+        );
+        assert_eq!(
+            &args[..6],
+            [
+                "-hwaccel",
+                "cuda",
+                "-hwaccel_output_format",
+                "cuda",
+                "-i",
+                "in.mov"
+            ]
+        );
+        let filter = args.iter().position(|a| a == "-vf").unwrap() + 1;
+        assert_eq!(args[filter], "crop_cuda=w=1920:h=1080:x=1920:y=0");
+
+        // Other encoders, and sides with burned-in subtitles, crop on the CPU
+        let args = side_args(
+            &input,
+            Path::new("out.mp4"),
+            Side::Right,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+        assert!(!args.contains(&"-hwaccel".to_string()));
+
+        let config = config.with_burn_subtitles(Some(PathBuf::from("subs.srt")), Side::Right);
+        let args = side_args(
+            &input,
+            Path::new("out.mp4"),
+            Side::Right,
+            &config,
+            &HardwareEncoder::Nvenc,
+            None,
+        );
+        assert!(!args.contains(&"-hwaccel".to_string()));
+    }
+
+    #[test]
+    fn test_side_args_with_trim() {
+        let input = sample_input(Some(ResolvedTrim {
+            start: 60.0,
+            length: Some(30.0),
+        }));
+        let args = side_args(
+            &input,
+            Path::new("out.mov"),
+            Side::Left,
+            &ProcessingConfig::new(),
+            &HardwareEncoder::None,
+            None,
+        );
+
+        assert_eq!(
+            &args[..6],
+            ["-ss", "60.000", "-i", "in.mov", "-t", "30.000"]
+        );
+        assert_eq!(args.last().map(String::as_str), Some("out.mov"));
+    }
+
+    #[test]
+    fn test_side_args_vaapi_filter_order() {
+        let args = side_args(
+            &sample_input(None),
+            Path::new("out.mov"),
+            Side::Right,
+            &ProcessingConfig::new(),
+            &HardwareEncoder::Vaapi,
+            None,
+        );
+
+        let device = args.iter().position(|a| a == "-vaapi_device").unwrap();
+        let input = args.iter().position(|a| a == "-i").unwrap();
+        assert!(device < input);
+
+        let filter = args.iter().position(|a| a == "-vf").unwrap() + 1;
+        assert_eq!(args[filter], "crop=1920:1080:1920:0,format=nv12,hwupload");
+    }
+
+    #[test]
+    fn test_side_args_faststart() {
+        let args = |output: &str, config: &ProcessingConfig| {
+            side_args(
+                &sample_input(None),
+                Path::new(output),
+                Side::Left,
+                config,
+                &HardwareEncoder::None,
+                None,
+            )
+        };
+        let has_faststart =
+            |args: Vec<String>| args.windows(2).any(|w| w == ["-movflags", "+faststart"]);
+
+        let config = ProcessingConfig::new();
+        assert!(has_faststart(args("out.partial.mp4", &config)));
+        assert!(has_faststart(args("out.MOV", &config)));
+        assert!(!has_faststart(args("out.mkv", &config)));
+        assert!(!has_faststart(args(
+            "out.mp4",
+            &config.clone().with_faststart(false)
+        )));
+    }
+
+    #[test]
+    fn test_side_args_input_format() {
+        let config = ProcessingConfig::new().with_input_format(Some("h264".to_string()));
+        let args = side_args(
+            &sample_input(None),
+            Path::new("out.mp4"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+
+        let input = args.iter().position(|a| a == "-i").unwrap();
+        assert_eq!(args[input - 2..input], ["-f", "h264"]);
+    }
+
+    #[test]
+    fn test_side_args_hdr_source() {
+        let mut input = sample_input(None);
+        input.color = ColorInfo {
+            pix_fmt: Some("p010le".to_string()),
+            primaries: Some("bt2020".to_string()),
+            transfer: Some("smpte2084".to_string()),
+            space: Some("unknown".to_string()),
+            range: None,
+        };
+        let args = side_args(
+            &input,
+            Path::new("out.mov"),
+            Side::Left,
+            &ProcessingConfig::new(),
+            &HardwareEncoder::Nvenc,
+            None,
+        )
+        .join(" ");
+
+        assert!(args.contains("-c:v hevc_nvenc"));
+        assert!(args.contains("-pix_fmt p010le -profile:v main10"));
+        assert!(args.contains("-color_primaries bt2020 -color_trc smpte2084"));
+        assert!(!args.contains("-colorspace"));
+    }
+
+    #[test]
+    fn test_side_args_color_range() {
+        let mut input = sample_input(None);
+        input.color.range = Some("pc".to_string());
+        let args = |config: &ProcessingConfig| {
+            side_args(
+                &input,
+                Path::new("out.mp4"),
+                Side::Left,
+                config,
+                &HardwareEncoder::None,
+                None,
+            )
+            .join(" ")
+        };
+
+        // A full-range source stays full range, tagged as such
+        let kept = args(&ProcessingConfig::new());
+        assert!(kept.contains("-color_range pc"));
+        assert!(!kept.contains("out_range"));
+
+        // Overriding the range converts the values as well as the tag
+        let config = ProcessingConfig::new().with_color_range(Some(ColorRange::Limited));
+        let converted = args(&config);
+        assert!(converted.contains("crop=1920:1080:0:0,scale=in_range=full:out_range=limited"));
+        assert!(converted.contains("-color_range tv"));
+    }
+
+    #[test]
+    fn test_video_filter_max_height() {
+        let config = ProcessingConfig::new().with_max_height(Some(1080));
+        let filter = |size| {
+            let input = SideInput {
+                size,
+                ..sample_input(None)
+            };
+            video_filter(&input, Side::Right, &config, &HardwareEncoder::None)
+        };
+
+        assert_eq!(
+            filter((5120, 1440)),
+            "crop=2560:1440:2560:0,scale=1920:1080"
+        );
+        assert_eq!(filter((3840, 1080)), "crop=1920:1080:1920:0");
+    }
+
+    #[test]
+    fn test_video_filter_pad() {
+        let config = ProcessingConfig::new().with_fit(crate::core::config::FitMode::Pad);
+        let filter = |size, config: &ProcessingConfig| {
+            let input = SideInput {
+                size,
+                ..sample_input(None)
+            };
+            video_filter(&input, Side::Left, config, &HardwareEncoder::None)
+        };
+
+        // A 3440x1440 ultrawide half gets black bars left and right
+        assert_eq!(
+            filter((3440, 1440), &config),
+            "crop=1720:1440:0:0,pad=2560:1440:420:0"
+        );
+        assert_eq!(filter((3840, 1080), &config), "crop=1920:1080:0:0");
+
+        // Padding happens before the downscale, which sees the 16:9 frame
+        let capped = config.with_max_height(Some(1080));
+        assert_eq!(
+            filter((3440, 1440), &capped),
+            "crop=1720:1440:0:0,pad=2560:1440:420:0,scale=1920:1080"
+        );
+    }
+
+    #[test]
+    fn test_video_filter_content_crop() {
+        // 16:9 content pillarboxed into the middle of a 5120x1080 canvas
+        let content = CropRect {
+            width: 3840,
+            height: 1080,
+            x: 640,
+            y: 0,
+        };
+        let config = ProcessingConfig::new().with_content_crop(Some(content));
+        let input = SideInput {
+            size: (3840, 1080),
+            ..sample_input(None)
+        };
+
+        assert_eq!(
+            video_filter(&input, Side::Left, &config, &HardwareEncoder::None),
+            "crop=1920:1080:640:0"
+        );
+        assert_eq!(
+            video_filter(&input, Side::Right, &config, &HardwareEncoder::None),
+            "crop=1920:1080:2560:0"
+        );
+    }
+
+    #[test]
+    fn test_black_bar_args_use_selected_stream() {
+        let input = Path::new("in.ts");
+        let args = black_bar_args(input, 10.0, &ProcessingConfig::default());
+        assert!(args.windows(2).any(|w| w == ["-map", "0:v:0"]));
+        assert!(!args.windows(2).any(|w| w == ["-f", "mpegts"]));
+
+        let config = ProcessingConfig::default()
+            .with_video_stream(Some(1))
+            .with_input_format(Some("mpegts".to_string()));
+        let args = black_bar_args(input, 10.0, &config);
+        assert!(args.windows(2).any(|w| w == ["-map", "0:v:1"]));
+        let format = args.iter().position(|a| a == "mpegts").unwrap();
+        let input = args.iter().position(|a| a == "in.ts").unwrap();
+        assert!(format < input);
+    }
+
+    #[test]
+    fn test_parse_cropdetect() {
+        let line = "[Parsed_cropdetect_0 @ 0x55d5] x1:960 x2:2879 y1:0 y2:1079 w:1920 h:1080 \
+                    x:960 y:0 pts:153600 t:12.000000 limit:0.094118 crop=1920:1080:960:0";
+        assert_eq!(
+            parse_cropdetect(line),
+            Some(CropRect {
+                width: 1920,
+                height: 1080,
+                x: 960,
+                y: 0,
+            })
+        );
+        // An all-black frame yields a negative size
+        assert_eq!(
+            parse_cropdetect("[Parsed_cropdetect_0 @ 0x55d5] crop=-3824:-1072:3832:1076"),
+            None
+        );
+        assert_eq!(parse_cropdetect("frame=  100 crop=1920:1080:0:0"), None);
+    }
+
+    #[test]
+    fn test_has_black_bars() {
+        let rect = |width, height| CropRect {
+            width,
+            height,
+            x: 0,
+            y: 0,
+        };
+        assert!(has_black_bars(rect(1920, 1080), (3840, 1080)));
+        assert!(has_black_bars(rect(3840, 800), (3840, 1080)));
+        assert!(!has_black_bars(rect(3840, 1080), (3840, 1080)));
+        // Dark edges a few pixels wide are not bars
+        assert!(!has_black_bars(rect(3836, 1078), (3840, 1080)));
+    }
+
+    #[test]
+    fn test_classify_encoder_failure() {
+        let failed = |stderr: &str| ObsCutterError::FfmpegFailed(stderr.to_string());
+        let no_driver = "[h264_nvenc @ 0x55d5] Cannot load nvcuda.dll\n\
+                         Error initializing output stream 0:0 -- Error while opening encoder";
+
+        assert!(matches!(
+            classify_encoder_failure(failed(no_driver), &HardwareEncoder::Nvenc),
+            ObsCutterError::EncoderUnavailable(HardwareEncoder::Nvenc)
+        ));
+        assert!(matches!(
+            classify_encoder_failure(
+                failed("Unknown encoder 'h264_qsv'"),
+                &HardwareEncoder::QuickSync
+            ),
+            ObsCutterError::EncoderUnavailable(HardwareEncoder::QuickSync)
+        ));
+
+        // Other failures, and software encodes, are left alone
+        assert!(matches!(
+            classify_encoder_failure(failed("Invalid data found"), &HardwareEncoder::Nvenc),
+            ObsCutterError::FfmpegFailed(_)
+        ));
+        assert!(matches!(
+            classify_encoder_failure(failed("Unknown encoder 'libx264'"), &HardwareEncoder::None),
+            ObsCutterError::FfmpegFailed(_)
+        ));
+    }
+
+    #[test]
+    fn test_is_splittable() {
+        assert!(is_splittable((3840, 1080)));
+        assert!(is_splittable((5120, 1440)));
+        assert!(is_splittable((7680, 2160)));
+        assert!(!is_splittable((1920, 1080)));
+        assert!(!is_splittable((3840, 1088)));
+        assert!(!is_splittable((0, 0)));
+    }
+
+    #[test]
+    fn test_side_args_audio_language() {
+        let mut info = sample_info(None, None);
+        info.audio_tracks = vec![
+            AudioTrack {
+                codec: "aac".to_string(),
+                language: Some("ita".to_string()),
+            },
+            AudioTrack {
+                codec: "opus".to_string(),
+                language: Some("ENG".to_string()),
+            },
+        ];
+        let config = ProcessingConfig::new().with_audio_language(Some("eng".to_string()));
+        let track = selected_audio_track(&info, &config).unwrap();
+        assert_eq!(track.codec, "opus");
+
+        // The stream's own tag is mapped, so FFmpeg's match is exact
+        let input = SideInput {
+            audio_language: track.language.clone(),
+            ..sample_input(None)
+        };
+        let args = side_args(
+            &input,
+            Path::new("out.mkv"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        )
+        .join(" ");
+        assert!(args.contains("-map 0:v:0 -map 0:a:m:language:ENG"));
+
+        // No matching track keeps FFmpeg's default selection
+        let config = config.with_audio_language(Some("fra".to_string()));
+        assert!(selected_audio_track(&info, &config).is_none());
+        let args = side_args(
+            &sample_input(None),
+            Path::new("out.mkv"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+        assert!(!args.contains(&"-map".to_string()));
+    }
+
+    #[test]
+    fn test_is_ten_bit() {
+        let color = |f: &str| ColorInfo {
+            pix_fmt: Some(f.to_string()),
+            ..ColorInfo::default()
+        };
+        assert!(color("p010le").is_ten_bit());
+        assert!(color("yuv420p10le").is_ten_bit());
+        assert!(!color("yuv420p").is_ten_bit());
+        assert!(!color("nv12").is_ten_bit());
+        assert!(!ColorInfo::default().is_ten_bit());
+    }
+
+    #[test]
+    fn test_preview_args() {
+        let args = preview_args(Path::new("in.mov"), Side::Right, 12.5, (3840, 1080));
+        assert_eq!(
+            args.join(" "),
+            "-ss 12.500 -i in.mov -vf crop=1920:1080:1920:0 \
+             -vframes 1 -f image2pipe -vcodec png -"
+        );
+
+        // Negative timestamps clamp to the first frame
+        let args = preview_args(Path::new("in.mov"), Side::Left, -1.0, (3840, 1080));
+        assert_eq!(args[1], "0.000");
+    }
+
+    #[test]
+    fn test_is_media_format() {
+        assert!(is_media_format("mov,mp4,m4a,3gp,3g2,mj2\n"));
+        assert!(is_media_format("matroska,webm"));
+        assert!(!is_media_format(""));
+        assert!(!is_media_format("tty\n"));
+    }
+
+    #[test]
+    fn test_join_args() {
+        let args = join_args(
+            Path::new("l.mp4"),
+            Path::new("r.mp4"),
+            Path::new("out.mp4"),
+            JoinLayout::Horizontal,
+        );
+        let joined = args.join(" ");
+        assert!(joined.starts_with(
+            "-i l.mp4 -i r.mp4 -filter_complex [0:v][1:v]hstack=inputs=2[v] -map [v] -map 0:a?"
+        ));
+        assert!(joined.ends_with("-y out.mp4"));
+
+        let args = join_args(
+            Path::new("l.mp4"),
+            Path::new("r.mp4"),
+            Path::new("out.mp4"),
+            JoinLayout::Vertical,
+        );
+        assert_eq!(args[5], "[0:v][1:v]vstack=inputs=2[v]");
+    }
+
+    #[test]
+    fn test_check_join_dimensions() {
+        let horizontal = JoinLayout::Horizontal;
+        assert!(check_join_dimensions((1920, 1080), (1280, 1080), horizontal).is_ok());
+        assert!(check_join_dimensions((1920, 1080), (1920, 1072), horizontal).is_err());
+
+        let vertical = JoinLayout::Vertical;
+        assert!(check_join_dimensions((1920, 1080), (1920, 720), vertical).is_ok());
+        let error = check_join_dimensions((1920, 1080), (1280, 1080), vertical).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot join videos: widths must match, but the inputs are 1920x1080 and 1280x1080"
+        );
+    }
+
+    #[test]
+    fn test_resolve_output_dir() {
+        let input = Path::new("/rec/clip.mov");
+        assert_eq!(resolve_output_dir(input, None), PathBuf::from("/rec"));
+        assert_eq!(
+            resolve_output_dir(Path::new("clip.mov"), None),
+            PathBuf::from(".")
+        );
+        assert_eq!(
+            resolve_output_dir(Path::new("rec/clip.mov"), None),
+            PathBuf::from("rec")
+        );
+
+        // An explicit output directory is used as given
+        for dir in [".", "./out", "out", "/tmp/out"] {
+            assert_eq!(
+                resolve_output_dir(input, Some(Path::new(dir))),
+                PathBuf::from(dir)
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_date_placeholders() {
+        let date = DateTime::parse_iso8601("2024-06-12T21:30:00Z").unwrap();
+        assert_eq!(
+            expand_date_placeholders("out/{date:%Y-%m-%d}/{date:%H}", Some(&date)),
+            "out/2024-06-12/21"
+        );
+        assert_eq!(
+            expand_date_placeholders("{date}_{side}", Some(&date)),
+            "20240612-213000_{side}"
+        );
+        assert_eq!(
+            expand_date_placeholders("out/{date:%Y}", None),
+            "out/undated"
+        );
+        // Anything else in braces is left alone
+        assert_eq!(
+            expand_date_placeholders("{dates}/{date:%Y", Some(&date)),
+            "{dates}/{date:%Y"
+        );
+
+        // Paths without placeholders are used as given, without a lookup
+        assert_eq!(
+            expand_output_dir(Path::new("missing.mov"), Path::new("out")),
+            PathBuf::from("out")
+        );
+    }
+
+    #[test]
+    fn test_output_dir_root() {
+        assert_eq!(
+            output_dir_root(Path::new("out/{date:%Y-%m-%d}/{date:%H}")),
+            PathBuf::from("out")
+        );
+        assert_eq!(
+            output_dir_root(Path::new("out/day-{date}")),
+            PathBuf::from("out")
+        );
+        assert_eq!(
+            output_dir_root(Path::new("out/all")),
+            PathBuf::from("out/all")
+        );
+        assert_eq!(output_dir_root(Path::new("{date}")), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_batch_output_dir() {
+        let inputs = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert_eq!(
+            batch_output_dir(&inputs(&["/rec/a.mov"]), Some(Path::new("out/{date}"))),
+            PathBuf::from("out")
+        );
+        assert_eq!(
+            batch_output_dir(&inputs(&["/rec/a.mov", "/rec/b.mov"]), None),
+            PathBuf::from("/rec")
+        );
+        assert_eq!(
+            batch_output_dir(&inputs(&["/rec/day1/a.mov", "/rec/day2/b.mov"]), None),
+            PathBuf::from("/rec")
+        );
+        assert_eq!(
+            batch_output_dir(&inputs(&["a.mov", "b.mov"]), None),
+            PathBuf::from(".")
+        );
+        assert_eq!(batch_output_dir(&[], None), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_is_side_output_name() {
+        for name in [
+            "clip-left.mp4",
+            "clip-right.mkv",
+            "clip-left-001.mp4",
+            "clip-right-sample.mp4",
+            "clip-left-sample-012.mp4",
+        ] {
+            assert!(is_side_output_name(Path::new(name)), "{}", name);
+        }
+        for name in ["clip.mp4", "left.mp4", "clip-001.mp4", "clip-leftover.mp4"] {
+            assert!(!is_side_output_name(Path::new(name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_output_part_number() {
+        let output = Path::new("out/clip-left.mp4");
+        assert_eq!(
+            output_part_number(output, Path::new("out/clip-left-003.mp4")),
+            Some(3)
+        );
+        assert_eq!(
+            output_part_number(output, Path::new("out/clip-left-1200.mp4")),
+            Some(1200)
+        );
+        assert_eq!(
+            output_part_number(output, Path::new("out/clip-left.mp4")),
+            None
+        );
+        assert_eq!(
+            output_part_number(output, Path::new("out/clip-left-.mp4")),
+            None
+        );
+        assert_eq!(
+            output_part_number(output, Path::new("out/clip-left-003.mkv")),
+            None
+        );
+        assert_eq!(
+            output_part_number(output, Path::new("clip-left-003.mp4")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_side_output_path() {
+        let config = ProcessingConfig::new();
+        let input = Path::new("/rec/clip.mov");
+        assert_eq!(
+            side_output_path(input, Path::new("/out"), Side::Left, &config).unwrap(),
+            PathBuf::from("/out/clip-left.mov")
+        );
+
+        let config = config
+            .with_subdir_per_input(true)
+            .with_output_format(Some("mp4".to_string()));
+        assert_eq!(
+            side_output_path(input, Path::new("/out"), Side::Right, &config).unwrap(),
+            PathBuf::from("/out/clip/right.mp4")
+        );
+
+        let config = ProcessingConfig::new().with_name_template(Some("{side}_{stem}".to_string()));
+        assert_eq!(
+            side_output_path(input, Path::new("/out"), Side::Left, &config).unwrap(),
+            PathBuf::from("/out/left_clip.mov")
+        );
+
+        // Without {side} both outputs would overwrite each other
+        let config = ProcessingConfig::new().with_name_template(Some("{stem}".to_string()));
+        assert!(matches!(
+            side_output_path(input, Path::new("/out"), Side::Left, &config),
+            Err(ObsCutterError::InvalidNameTemplate(_))
+        ));
+
+        let config = ProcessingConfig::new().with_sample(Some(30.0));
+        assert_eq!(
+            side_output_path(input, Path::new("/out"), Side::Left, &config).unwrap(),
+            PathBuf::from("/out/clip-left-sample.mov")
+        );
+    }
+
+    #[test]
+    fn test_resolve_trim_sample() {
+        // A sample replaces the trim range and starts at the beginning
+        let trim = crate::core::config::Trim {
+            start: Some(60.0),
+            ..Default::default()
+        };
+        let config = ProcessingConfig::new()
+            .with_trim(Some(trim))
+            .with_sample(Some(30.0));
+        assert_eq!(
+            resolve_trim(&config, Some(3600.0)).unwrap(),
+            Some(ResolvedTrim {
+                start: 0.0,
+                length: Some(30.0),
+            })
+        );
+        assert_eq!(
+            resolve_trim(&config.with_sample(None), Some(3600.0)).unwrap(),
+            Some(ResolvedTrim {
+                start: 60.0,
+                length: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_side_args_threads() {
+        let config = ProcessingConfig::new().with_threads(Some(16));
+        let software = side_args(
+            &sample_input(None),
+            Path::new("out.mov"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+        let threads: Vec<_> = software
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| *a == "-threads")
+            .map(|(i, _)| i)
+            .collect();
+        let input = software.iter().position(|a| a == "-i").unwrap();
+        assert_eq!(threads.len(), 2);
+        assert!(threads[0] < input && threads[1] > input);
+        assert_eq!(software[threads[1] + 1], "16");
+
+        // Hardware encodes only thread the decoder
+        let hardware = side_args(
+            &sample_input(None),
+            Path::new("out.mov"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::Nvenc,
+            None,
+        );
+        assert_eq!(hardware.iter().filter(|a| *a == "-threads").count(), 1);
+    }
+
+    #[test]
+    fn test_partial_path() {
+        assert_eq!(
+            partial_path(Path::new("/out/clip-left.mp4")),
+            PathBuf::from("/out/clip-left.partial.mp4")
+        );
+        assert_eq!(
+            partial_path(Path::new("clip")),
+            PathBuf::from("clip.partial")
+        );
+    }
+
+    #[test]
+    fn test_write_atomically() {
+        let dir = std::env::temp_dir().join(format!("obs-cutter-atomic-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("clip-left.mp4");
+
+        write_atomically(&output, |partial| {
+            assert_ne!(partial, output.as_path());
+            std::fs::write(partial, b"video")?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), b"video");
+        assert!(!partial_path(&output).exists());
+
+        // A failed encode leaves neither the partial nor a new output
+        let failed = dir.join("clip-right.mp4");
+        let result: Result<()> = write_atomically(&failed, |partial| {
+            std::fs::write(partial, b"half")?;
+            Err(ObsCutterError::FfmpegFailed("interrupted".to_string()))
+        });
+        assert!(result.is_err());
+        assert!(!failed.exists());
+        assert!(!partial_path(&failed).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_side_output_chunks() {
+        let dir = std::env::temp_dir().join(format!("obs-cutter-chunks-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("clip-left.mp4");
+        let config = ProcessingConfig::new().with_segment_time(Some(3600.0));
+
+        let encode = |partial: &Path| {
+            let pattern = chunk_pattern(partial);
+            for number in 1..=2 {
+                let chunk = pattern
+                    .to_string_lossy()
+                    .replace("%03d", &format!("{:03}", number));
+                std::fs::write(chunk, b"video")?;
+            }
+            Ok(())
+        };
+
+        // A chunk left by an earlier run is neither mixed in nor deleted
+        let stale = dir.join("clip-left-003.mp4");
+        std::fs::write(&stale, b"stale").unwrap();
+        assert!(matches!(
+            write_side_output(&output, &config, encode),
+            Err(ObsCutterError::StaleChunks { existing, .. }) if existing == stale
+        ));
+        assert!(stale.exists());
+        assert!(chunk_paths(&partial_path(&output)).is_empty());
+        std::fs::remove_file(&stale).unwrap();
+
+        write_side_output(&output, &config, encode).unwrap();
+
+        assert_eq!(
+            side_output_files(&output, &config),
+            vec![dir.join("clip-left-001.mp4"), dir.join("clip-left-002.mp4")]
+        );
+        assert!(chunk_paths(&partial_path(&output)).is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_side_args_extra_args() {
+        let config =
+            ProcessingConfig::new().with_extra_args(vec!["-tune".to_string(), "film".to_string()]);
+        let args = side_args(
+            &sample_input(None),
+            Path::new("out.mov"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+        assert_eq!(args[args.len() - 4..], ["-tune", "film", "-y", "out.mov"]);
+    }
+
+    #[test]
+    fn test_side_args_downmix() {
+        let args = |config: &ProcessingConfig| {
+            side_args(
+                &sample_input(None),
+                Path::new("out.mp4"),
+                Side::Left,
+                config,
+                &HardwareEncoder::None,
+                None,
+            )
+            .join(" ")
+        };
+
+        let config = ProcessingConfig::new().with_downmix(true);
+        // Copied audio keeps its channels
+        assert!(!args(&config).contains("-ac "));
+        let aac = config.with_audio(AudioMode::Aac { bitrate: 192 });
+        assert!(args(&aac).contains("-b:a 192k -ac 2"));
+    }
+
+    #[test]
+    fn test_side_args_normalize_audio() {
+        let config = ProcessingConfig::new().with_normalize_audio(true);
+        let args = |config: &ProcessingConfig| {
+            side_args(
+                &sample_input(None),
+                Path::new("out.mp4"),
+                Side::Left,
+                config,
+                &HardwareEncoder::None,
+                None,
+            )
+        };
+
+        assert!(!args(&config).contains(&"-af".to_string()));
+        assert!(!args(&config).contains(&"-ar".to_string()));
+        let aac = args(&config.with_audio(AudioMode::Aac { bitrate: 192 }));
+        let filter = aac.iter().position(|arg| arg == "-af").unwrap();
+        assert_eq!(aac[filter + 1], "loudnorm=I=-16:TP=-1.5:LRA=11");
+        // loudnorm's 192 kHz output is resampled
+        let rate = aac.iter().position(|arg| arg == "-ar").unwrap();
+        assert_eq!(aac[rate + 1], "48000");
+    }
+
+    #[test]
+    fn test_side_args_segment_time() {
+        let config = ProcessingConfig::new().with_segment_time(Some(3600.0));
+        let args = side_args(
+            &sample_input(None),
+            Path::new("out/clip-left.partial.mp4"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+
+        let muxer = args.iter().position(|a| a == "segment").unwrap();
+        assert_eq!(args[muxer - 1], "-f");
+        assert_eq!(args[muxer + 1..muxer + 3], ["-segment_time", "3600"]);
+        assert!(args.contains(&"movflags=+faststart".to_string()));
+        assert!(!args.contains(&"-movflags".to_string()));
+        assert_eq!(args.last().unwrap(), "out/clip-left.partial-%03d.mp4");
+    }
+
+    #[test]
+    fn test_split_video_deletes_source_only_on_success() {
+        let dir = std::env::temp_dir().join(format!("obs-cutter-delete-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("clip.mp4");
+        let config = ProcessingConfig::new().with_delete_source_on_success(true);
+        let encoder = HardwareEncoder::None;
+
+        // A failed right side keeps the source
+        std::fs::write(&input, b"source").unwrap();
+        let result = split_video(
+            &input,
+            &dir,
+            &config,
+            &encoder,
+            |side, output, _, encoder| {
+                if side == Side::Right {
+                    return Err(ObsCutterError::FfmpegFailed("crashed".to_string()));
+                }
+                std::fs::write(output, b"side")?;
+                Ok(*encoder)
+            },
+        );
+        assert!(result.is_err());
+        assert!(input.exists());
+
+        let result = split_video(
+            &input,
+            &dir,
+            &config,
+            &encoder,
+            |side, output, _, encoder| {
+                // A slow left side shows up in its own timing
+                if side == Side::Left {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                std::fs::write(output, b"side")?;
+                Ok(*encoder)
+            },
+        )
+        .unwrap();
+        assert!(!input.exists());
+        assert_eq!(result.left_size, 4);
+        assert!(result.left_duration >= Duration::from_millis(50));
+        assert!(result.right_duration < result.left_duration);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_process_video_with_progress_reports_phases() {
+        let mut events = Vec::new();
+        let input = Path::new("missing-clip.mp4");
+        let result = process_video_with_progress(
+            input,
+            Path::new("."),
+            &ProcessingConfig::new(),
+            &HardwareEncoder::None,
+            2,
+            5,
+            |progress| events.push(progress),
+        );
+        assert!(result.is_err());
+
+        assert!(matches!(
+            events.first(),
+            Some(ProcessingProgress::Analyzing { video_index: 2, total: 5, path }) if path == input
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(ProcessingProgress::Failed {
+                video_index: 2,
+                total: 5,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_split_jobs_per_scene_segment() {
+        let config = ProcessingConfig::new();
+        let input = Path::new("clip.mp4");
+        let out = Path::new("out");
+
+        let jobs = split_jobs(input, out, &config, &[]).unwrap();
+        let outputs: Vec<_> = jobs.iter().map(|(_, output, _)| output.clone()).collect();
+        assert_eq!(
+            outputs,
+            [out.join("clip-left.mp4"), out.join("clip-right.mp4")]
+        );
+
+        let segments = scenes::scene_segments(&[10.0], Some(25.0));
+        let jobs = split_jobs(input, out, &config, &segments).unwrap();
+        assert_eq!(segments.len(), 2);
+        let expected = [
+            (Side::Left, "clip-left-001.mp4", segments[0]),
+            (Side::Left, "clip-left-002.mp4", segments[1]),
+            (Side::Right, "clip-right-001.mp4", segments[0]),
+            (Side::Right, "clip-right-002.mp4", segments[1]),
+        ];
+        assert_eq!(jobs.len(), expected.len());
+        for ((side, output, job_config), (expected_side, name, trim)) in jobs.iter().zip(expected) {
+            assert_eq!(*side, expected_side);
+            assert_eq!(*output, out.join(name));
+            assert_eq!(job_config.trim, Some(trim));
+        }
+    }
+
+    #[test]
+    fn test_split_video_container_fallback() {
+        let dir = std::env::temp_dir().join(format!("obs-cutter-mkv-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("clip.mp4");
+        std::fs::write(&input, b"source").unwrap();
+        let encode = |_: Side, output: &Path, _: &ProcessingConfig, encoder: &HardwareEncoder| {
+            if output.extension().is_some_and(|ext| ext == "mp4") {
+                return Err(ObsCutterError::FfmpegFailed(
+                    "Could not find tag for codec pcm_s16le in stream #1, \
+                     codec not currently supported in container"
+                        .to_string(),
+                ));
+            }
+            std::fs::write(output, b"side")?;
+            Ok(*encoder)
+        };
+
+        let config = ProcessingConfig::new();
+        assert!(split_video(&input, &dir, &config, &HardwareEncoder::None, encode).is_err());
+
+        let config = config.with_container_fallback(true);
+        let result = split_video(&input, &dir, &config, &HardwareEncoder::None, encode).unwrap();
+        assert_eq!(result.left_output, dir.join("clip-left.mkv"));
+        assert_eq!(result.right_output, dir.join("clip-right.mkv"));
+        assert_eq!(result.left_size, 4);
+        let fallback = &result.container_fallbacks[0];
+        assert_eq!(result.container_fallbacks.len(), 2);
+        assert_eq!(fallback.side, Side::Left);
+        assert_eq!(fallback.requested, dir.join("clip-left.mp4"));
+        assert!(fallback.reason.contains("codec not currently supported"));
+
+        // Other failures are not retried
+        let crashed = split_video(
+            &input,
+            &dir,
+            &config,
+            &HardwareEncoder::None,
+            |_, _, _, _| Err(ObsCutterError::FfmpegFailed("crashed".to_string())),
+        );
+        assert!(crashed.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_side_args_chapters() {
+        let input = sample_input(Some(ResolvedTrim {
+            start: 600.0,
+            length: Some(300.0),
+        }));
+        let chaptered = side_args(
+            &input,
+            Path::new("out.mkv"),
+            Side::Left,
+            &ProcessingConfig::new(),
+            &HardwareEncoder::None,
+            None,
+        )
+        .join(" ");
+        assert!(chaptered.contains("-map_chapters 0"));
+        // The trim start is an input option, so chapters are rebased on it
+        assert!(chaptered.find("-ss").unwrap() < chaptered.find("-i ").unwrap());
+
+        let plain = side_args(
+            &input,
+            Path::new("out.mkv"),
+            Side::Left,
+            &ProcessingConfig::new().with_preserve_chapters(false),
+            &HardwareEncoder::None,
+            None,
+        )
+        .join(" ");
+        assert!(plain.contains("-map_chapters -1"));
+    }
+
+    #[test]
+    fn test_check_container() {
+        let info = sample_info(None, None);
+        let config = ProcessingConfig::new();
+        let check = |output: &str, config: &ProcessingConfig| {
+            check_container(&info, Path::new(output), config, &HardwareEncoder::None)
+        };
+
+        assert!(check("out.mp4", &config).is_ok());
+        assert!(check("out.mkv", &config).is_ok());
+        assert!(matches!(
+            check("out.webm", &config),
+            Err(ObsCutterError::IncompatibleCodec { ref codec, .. }) if codec == "h264"
+        ));
+
+        // Copied PCM audio does not fit in MP4, but re-encoding to AAC does
+        let mut pcm = sample_info(None, None);
+        pcm.audio_codec = Some("pcm_s16le".to_string());
+        let mp4 = Path::new("out.mp4");
+        assert!(check_container(&pcm, mp4, &config, &HardwareEncoder::None).is_err());
+        let aac = config.clone().with_audio(AudioMode::Aac { bitrate: 192 });
+        assert!(check_container(&pcm, mp4, &aac, &HardwareEncoder::None).is_ok());
+    }
+
+    #[test]
+    fn test_escape_filter_path() {
+        assert_eq!(escape_filter_path(Path::new("subs.srt")), "subs.srt");
+        assert_eq!(
+            escape_filter_path(Path::new(r"C:\subs\it's.srt")),
+            r"C\\:\\\\subs\\\\it\\\'s.srt"
+        );
+        assert_eq!(
+            escape_filter_path(Path::new("/tmp/a,b [1].srt")),
+            r"/tmp/a\,b \[1\].srt"
+        );
+    }
+
+    #[test]
+    fn test_subtitles_only_on_chosen_side() {
+        let config = ProcessingConfig::new()
+            .with_burn_subtitles(Some(PathBuf::from("subs.srt")), Side::Right);
+        let input = sample_input(None);
+
+        assert_eq!(
+            video_filter(&input, Side::Right, &config, &HardwareEncoder::Vaapi),
+            "crop=1920:1080:1920:0,subtitles=subs.srt,format=nv12,hwupload"
+        );
+        assert_eq!(
+            video_filter(&input, Side::Left, &config, &HardwareEncoder::None),
+            "crop=1920:1080:0:0"
+        );
+    }
+
+    #[test]
+    fn test_side_args_stdout() {
+        let args = side_args(
+            &sample_input(None),
+            Path::new("-"),
+            Side::Left,
+            &ProcessingConfig::new(),
+            &HardwareEncoder::None,
+            None,
+        );
+
+        assert_eq!(&args[args.len() - 3..], ["-f", "matroska", "-"]);
+        assert!(!args.iter().any(|a| a == "-y"));
+    }
+
+    #[test]
+    fn test_side_args_metadata() {
+        let metadata = [("title".to_string(), "Left Feed".to_string())].into();
+        let config = ProcessingConfig::new()
+            .with_metadata(metadata)
+            .with_tag_side(true);
+        let args = side_args(
+            &sample_input(None),
+            Path::new("out.mov"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+
+        assert!(args.windows(2).any(|w| w == ["-map_metadata", "0"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-metadata", "title=Left Feed"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-metadata", "comment=obs-cutter left side"]));
+    }
+
+    #[test]
+    fn test_side_args_two_pass() {
+        let config = ProcessingConfig::new()
+            .with_target_bitrate(Some(8000))
+            .with_two_pass(true);
+        assert_eq!(pass_count(&config, &HardwareEncoder::None), 2);
+        assert_eq!(pass_count(&config, &HardwareEncoder::Nvenc), 1);
+
+        let log_prefix = Path::new("/tmp/passlog");
+        let pass = |number| {
+            side_args(
+                &sample_input(None),
+                Path::new("out.mov"),
+                Side::Left,
+                &config,
+                &HardwareEncoder::None,
+                Some(EncodePass { number, log_prefix }),
+            )
+        };
+
+        let first = pass(1);
+        assert!(first.windows(2).any(|w| w == ["-b:v", "8000k"]));
+        assert!(first.windows(2).any(|w| w == ["-pass", "1"]));
+        assert!(first.windows(2).any(|w| w == ["-f", "null"]));
+        assert!(!first.iter().any(|a| a == "out.mov"));
+
+        let second = pass(2);
+        assert!(second.windows(2).any(|w| w == ["-pass", "2"]));
+        assert_eq!(second.last().map(String::as_str), Some("out.mov"));
+    }
+
+    #[test]
+    fn test_span_passes() {
+        let log_prefix = Path::new("/tmp/passlog");
+        let progress = EncodingProgress {
+            current_time_secs: 30.0,
+            total_duration_secs: 60.0,
+            percentage: 50.0,
+            ..Default::default()
+        };
+
+        let first = span_passes(
+            progress.clone(),
+            Some(EncodePass {
+                number: 1,
+                log_prefix,
+            }),
+            2,
+        );
+        assert!((first.percentage - 25.0).abs() < 0.01);
+
+        let second = span_passes(
+            progress.clone(),
+            Some(EncodePass {
+                number: 2,
+                log_prefix,
+            }),
+            2,
+        );
+        assert!((second.percentage - 75.0).abs() < 0.01);
+
+        let single = span_passes(progress, None, 1);
+        assert!((single.percentage - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_frame_rate() {
+        assert_eq!(parse_frame_rate("60/1"), Some(60.0));
+        assert_eq!(parse_frame_rate("30"), Some(30.0));
+        assert!((parse_frame_rate("30000/1001").unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(parse_frame_rate("0/0"), None);
+        assert_eq!(parse_frame_rate("n/a"), None);
+    }
+
+    #[test]
+    fn test_cancelled_encode_does_not_run() {
+        let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let config = ProcessingConfig::new().with_cancel_flag(Some(cancel.clone()));
+        let mut runs = 0;
+        run_passes(&config, &HardwareEncoder::None, Side::Left, |_| {
+            runs += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(runs, 1);
+
+        cancel.store(true, std::sync::atomic::Ordering::SeqCst);
+        let result = run_passes(&config, &HardwareEncoder::None, Side::Left, |_| {
+            runs += 1;
+            Ok(())
+        });
+        assert!(matches!(result, Err(ObsCutterError::Cancelled)));
+        assert_eq!(runs, 1);
+    }
+
+    #[test]
+    fn test_side_args_target_fps() {
+        let args = |config: &ProcessingConfig| {
+            side_args(
+                &sample_input(None),
+                Path::new("out.mp4"),
+                Side::Left,
+                config,
+                &HardwareEncoder::None,
+                None,
+            )
+            .join(" ")
+        };
+
+        assert!(!args(&ProcessingConfig::new()).contains("-fps_mode"));
+        let config = ProcessingConfig::new().with_target_fps(Some(60.0));
+        assert!(args(&config).contains("-r 60 -fps_mode cfr"));
+        let config = ProcessingConfig::new().with_target_fps(Some(29.97));
+        assert!(args(&config).contains("-r 30000/1001 -fps_mode cfr"));
+
+        // FFmpeg before 5.1 only knows -vsync
+        let input = SideInput {
+            fps_mode: false,
+            ..sample_input(None)
+        };
+        let args = side_args(
+            &input,
+            Path::new("out.mp4"),
+            Side::Left,
+            &config,
+            &HardwareEncoder::None,
+            None,
+        );
+        assert!(args.join(" ").contains("-vsync cfr"));
+    }
+
+    #[test]
+    fn test_frame_rate_arg() {
+        assert_eq!(frame_rate_arg(60.0), "60");
+        assert_eq!(frame_rate_arg(25.0), "25");
+        assert_eq!(frame_rate_arg(29.97), "30000/1001");
+        assert_eq!(frame_rate_arg(59.94), "60000/1001");
+        assert_eq!(frame_rate_arg(23.976), "24000/1001");
+        assert_eq!(frame_rate_arg(12.5), "12.5");
+    }
+
+    #[test]
+    fn test_select_video_stream() {
+        let streams: Vec<StreamInfo> = serde_json::from_str(
+            r#"[
+                {"codec_name": "mjpeg", "codec_type": "video"},
+                {"codec_name": "aac", "codec_type": "audio"},
+                {"codec_name": "h264", "codec_type": "video", "width": 2560, "height": 1440},
+                {"codec_name": "h264", "codec_type": "video", "width": 1920, "height": 1080}
+            ]"#,
+        )
+        .unwrap();
+
+        // By default the first stream with a frame size is used
+        assert_eq!(
+            select_video_stream(&streams, None).unwrap().width,
+            Some(2560)
+        );
+        // An index counts video streams only
+        assert_eq!(
+            select_video_stream(&streams, Some(2)).unwrap().width,
+            Some(1920)
+        );
+        assert!(matches!(
+            select_video_stream(&streams, Some(3)),
+            Err(ObsCutterError::VideoStreamNotFound { index: 3, count: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_stream_maps() {
+        let input = sample_input(None);
+        assert!(stream_maps(&input, &ProcessingConfig::new()).is_empty());
+
+        let config = ProcessingConfig::new().with_video_stream(Some(1));
+        assert_eq!(
+            stream_maps(&input, &config),
+            ["-map", "0:v:1", "-map", "0:a:0?"]
+        );
+
+        let config = config.with_audio(AudioMode::None);
+        assert_eq!(stream_maps(&input, &config), ["-map", "0:v:1"]);
+    }
+
+    #[test]
+    fn test_stream_rotation() {
+        let stream = |json: &str| serde_json::from_str::<StreamInfo>(json).unwrap();
+
+        let rotated = stream(
+            r#"{"codec_name": "h264", "side_data_list": [
+                {"side_data_type": "Display Matrix", "rotation": -90}]}"#,
+        );
+        assert_eq!(rotated.rotation(), Some(270));
+
+        let tagged = stream(r#"{"codec_name": "h264", "tags": {"rotate": "90"}}"#);
+        assert_eq!(tagged.rotation(), Some(90));
+
+        let upright = stream(r#"{"codec_name": "h264", "tags": {"rotate": "0"}}"#);
+        assert_eq!(upright.rotation(), None);
+
+        let mut info = sample_info(None, None);
+        assert!(info.is_valid_dimensions());
+        info.rotation = Some(90);
+        assert_eq!(info.display_size(), (1080, 3840));
+        assert!(!info.is_valid_dimensions());
     }
 }
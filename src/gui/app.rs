@@ -1,19 +1,22 @@
 //! Main GUI application state and logic.
 
-use std::path::PathBuf;
-use std::sync::mpsc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use iced::widget::{
     button, center, column, container, horizontal_space, pick_list, progress_bar, radio, row,
-    scrollable, text, toggler, Space,
+    scrollable, text, toggler, Column, Row, Space,
 };
-use iced::{Alignment, Element, Fill, Length, Task, Theme};
+use iced::{Alignment, Element, Fill, Length, Subscription, Task, Theme};
 
 use crate::core::{
-    check_ffmpeg, detect_hardware_encoder, format_file_size, get_video_duration,
-    process_video_side_with_progress, HardwareEncoder, ProcessingResult, Quality, Side,
+    check_ffmpeg, detect_hardware_encoder, estimate_output_size, format_duration, format_file_size,
+    get_video_info, probe_all_encoders, resolve_output_dir, side_output_path, HardwareEncoder,
+    ProcessingConfig, ProcessingResult, Quality, Side, VideoInfo, SUPPORTED_EXTENSIONS,
 };
 use crate::gui::message::Message;
+use crate::gui::subscription::{processing_subscription, SideJob};
 use crate::gui::theme::{self, colors};
 
 /// Current screen in the application.
@@ -37,6 +40,23 @@ pub struct Settings {
     pub output_format: Option<String>,
     pub output_dir: Option<PathBuf>,
     pub use_hardware_accel: bool,
+    pub fallback_to_software: bool,
+    /// Explicitly chosen encoder; None uses the auto-detected one.
+    pub encoder: Option<HardwareEncoder>,
+    /// Which sides of each video are produced.
+    pub output_sides: OutputSides,
+}
+
+impl Settings {
+    /// Builds the core processing configuration from these settings.
+    pub fn processing_config(&self) -> ProcessingConfig {
+        ProcessingConfig::new()
+            .with_quality(self.quality)
+            .with_output_format(self.output_format.clone())
+            .with_output_dir(self.output_dir.clone())
+            .with_hardware_accel(self.use_hardware_accel)
+            .with_fallback_to_software(self.fallback_to_software)
+    }
 }
 
 impl Default for Settings {
@@ -46,8 +66,210 @@ impl Default for Settings {
             output_format: None,
             output_dir: None,
             use_hardware_accel: true,
+            fallback_to_software: true,
+            encoder: None,
+            output_sides: OutputSides::Both,
+        }
+    }
+}
+
+/// Which sides of each video the GUI produces, like the CLI's `--side`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputSides {
+    /// Both sides, left first.
+    #[default]
+    Both,
+    /// Only the left side.
+    Left,
+    /// Only the right side.
+    Right,
+}
+
+impl OutputSides {
+    /// Every choice, in the order shown in the settings.
+    pub const ALL: [OutputSides; 3] = [OutputSides::Both, OutputSides::Left, OutputSides::Right];
+
+    /// Returns the sides to encode, in order.
+    pub fn sides(&self) -> &'static [Side] {
+        match self {
+            OutputSides::Both => &Side::ALL,
+            OutputSides::Left => &[Side::Left],
+            OutputSides::Right => &[Side::Right],
+        }
+    }
+
+    /// Returns the side each video starts with.
+    pub fn first(&self) -> Side {
+        self.sides()[0]
+    }
+
+    /// Returns the side encoded after `side`, or None if it is the last.
+    pub fn next_after(&self, side: Side) -> Option<Side> {
+        let sides = self.sides();
+        let index = sides.iter().position(|s| *s == side)?;
+        sides.get(index + 1).copied()
+    }
+
+    /// Returns true if `side` is produced.
+    pub fn includes(&self, side: Side) -> bool {
+        self.sides().contains(&side)
+    }
+
+    /// Returns the label shown next to its radio button.
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputSides::Both => "Both sides",
+            OutputSides::Left => "Left only",
+            OutputSides::Right => "Right only",
+        }
+    }
+}
+
+/// Entry in the encoder picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderOption {
+    /// The chosen encoder, or None for "Auto".
+    pub encoder: Option<HardwareEncoder>,
+    /// The auto-detected encoder, shown next to "Auto".
+    pub detected: HardwareEncoder,
+}
+
+impl std::fmt::Display for EncoderOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.encoder {
+            Some(encoder) => write!(f, "{}", encoder.name()),
+            None => write!(f, "Auto ({})", self.detected.name()),
+        }
+    }
+}
+
+/// Shows `path` selected in the platform's file manager.
+///
+/// Linux file managers have no common way to select a file, so its folder
+/// is opened instead.
+fn reveal_in_file_manager(path: &Path) {
+    #[cfg(target_os = "macos")]
+    let _ = std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn();
+
+    #[cfg(target_os = "windows")]
+    let _ = std::process::Command::new("explorer")
+        .arg("/select,")
+        .arg(path)
+        .spawn();
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    if let Some(parent) = path.parent() {
+        let _ = open::that(parent);
+    }
+}
+
+/// Builds a small button that reveals the `side` output in the file manager.
+fn reveal_button<'a>(side: &str, path: &Path) -> Element<'a, Message> {
+    button(
+        text(format!("Show {}", side))
+            .size(12)
+            .color(colors::TEXT_PRIMARY),
+    )
+    .padding([6, 12])
+    .style(theme::secondary_button)
+    .on_press(Message::RevealFile(path.to_path_buf()))
+    .into()
+}
+
+/// Returns the capitalized preset name shown next to its radio button.
+fn quality_label(quality: Quality) -> &'static str {
+    match quality {
+        Quality::Lossless => "Lossless",
+        Quality::High => "High",
+        Quality::Medium => "Medium",
+        Quality::Fast => "Fast",
+    }
+}
+
+/// Status of a single video in the processing queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VideoStatus {
+    /// Waiting to be processed.
+    #[default]
+    Pending,
+    /// Currently being encoded.
+    Encoding,
+    /// Both sides were encoded successfully.
+    Completed,
+    /// Processing failed.
+    Failed,
+}
+
+/// Progress information for a single video in the batch.
+#[derive(Debug, Clone, Default)]
+pub struct VideoProgress {
+    pub status: VideoStatus,
+    /// Side currently being encoded.
+    pub side: Side,
+    /// Encoding progress of the current side (0.0 - 100.0).
+    pub percentage: f32,
+    /// Encoder in use; switches to software if a side fell back.
+    pub encoder: HardwareEncoder,
+    /// When encoding of this video started.
+    pub started_at: Option<Instant>,
+    /// Total time spent on this video, once finished.
+    pub elapsed: Option<Duration>,
+    /// When encoding of the current side started.
+    side_started_at: Option<Instant>,
+    /// Time spent encoding the left side, once finished.
+    pub left_elapsed: Duration,
+    /// Time spent encoding the right side, once finished.
+    pub right_elapsed: Duration,
+}
+
+impl VideoProgress {
+    /// Returns the overall progress of this video across the encoded
+    /// `sides` (0.0 - 1.0).
+    pub fn overall(&self, sides: &[Side]) -> f32 {
+        match self.status {
+            VideoStatus::Pending => 0.0,
+            VideoStatus::Completed => 1.0,
+            VideoStatus::Encoding | VideoStatus::Failed => {
+                let completed_sides = sides.iter().position(|s| *s == self.side).unwrap_or(0);
+                (completed_sides as f32 + self.percentage / 100.0) / sides.len().max(1) as f32
+            }
+        }
+    }
+
+    /// Marks this video as started on `side` with the given encoder.
+    fn start(&mut self, encoder: HardwareEncoder, side: Side) {
+        self.status = VideoStatus::Encoding;
+        self.side = side;
+        self.percentage = 0.0;
+        self.encoder = encoder;
+        self.started_at = Some(Instant::now());
+    }
+
+    /// Records that encoding of the current side starts now.
+    fn start_side(&mut self) {
+        self.side_started_at = Some(Instant::now());
+    }
+
+    /// Records the time spent on `side`, which just finished.
+    fn finish_side(&mut self, side: Side) {
+        let elapsed = self
+            .side_started_at
+            .take()
+            .map_or(Duration::ZERO, |t| t.elapsed());
+        match side {
+            Side::Left => self.left_elapsed = elapsed,
+            Side::Right => self.right_elapsed = elapsed,
         }
     }
+
+    /// Marks this video as finished with the given status.
+    fn finish(&mut self, status: VideoStatus) {
+        self.status = status;
+        self.elapsed = self.started_at.map(|t| t.elapsed());
+    }
 }
 
 /// Processing state information.
@@ -58,6 +280,12 @@ pub struct ProcessingState {
     pub current_side: Side,
     pub current_status: String,
     pub is_cancelled: bool,
+    /// Whether the queue is held; takes effect between sides or videos.
+    pub is_paused: bool,
+    /// Side to start when resuming, set once a pause has taken effect.
+    pub resume_side: Option<Side>,
+    /// Per-video progress, indexed like `App::videos`.
+    pub videos: Vec<VideoProgress>,
     // Encoding progress details
     pub encoding_percentage: f32,
     pub encoding_fps: f64,
@@ -65,18 +293,31 @@ pub struct ProcessingState {
     pub eta_secs: Option<f64>,
 }
 
+impl ProcessingState {
+    /// Returns the progress entry for the given video, if any.
+    fn video_mut(&mut self, index: usize) -> Option<&mut VideoProgress> {
+        self.videos.get_mut(index)
+    }
+}
+
 /// Main application state.
 #[derive(Default)]
 pub struct App {
     pub screen: Screen,
     pub videos: Vec<PathBuf>,
+    /// Probed information for the selected videos, keyed by path.
+    pub video_infos: HashMap<PathBuf, VideoInfo>,
     pub settings: Settings,
     pub processing_state: ProcessingState,
     pub results: Vec<ProcessingResult>,
     pub errors: Vec<(PathBuf, String)>,
     pub encoder: HardwareEncoder,
+    /// Every encoder available on this system, for the encoder picker.
+    pub available_encoders: Vec<HardwareEncoder>,
     pub ffmpeg_available: bool,
     pub ffmpeg_checked: bool,
+    /// Side currently being encoded, driving the processing subscription.
+    pub job: Option<SideJob>,
 }
 
 impl App {
@@ -92,7 +333,9 @@ impl App {
             Message::EncoderDetected,
         );
 
-        (app, Task::batch([ffmpeg_task, encoder_task]))
+        let encoders_task = Task::perform(async { probe_all_encoders() }, Message::EncodersProbed);
+
+        (app, Task::batch([ffmpeg_task, encoder_task, encoders_task]))
     }
 
     /// Get the window title.
@@ -110,6 +353,14 @@ impl App {
         Theme::Dark
     }
 
+    /// Subscribe to progress from the side currently being encoded.
+    pub fn subscription(&self) -> Subscription<Message> {
+        match &self.job {
+            Some(job) => processing_subscription(job.clone()),
+            None => Subscription::none(),
+        }
+    }
+
     /// Handle messages and update state.
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
@@ -127,7 +378,7 @@ impl App {
             Message::OpenFilePicker => Task::perform(
                 async {
                     let files = rfd::AsyncFileDialog::new()
-                        .add_filter("Video Files", &["mp4", "mov", "mkv", "avi", "webm"])
+                        .add_filter("Video Files", SUPPORTED_EXTENSIONS)
                         .set_title("Select Videos to Split")
                         .pick_files()
                         .await;
@@ -144,21 +395,51 @@ impl App {
                 Message::FilesSelected,
             ),
             Message::FilesSelected(paths) => {
+                let mut probes = Vec::new();
                 for path in paths {
                     if !self.videos.contains(&path) {
-                        self.videos.push(path);
+                        self.videos.push(path.clone());
+                        probes.push(Task::perform(
+                            async move {
+                                let info = get_video_info(&path).ok();
+                                (path, info)
+                            },
+                            |(path, info)| Message::VideoAnalyzed(path, info),
+                        ));
                     }
                 }
-                Task::none()
+                Task::batch(probes)
             }
             Message::RemoveFile(index) => {
                 if index < self.videos.len() {
-                    self.videos.remove(index);
+                    let path = self.videos.remove(index);
+                    self.video_infos.remove(&path);
+                }
+                Task::none()
+            }
+            Message::MoveFileUp(index) => {
+                if index > 0 && index < self.videos.len() {
+                    self.videos.swap(index - 1, index);
+                }
+                Task::none()
+            }
+            Message::MoveFileDown(index) => {
+                if index + 1 < self.videos.len() {
+                    self.videos.swap(index, index + 1);
                 }
                 Task::none()
             }
             Message::ClearFiles => {
                 self.videos.clear();
+                self.video_infos.clear();
+                Task::none()
+            }
+            Message::VideoAnalyzed(path, info) => {
+                if let Some(info) = info {
+                    if self.videos.contains(&path) {
+                        self.video_infos.insert(path, info);
+                    }
+                }
                 Task::none()
             }
 
@@ -167,6 +448,10 @@ impl App {
                 self.settings.quality = quality;
                 Task::none()
             }
+            Message::SetOutputSides(sides) => {
+                self.settings.output_sides = sides;
+                Task::none()
+            }
             Message::SetOutputFormat(format) => {
                 self.settings.output_format = format;
                 Task::none()
@@ -189,6 +474,10 @@ impl App {
                 self.settings.use_hardware_accel = enabled;
                 Task::none()
             }
+            Message::SetEncoder(encoder) => {
+                self.settings.encoder = encoder;
+                Task::none()
+            }
 
             // Processing
             Message::StartProcessing => {
@@ -198,23 +487,52 @@ impl App {
                 self.processing_state = ProcessingState {
                     current_video: 0,
                     total_videos: self.videos.len(),
-                    current_side: Side::Left,
+                    current_side: self.settings.output_sides.first(),
                     current_status: "Starting...".to_string(),
                     is_cancelled: false,
+                    is_paused: false,
+                    resume_side: None,
+                    videos: vec![VideoProgress::default(); self.videos.len()],
                     encoding_percentage: 0.0,
                     encoding_fps: 0.0,
                     encoding_speed: 0.0,
                     eta_secs: None,
                 };
 
-                // Start processing the first video (left side first)
+                // Start processing the first video
                 self.process_next_video()
             }
             Message::CancelProcessing => {
                 self.processing_state.is_cancelled = true;
+                self.job = None;
                 self.screen = Screen::FileSelection;
                 Task::none()
             }
+            Message::PauseProcessing => {
+                self.processing_state.is_paused = true;
+                self.processing_state.current_status =
+                    "Pausing after the current side finishes...".to_string();
+                Task::none()
+            }
+            Message::ResumeProcessing => {
+                self.processing_state.is_paused = false;
+                match self.processing_state.resume_side.take() {
+                    // Held between videos
+                    Some(side) if side == self.settings.output_sides.first() => {
+                        self.process_next_video()
+                    }
+                    // Held between the sides of a video
+                    Some(side) => {
+                        self.set_side_status(side);
+                        self.process_video_side(side)
+                    }
+                    // Pause never took effect; the current side is still encoding
+                    None => {
+                        self.set_side_status(self.processing_state.current_side);
+                        Task::none()
+                    }
+                }
+            }
             Message::VideoProcessed(result) => {
                 match result {
                     Ok(processing_result) => {
@@ -224,26 +542,22 @@ impl App {
                         if let Some(video) = self.videos.get(self.processing_state.current_video) {
                             self.errors.push((video.clone(), error));
                         }
+                        let index = self.processing_state.current_video;
+                        if let Some(progress) = self.processing_state.video_mut(index) {
+                            progress.finish(VideoStatus::Failed);
+                        }
                     }
                 }
 
                 self.processing_state.current_video += 1;
-
-                // Continue with next video or finish
-                if self.processing_state.current_video < self.processing_state.total_videos
-                    && !self.processing_state.is_cancelled
-                {
-                    self.process_next_video()
-                } else {
-                    Task::done(Message::ProcessingComplete)
-                }
+                self.continue_queue()
             }
             Message::ProcessingComplete => {
                 self.screen = Screen::Results;
                 Task::none()
             }
             Message::EncodingProgress {
-                video_index: _,
+                video_index,
                 side,
                 percentage,
                 fps,
@@ -251,6 +565,10 @@ impl App {
                 eta_secs,
             } => {
                 // Update encoding progress in real-time
+                if let Some(progress) = self.processing_state.video_mut(video_index) {
+                    progress.side = side;
+                    progress.percentage = percentage;
+                }
                 self.processing_state.current_side = side;
                 self.processing_state.encoding_percentage = percentage;
                 self.processing_state.encoding_fps = fps;
@@ -259,28 +577,36 @@ impl App {
                 Task::none()
             }
             Message::VideoSideProcessed {
-                video_index: _,
+                video_index,
                 side,
                 result,
             } => {
+                self.job = None;
                 match result {
-                    Ok(()) => {
-                        // Side completed successfully
-                        if side == Side::Left {
-                            // Left done, continue with right
-                            self.processing_state.current_side = Side::Right;
+                    Ok(encoder_used) => {
+                        // Side completed successfully; remember a software fallback
+                        if let Some(progress) = self.processing_state.video_mut(video_index) {
+                            progress.encoder = encoder_used;
+                            progress.finish_side(side);
+                        }
+
+                        if let Some(next) = self.settings.output_sides.next_after(side) {
+                            // Continue with the next side
+                            if let Some(progress) = self.processing_state.video_mut(video_index) {
+                                progress.side = next;
+                                progress.percentage = 0.0;
+                            }
+                            self.processing_state.current_side = next;
                             self.processing_state.encoding_percentage = 0.0;
-                            self.processing_state.current_status = format!(
-                                "Encoding right side of: {}",
-                                self.videos
-                                    .get(self.processing_state.current_video)
-                                    .and_then(|p| p.file_name())
-                                    .unwrap_or_default()
-                                    .to_string_lossy()
-                            );
-                            self.process_video_side(Side::Right)
+
+                            if self.processing_state.is_paused {
+                                self.hold_queue(next)
+                            } else {
+                                self.set_side_status(next);
+                                self.process_video_side(next)
+                            }
                         } else {
-                            // Right done, video complete - collect result
+                            // Last side done, video complete - collect result
                             self.collect_video_result()
                         }
                     }
@@ -289,15 +615,11 @@ impl App {
                         if let Some(video) = self.videos.get(self.processing_state.current_video) {
                             self.errors.push((video.clone(), error));
                         }
-                        self.processing_state.current_video += 1;
-
-                        if self.processing_state.current_video < self.processing_state.total_videos
-                            && !self.processing_state.is_cancelled
-                        {
-                            self.process_next_video()
-                        } else {
-                            Task::done(Message::ProcessingComplete)
+                        if let Some(progress) = self.processing_state.video_mut(video_index) {
+                            progress.finish(VideoStatus::Failed);
                         }
+                        self.processing_state.current_video += 1;
+                        self.continue_queue()
                     }
                 }
             }
@@ -313,8 +635,13 @@ impl App {
                 }
                 Task::none()
             }
+            Message::RevealFile(path) => {
+                reveal_in_file_manager(&path);
+                Task::none()
+            }
             Message::ProcessMore => {
                 self.videos.clear();
+                self.video_infos.clear();
                 self.results.clear();
                 self.errors.clear();
                 self.screen = Screen::FileSelection;
@@ -329,6 +656,10 @@ impl App {
                 self.encoder = encoder;
                 Task::none()
             }
+            Message::EncodersProbed(encoders) => {
+                self.available_encoders = encoders;
+                Task::none()
+            }
             Message::FfmpegChecked(available) => {
                 self.ffmpeg_available = available;
                 self.ffmpeg_checked = true;
@@ -343,179 +674,181 @@ impl App {
         }
     }
 
-    /// Process the next video in the queue (starts with left side).
+    /// Returns the encoder selected by the current settings.
+    ///
+    /// An explicit choice wins; otherwise the auto-detected encoder is used
+    /// unless hardware acceleration is disabled.
+    fn selected_encoder(&self) -> HardwareEncoder {
+        match self.settings.encoder {
+            Some(encoder) => encoder,
+            None if self.settings.use_hardware_accel => self.encoder,
+            None => HardwareEncoder::None,
+        }
+    }
+
+    /// Describes a result's combined output size, relative to the input when known.
+    fn total_size_label(&self, result: &ProcessingResult) -> String {
+        let total = format_file_size(result.total_size());
+        match self
+            .video_infos
+            .get(&result.input)
+            .and_then(|info| info.file_size)
+        {
+            Some(size) if size > 0 => {
+                format!(
+                    "Total: {} ({:.2}× original)",
+                    total,
+                    result.compression_ratio(size)
+                )
+            }
+            _ => format!("Total: {}", total),
+        }
+    }
+
+    /// Estimates the total output size per side for all selected videos.
+    ///
+    /// Returns None until every selected video has been probed and estimated.
+    fn estimated_size_per_side(&self) -> Option<u64> {
+        if self.videos.is_empty() {
+            return None;
+        }
+        let encoder = self.selected_encoder();
+        self.videos.iter().try_fold(0u64, |total, path| {
+            let info = self.video_infos.get(path)?;
+            estimate_output_size(info, self.settings.quality, &encoder).map(|size| total + size)
+        })
+    }
+
+    /// Continue with the next video, finish, or hold the queue if paused.
+    fn continue_queue(&mut self) -> Task<Message> {
+        if self.processing_state.current_video >= self.processing_state.total_videos
+            || self.processing_state.is_cancelled
+        {
+            Task::done(Message::ProcessingComplete)
+        } else if self.processing_state.is_paused {
+            self.hold_queue(self.settings.output_sides.first())
+        } else {
+            self.process_next_video()
+        }
+    }
+
+    /// Hold the queue until resumed, starting with `side` of the current video.
+    fn hold_queue(&mut self, side: Side) -> Task<Message> {
+        self.processing_state.resume_side = Some(side);
+        self.processing_state.current_status = "Paused".to_string();
+        Task::none()
+    }
+
+    /// Update the status line for encoding `side` of the current video.
+    fn set_side_status(&mut self, side: Side) {
+        self.processing_state.current_status = format!(
+            "Encoding {} side of: {}",
+            side,
+            self.videos
+                .get(self.processing_state.current_video)
+                .and_then(|p| p.file_name())
+                .unwrap_or_default()
+                .to_string_lossy()
+        );
+    }
+
+    /// Process the next video in the queue, starting with its first side.
     fn process_next_video(&mut self) -> Task<Message> {
         if self.processing_state.current_video >= self.videos.len() {
             return Task::done(Message::ProcessingComplete);
         }
 
-        let video = &self.videos[self.processing_state.current_video];
-        self.processing_state.current_side = Side::Left;
+        let index = self.processing_state.current_video;
+        let encoder = self.selected_encoder();
+        let side = self.settings.output_sides.first();
+        if let Some(progress) = self.processing_state.video_mut(index) {
+            progress.start(encoder, side);
+        }
+
+        self.processing_state.current_side = side;
         self.processing_state.encoding_percentage = 0.0;
-        self.processing_state.current_status = format!(
-            "Encoding left side of: {}",
-            video.file_name().unwrap_or_default().to_string_lossy()
-        );
+        self.set_side_status(side);
 
-        self.process_video_side(Side::Left)
+        self.process_video_side(side)
     }
 
     /// Process a specific side of the current video with real-time progress.
-    fn process_video_side(&self, side: Side) -> Task<Message> {
+    fn process_video_side(&mut self, side: Side) -> Task<Message> {
         let video_index = self.processing_state.current_video;
+        if let Some(progress) = self.processing_state.video_mut(video_index) {
+            progress.start_side();
+        }
         let video = self.videos[video_index].clone();
-        let quality = self.settings.quality;
-        let output_format = self.settings.output_format.clone();
+        let config = self.settings.processing_config();
         let output_dir = self.settings.output_dir.clone();
-        let encoder = if self.settings.use_hardware_accel {
-            self.encoder
-        } else {
-            HardwareEncoder::None
-        };
-
-        // Create a channel for progress updates
-        let (tx, rx) = mpsc::channel::<Message>();
-
-        // Spawn the processing task
-        let process_task = Task::perform(
-            async move {
-                // Determine output directory
-                let output_path = output_dir.unwrap_or_else(|| {
-                    video
-                        .parent()
-                        .unwrap_or(std::path::Path::new("."))
-                        .to_path_buf()
-                });
-
-                // Create output directory if needed
-                if !output_path.exists() {
-                    std::fs::create_dir_all(&output_path)
-                        .map_err(|e| format!("Failed to create output directory: {}", e))?;
-                }
-
-                // Prepare output path
-                let input_name = video
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("video");
-                let input_ext = video.extension().and_then(|s| s.to_str()).unwrap_or("mp4");
-                let ext = output_format.as_deref().unwrap_or(input_ext);
-                let side_suffix = if side == Side::Left { "left" } else { "right" };
-                let output_file =
-                    output_path.join(format!("{}-{}.{}", input_name, side_suffix, ext));
-
-                // Get video duration for progress calculation
-                let duration = get_video_duration(&video).ok();
-
-                // Process with progress callback
-                std::thread::spawn(move || {
-                    process_video_side_with_progress(
-                        &video,
-                        &output_file,
-                        side,
-                        quality,
-                        &encoder,
-                        duration,
-                        |progress| {
-                            let _ = tx.send(Message::EncodingProgress {
-                                video_index,
-                                side,
-                                percentage: progress.percentage,
-                                fps: progress.fps,
-                                speed: progress.speed,
-                                eta_secs: if progress.speed > 0.0
-                                    && progress.total_duration_secs > 0.0
-                                {
-                                    let remaining =
-                                        progress.total_duration_secs - progress.current_time_secs;
-                                    Some(remaining / progress.speed)
-                                } else {
-                                    None
-                                },
-                            });
-                        },
-                    )
-                })
-                .join()
-                .map_err(|_| "Thread panicked".to_string())?
-                .map_err(|e| e.to_string())
-            },
-            move |result| Message::VideoSideProcessed {
-                video_index,
-                side,
-                result,
-            },
-        );
-
-        // Create a stream of progress messages from the channel
-        let progress_stream = Task::run(
-            async_stream::stream! {
-                loop {
-                    match rx.try_recv() {
-                        Ok(msg) => yield msg,
-                        Err(mpsc::TryRecvError::Empty) => {
-                            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-                        }
-                        Err(mpsc::TryRecvError::Disconnected) => break,
-                    }
-                }
-            },
-            |msg| msg,
-        );
-
-        Task::batch([process_task, progress_stream])
+        let encoder = self
+            .processing_state
+            .videos
+            .get(video_index)
+            .map(|p| p.encoder)
+            .unwrap_or_else(|| self.selected_encoder());
+
+        // The subscription picks the job up and reports back through messages
+        self.job = Some(SideJob {
+            video_index,
+            video,
+            side,
+            output_dir,
+            config,
+            encoder,
+        });
+        Task::none()
     }
 
-    /// Collect the result after both sides are processed.
+    /// Collect the result after the chosen sides are processed.
+    ///
+    /// A side that was not produced is reported with a size of zero.
     fn collect_video_result(&mut self) -> Task<Message> {
         let video_index = self.processing_state.current_video;
         let video = self.videos[video_index].clone();
-        let output_format = self.settings.output_format.clone();
+        let config = self.settings.processing_config();
         let output_dir = self.settings.output_dir.clone();
-        let encoder = if self.settings.use_hardware_accel {
-            self.encoder
-        } else {
-            HardwareEncoder::None
-        };
-        let start_time = std::time::Instant::now();
-
-        // Advance to next video
-        self.processing_state.current_video += 1;
+        let output_sides = self.settings.output_sides;
+
+        let mut duration = Duration::ZERO;
+        let mut side_durations = (Duration::ZERO, Duration::ZERO);
+        let mut encoder = self.selected_encoder();
+        if let Some(progress) = self.processing_state.video_mut(video_index) {
+            progress.finish(VideoStatus::Completed);
+            duration = progress.elapsed.unwrap_or_default();
+            side_durations = (progress.left_elapsed, progress.right_elapsed);
+            encoder = progress.encoder;
+        }
 
         Task::perform(
             async move {
-                let output_path = output_dir.unwrap_or_else(|| {
-                    video
-                        .parent()
-                        .unwrap_or(std::path::Path::new("."))
-                        .to_path_buf()
-                });
-
-                let input_name = video
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("video");
-                let input_ext = video.extension().and_then(|s| s.to_str()).unwrap_or("mp4");
-                let ext = output_format.as_deref().unwrap_or(input_ext);
-
-                let left_output = output_path.join(format!("{}-left.{}", input_name, ext));
-                let right_output = output_path.join(format!("{}-right.{}", input_name, ext));
-
-                let left_size = std::fs::metadata(&left_output)
-                    .map(|m| m.len())
-                    .unwrap_or(0);
-                let right_size = std::fs::metadata(&right_output)
-                    .map(|m| m.len())
-                    .unwrap_or(0);
+                let output_path = resolve_output_dir(&video, output_dir.as_deref());
+                let left_output = side_output_path(&video, &output_path, Side::Left, &config)
+                    .map_err(|e| e.to_string())?;
+                let right_output = side_output_path(&video, &output_path, Side::Right, &config)
+                    .map_err(|e| e.to_string())?;
+
+                let size = |side: Side, output: &PathBuf| {
+                    std::fs::metadata(output)
+                        .ok()
+                        .filter(|_| output_sides.includes(side))
+                        .map_or(0, |m| m.len())
+                };
+                let left_size = size(Side::Left, &left_output);
+                let right_size = size(Side::Right, &right_output);
 
                 Ok(ProcessingResult {
                     input: video,
                     left_output,
                     right_output,
+                    segments: Vec::new(),
                     left_size,
                     right_size,
-                    duration: start_time.elapsed(),
+                    duration,
+                    left_duration: side_durations.0,
+                    right_duration: side_durations.1,
                     encoder_used: encoder,
+                    container_fallbacks: Vec::new(),
                 })
             },
             Message::VideoProcessed,
@@ -560,9 +893,12 @@ impl App {
                 .size(14)
                 .color(colors::TEXT_MUTED)
         } else if self.ffmpeg_available {
-            text(format!("Ready - Using encoder: {}", self.encoder.name()))
-                .size(14)
-                .color(colors::SUCCESS)
+            text(format!(
+                "Ready - Using encoder: {}",
+                self.selected_encoder().name()
+            ))
+            .size(14)
+            .color(colors::SUCCESS)
         } else {
             text("FFmpeg not found! Please check installation.")
                 .size(14)
@@ -638,23 +974,49 @@ impl App {
                     .map(|m| format_file_size(m.len()))
                     .unwrap_or_else(|_| "Unknown size".to_string());
 
+                let mut details = column![text(filename).size(14).color(colors::TEXT_PRIMARY)]
+                    .push(text(file_size).size(12).color(colors::TEXT_SECONDARY))
+                    .spacing(2)
+                    .width(Fill);
+                if let Some(info) = self.video_infos.get(path) {
+                    if let Some(rotation) = info.rotation {
+                        let (width, height) = info.display_size();
+                        details = details.push(
+                            text(format!(
+                                "Rotated {}° - displays as {}x{}, so the split may be wrong",
+                                rotation, width, height
+                            ))
+                            .size(12)
+                            .color(colors::WARNING),
+                        );
+                    }
+                }
+
                 let remove_btn = button(text("Remove").size(12).color(colors::TEXT_PRIMARY))
                     .padding([6, 12])
                     .style(theme::secondary_button)
                     .on_press(Message::RemoveFile(index));
 
+                // Queue order is processing order; the ends can't move further
+                let up_btn = button(text("↑").size(12).color(colors::TEXT_PRIMARY))
+                    .padding([6, 10])
+                    .style(theme::secondary_button)
+                    .on_press_maybe((index > 0).then_some(Message::MoveFileUp(index)));
+                let down_btn = button(text("↓").size(12).color(colors::TEXT_PRIMARY))
+                    .padding([6, 10])
+                    .style(theme::secondary_button)
+                    .on_press_maybe(
+                        (index + 1 < self.videos.len()).then_some(Message::MoveFileDown(index)),
+                    );
+
                 let file_row = container(
                     row![
                         text(format!("{}.", index + 1))
                             .size(14)
                             .color(colors::TEXT_MUTED)
                             .width(Length::Fixed(30.0)),
-                        column![
-                            text(filename).size(14).color(colors::TEXT_PRIMARY),
-                            text(file_size).size(12).color(colors::TEXT_SECONDARY),
-                        ]
-                        .spacing(2)
-                        .width(Fill),
+                        details,
+                        row![up_btn, down_btn].spacing(4),
                         remove_btn,
                     ]
                     .spacing(12)
@@ -716,36 +1078,47 @@ impl App {
 
         // Quality section
         let quality_title = text("Quality Preset").size(18).color(colors::TEXT_PRIMARY);
-        let quality_radios = column![
-            radio(
-                "Lossless - Largest files, best quality",
-                Quality::Lossless,
-                Some(self.settings.quality),
-                Message::SetQuality,
-            )
-            .size(18),
-            radio(
-                "High - Good balance of size and quality",
-                Quality::High,
-                Some(self.settings.quality),
-                Message::SetQuality,
-            )
-            .size(18),
+        let quality_radios = Column::with_children(Quality::all().iter().map(|quality| {
             radio(
-                "Medium - Smaller files, decent quality",
-                Quality::Medium,
+                format!("{} - {}", quality_label(*quality), quality.describe()),
+                *quality,
                 Some(self.settings.quality),
                 Message::SetQuality,
             )
-            .size(18),
-        ]
-        .spacing(12);
+            .size(18)
+            .into()
+        }))
+        .spacing(12)
+        .push_maybe(self.estimated_size_per_side().map(|bytes| {
+            text(format!("≈ {} per side", format_file_size(bytes)))
+                .size(13)
+                .color(colors::TEXT_SECONDARY)
+        }));
 
         let quality_section =
             container(column![quality_title, Space::with_height(12), quality_radios].padding(16))
                 .style(theme::card)
                 .width(Fill);
 
+        // Output sides section
+        let sides_title = text("Output").size(18).color(colors::TEXT_PRIMARY);
+        let sides_radios = Row::with_children(OutputSides::ALL.iter().map(|sides| {
+            radio(
+                sides.label(),
+                *sides,
+                Some(self.settings.output_sides),
+                Message::SetOutputSides,
+            )
+            .size(18)
+            .into()
+        }))
+        .spacing(24);
+
+        let sides_section =
+            container(column![sides_title, Space::with_height(12), sides_radios].padding(16))
+                .style(theme::card)
+                .width(Fill);
+
         // Output format section
         let format_title = text("Output Format").size(18).color(colors::TEXT_PRIMARY);
         let format_options = vec![
@@ -812,7 +1185,9 @@ impl App {
         let hw_title = text("Hardware Acceleration")
             .size(18)
             .color(colors::TEXT_PRIMARY);
-        let hw_status = if self.settings.use_hardware_accel {
+        let hw_status = if self.settings.encoder.is_some() {
+            format!("Using {}", self.selected_encoder().name())
+        } else if self.settings.use_hardware_accel {
             format!("Enabled - {}", self.encoder.name())
         } else {
             "Disabled - using software encoding".to_string()
@@ -827,9 +1202,42 @@ impl App {
         ]
         .align_y(Alignment::Center);
 
-        let hw_section = container(column![hw_title, Space::with_height(12), hw_row].padding(16))
-            .style(theme::card)
-            .width(Fill);
+        // Encoder picker: "Auto" followed by every available encoder
+        let detected = self.encoder;
+        let encoder_options: Vec<EncoderOption> = std::iter::once(None)
+            .chain(self.available_encoders.iter().copied().map(Some))
+            .map(|encoder| EncoderOption { encoder, detected })
+            .collect();
+        let current_encoder = EncoderOption {
+            encoder: self.settings.encoder,
+            detected,
+        };
+
+        let encoder_row = row![
+            text("Encoder").size(14).color(colors::TEXT_SECONDARY),
+            horizontal_space(),
+            pick_list(encoder_options, Some(current_encoder), |option| {
+                Message::SetEncoder(option.encoder)
+            })
+            .padding(10)
+            .width(Length::Fixed(240.0))
+            .style(theme::pick_list_style)
+            .menu_style(theme::pick_list_menu),
+        ]
+        .align_y(Alignment::Center);
+
+        let hw_section = container(
+            column![
+                hw_title,
+                Space::with_height(12),
+                hw_row,
+                Space::with_height(12),
+                encoder_row
+            ]
+            .padding(16),
+        )
+        .style(theme::card)
+        .width(Fill);
 
         // Note: Settings are saved automatically when changed
 
@@ -838,6 +1246,8 @@ impl App {
             Space::with_height(30),
             quality_section,
             Space::with_height(16),
+            sides_section,
+            Space::with_height(16),
             format_section,
             Space::with_height(16),
             dir_section,
@@ -865,15 +1275,15 @@ impl App {
             .color(colors::TEXT_SECONDARY);
 
         // Calculate overall progress:
-        // Each video has 2 sides (left + right), so 2 phases per video
-        let phases_per_video = 2.0_f32;
+        // Each chosen side of a video is one phase
+        let sides = self.settings.output_sides.sides();
+        let phases_per_video = sides.len() as f32;
         let total_phases = total as f32 * phases_per_video;
         let completed_phases = self.processing_state.current_video as f32 * phases_per_video
-            + if self.processing_state.current_side == Side::Right {
-                1.0
-            } else {
-                0.0
-            };
+            + sides
+                .iter()
+                .position(|s| *s == self.processing_state.current_side)
+                .unwrap_or(0) as f32;
         let current_phase_progress = self.processing_state.encoding_percentage / 100.0;
         let progress_value = if total_phases > 0.0 {
             (completed_phases + current_phase_progress) / total_phases
@@ -924,41 +1334,85 @@ impl App {
         ]
         .align_y(Alignment::Center);
 
-        // Completed videos list
-        let completed_content: Element<'_, Message> = if !self.results.is_empty() {
-            let mut completed_list = column![].spacing(8);
-            for result in &self.results {
-                let name = result
-                    .input
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy();
+        // Per-video progress list
+        let mut videos_list = column![].spacing(10);
+        for (index, path) in self.videos.iter().enumerate() {
+            let progress = self
+                .processing_state
+                .videos
+                .get(index)
+                .cloned()
+                .unwrap_or_default();
+
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            let (icon, icon_color) = match progress.status {
+                VideoStatus::Pending => ("•", colors::TEXT_MUTED),
+                VideoStatus::Encoding => ("▶", colors::PRIMARY),
+                VideoStatus::Completed => ("✓", colors::SUCCESS),
+                VideoStatus::Failed => ("✗", colors::DANGER),
+            };
+
+            let detail = match progress.status {
+                VideoStatus::Pending => "Pending".to_string(),
+                VideoStatus::Encoding => {
+                    format!("{} side {}%", progress.side, progress.percentage as u32)
+                }
+                VideoStatus::Completed => {
+                    let sizes = self
+                        .results
+                        .iter()
+                        .find(|r| &r.input == path)
+                        .map(|r| {
+                            format!(
+                                "{} | {}",
+                                format_file_size(r.left_size),
+                                format_file_size(r.right_size)
+                            )
+                        })
+                        .unwrap_or_default();
+                    let elapsed = progress.elapsed.map(format_duration).unwrap_or_default();
+                    format!("{}  {}", sizes, elapsed)
+                }
+                VideoStatus::Failed => format!(
+                    "Failed after {}",
+                    format_duration(progress.elapsed.unwrap_or_default())
+                ),
+            };
 
-                completed_list = completed_list.push(
+            videos_list = videos_list.push(
+                column![
                     row![
-                        text("✓").size(14).color(colors::SUCCESS),
+                        text(icon).size(14).color(icon_color),
                         Space::with_width(8),
                         text(name.to_string()).size(14).color(colors::TEXT_PRIMARY),
                         horizontal_space(),
-                        text(format!(
-                            "{} | {}",
-                            format_file_size(result.left_size),
-                            format_file_size(result.right_size)
-                        ))
-                        .size(12)
-                        .color(colors::TEXT_SECONDARY),
+                        text(detail).size(12).color(colors::TEXT_SECONDARY),
                     ]
                     .align_y(Alignment::Center),
-                );
-            }
+                    progress_bar(0.0..=1.0, progress.overall(sides))
+                        .height(6)
+                        .style(theme::progress),
+                ]
+                .spacing(4),
+            );
+        }
 
-            container(scrollable(completed_list).height(150))
-                .style(theme::card)
-                .padding(16)
-                .width(Fill)
-                .into()
+        let videos_content = container(scrollable(videos_list).height(180))
+            .style(theme::card)
+            .padding(16)
+            .width(Fill);
+
+        let pause_btn = if self.processing_state.is_paused {
+            button(text("Resume").size(14))
+                .padding([12, 32])
+                .style(theme::primary_button)
+                .on_press(Message::ResumeProcessing)
         } else {
-            Space::with_height(0).into()
+            button(text("Pause").size(14).color(colors::TEXT_PRIMARY))
+                .padding([12, 32])
+                .style(theme::secondary_button)
+                .on_press(Message::PauseProcessing)
         };
 
         let cancel_btn = button(text("Cancel").size(14))
@@ -966,6 +1420,8 @@ impl App {
             .style(theme::danger_button)
             .on_press(Message::CancelProcessing);
 
+        let actions = row![pause_btn, cancel_btn].spacing(16);
+
         center(
             column![
                 title,
@@ -980,17 +1436,11 @@ impl App {
                 Space::with_height(12),
                 stats_row,
                 Space::with_height(30),
-                if !self.results.is_empty() {
-                    column![
-                        text("Completed:").size(16).color(colors::TEXT_PRIMARY),
-                        Space::with_height(12),
-                        completed_content,
-                    ]
-                } else {
-                    column![]
-                },
+                text("Videos:").size(16).color(colors::TEXT_PRIMARY),
+                Space::with_height(12),
+                videos_content,
                 Space::with_height(30),
-                cancel_btn,
+                actions,
             ]
             .align_x(Alignment::Center)
             .max_width(500),
@@ -1029,23 +1479,65 @@ impl App {
                     .unwrap_or_default()
                     .to_string_lossy();
 
+                // Sizes and reveal buttons for the sides that were produced
+                let mut sizes = Row::new();
+                let mut reveal_buttons = row![].spacing(6);
+                for (side, label, size, output) in [
+                    (Side::Left, "Left", result.left_size, &result.left_output),
+                    (
+                        Side::Right,
+                        "Right",
+                        result.right_size,
+                        &result.right_output,
+                    ),
+                ] {
+                    if !self.settings.output_sides.includes(side) {
+                        continue;
+                    }
+                    sizes = sizes
+                        .push(
+                            text(format!("{}: {}", label, format_file_size(size)))
+                                .size(13)
+                                .color(colors::TEXT_SECONDARY),
+                        )
+                        .push(text("  |  ").size(13).color(colors::TEXT_MUTED));
+                    reveal_buttons = reveal_buttons.push(reveal_button(label, output));
+                }
+                let sizes = sizes.push(
+                    text(self.total_size_label(result))
+                        .size(13)
+                        .color(colors::TEXT_SECONDARY),
+                );
+
+                // Confirms whether hardware acceleration actually engaged
+                let encoded_with = if result.encoder_used != self.selected_encoder() {
+                    text(format!(
+                        "Fell back to {}",
+                        result.encoder_used.description()
+                    ))
+                    .size(12)
+                    .color(colors::WARNING)
+                } else {
+                    text(format!(
+                        "Encoded with {}",
+                        result.encoder_used.description()
+                    ))
+                    .size(12)
+                    .color(colors::TEXT_MUTED)
+                };
+
                 let result_row = container(
                     column![
                         row![
                             text("✓").size(16).color(colors::SUCCESS),
                             Space::with_width(8),
                             text(name.to_string()).size(15).color(colors::TEXT_PRIMARY),
+                            Space::with_width(Fill),
+                            reveal_buttons,
                         ]
                         .align_y(Alignment::Center),
-                        row![
-                            text(format!("Left: {}", format_file_size(result.left_size)))
-                                .size(13)
-                                .color(colors::TEXT_SECONDARY),
-                            text("  |  ").size(13).color(colors::TEXT_MUTED),
-                            text(format!("Right: {}", format_file_size(result.right_size)))
-                                .size(13)
-                                .color(colors::TEXT_SECONDARY),
-                        ],
+                        sizes,
+                        encoded_with,
                     ]
                     .spacing(4)
                     .padding(12),
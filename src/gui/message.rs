@@ -2,7 +2,8 @@
 
 use std::path::PathBuf;
 
-use crate::core::{HardwareEncoder, ProcessingResult, Quality, Side};
+use crate::core::{HardwareEncoder, ProcessingResult, Quality, Side, VideoInfo};
+use crate::gui::app::OutputSides;
 
 /// All possible messages in the GUI application.
 #[derive(Debug, Clone)]
@@ -20,12 +21,20 @@ pub enum Message {
     FilesSelected(Vec<PathBuf>),
     /// Remove a specific file from the list.
     RemoveFile(usize),
+    /// Move a file one place earlier in the processing order.
+    MoveFileUp(usize),
+    /// Move a file one place later in the processing order.
+    MoveFileDown(usize),
     /// Clear all selected files.
     ClearFiles,
+    /// A selected file has been probed (None if analysis failed).
+    VideoAnalyzed(PathBuf, Option<VideoInfo>),
 
     // Settings
     /// Change the quality preset.
     SetQuality(Quality),
+    /// Choose which sides are produced.
+    SetOutputSides(OutputSides),
     /// Change the output format.
     SetOutputFormat(Option<String>),
     /// Open the output directory picker.
@@ -34,17 +43,24 @@ pub enum Message {
     OutputDirSelected(Option<PathBuf>),
     /// Toggle hardware acceleration.
     ToggleHardwareAccel(bool),
+    /// Choose a specific encoder (None for automatic detection).
+    SetEncoder(Option<HardwareEncoder>),
 
     // Processing
     /// Start processing the selected videos.
     StartProcessing,
     /// Cancel the current processing.
     CancelProcessing,
+    /// Pause processing once the current side finishes.
+    PauseProcessing,
+    /// Resume paused processing.
+    ResumeProcessing,
     /// A video has been processed (one side complete).
+    /// On success, carries the encoder that was actually used.
     VideoSideProcessed {
         video_index: usize,
         side: Side,
-        result: Result<(), String>,
+        result: Result<HardwareEncoder, String>,
     },
     /// A full video has been processed (both sides complete).
     VideoProcessed(Result<ProcessingResult, String>),
@@ -63,6 +79,8 @@ pub enum Message {
     // Results
     /// Open the output directory in the file manager.
     OpenOutputDir,
+    /// Show an output file selected in the file manager.
+    RevealFile(PathBuf),
     /// Process more videos (go back to file selection).
     ProcessMore,
     /// Exit the application.
@@ -71,6 +89,8 @@ pub enum Message {
     // System
     /// Hardware encoder has been detected.
     EncoderDetected(HardwareEncoder),
+    /// All available encoders have been probed.
+    EncodersProbed(Vec<HardwareEncoder>),
     /// FFmpeg check result.
     FfmpegChecked(bool),
 
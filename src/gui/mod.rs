@@ -5,7 +5,9 @@
 
 pub mod app;
 pub mod message;
+pub mod subscription;
 pub mod theme;
 
 pub use app::App;
 pub use message::Message;
+pub use subscription::{processing_subscription, SideJob};
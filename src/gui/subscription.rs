@@ -0,0 +1,113 @@
+//! Event-driven progress updates for background encoding.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use iced::Subscription;
+use tokio::sync::mpsc;
+
+use crate::core::{
+    get_video_duration, humanize_ffmpeg_error, process_side_with_progress, resolve_output_dir,
+    side_output_path, HardwareEncoder, ObsCutterError, ProcessingConfig, Side, SideProgress,
+};
+use crate::gui::message::Message;
+
+/// One side of one video to encode in the background.
+#[derive(Debug, Clone)]
+pub struct SideJob {
+    /// Index of the video in the queue, echoed back in every message.
+    pub video_index: usize,
+    pub video: PathBuf,
+    pub side: Side,
+    pub output_dir: Option<PathBuf>,
+    pub config: ProcessingConfig,
+    pub encoder: HardwareEncoder,
+}
+
+/// Encodes `job` on a worker thread and reports its progress.
+///
+/// Emits [`Message::EncodingProgress`] as FFmpeg reports progress, then a
+/// single [`Message::VideoSideProcessed`] once the side is done. Messages
+/// are delivered as soon as they are sent and none are dropped when the
+/// worker finishes.
+///
+/// The subscription is keyed by the video and side, so it keeps running
+/// for as long as the application returns it for the same job.
+pub fn processing_subscription(job: SideJob) -> Subscription<Message> {
+    let id = (job.video_index, job.side, job.video.clone());
+
+    Subscription::run_with_id(
+        id,
+        async_stream::stream! {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            std::thread::spawn(move || run_job(job, tx));
+
+            // Yields until the worker drops its sender, after its last message
+            while let Some(message) = rx.recv().await {
+                yield message;
+            }
+        },
+    )
+}
+
+/// Encodes one side, sending progress and then the result over `tx`.
+fn run_job(job: SideJob, tx: mpsc::UnboundedSender<Message>) {
+    let SideJob {
+        video_index,
+        video,
+        side,
+        output_dir,
+        config,
+        encoder,
+    } = job;
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        // Prepare output path; its directory is created when encoding
+        let output_path = resolve_output_dir(&video, output_dir.as_deref());
+        let output_file =
+            side_output_path(&video, &output_path, side, &config).map_err(|e| e.to_string())?;
+
+        // Get video duration for progress calculation
+        let duration = get_video_duration(&video).ok();
+
+        process_side_with_progress(
+            &video,
+            &output_file,
+            side,
+            &config,
+            &encoder,
+            duration,
+            |progress| {
+                // Retries aren't enabled in the GUI
+                let SideProgress::Encoding(progress) = progress else {
+                    return;
+                };
+                let _ = tx.send(Message::EncodingProgress {
+                    video_index,
+                    side,
+                    percentage: progress.percentage,
+                    fps: progress.fps,
+                    speed: progress.speed,
+                    eta_secs: progress.eta_secs(),
+                });
+            },
+        )
+        .map_err(|e| match e {
+            ObsCutterError::EncoderUnavailable(_) | ObsCutterError::ResolutionTooLarge { .. } => {
+                format!(
+                    "{}. Turn off hardware acceleration to encode in software.",
+                    e
+                )
+            }
+            ObsCutterError::FfmpegFailed(stderr) => humanize_ffmpeg_error(&stderr),
+            e => e.to_string(),
+        })
+    }))
+    .unwrap_or_else(|_| Err("Thread panicked".to_string()));
+
+    let _ = tx.send(Message::VideoSideProcessed {
+        video_index,
+        side,
+        result,
+    });
+}
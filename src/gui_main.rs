@@ -10,6 +10,7 @@ use obs_cutter::gui::App;
 fn main() -> iced::Result {
     application("OBS-Cutter", App::update, App::view)
         .theme(App::theme)
+        .subscription(App::subscription)
         .window_size(Size::new(600.0, 700.0))
         .run_with(App::new)
 }
@@ -14,7 +14,7 @@
 //! ```no_run
 //! use obs_cutter::core::{
 //!     ProcessingConfig, Quality, detect_hardware_encoder,
-//!     get_video_info, process_video, check_ffmpeg,
+//!     get_video_info, process_video_with_config, check_ffmpeg,
 //! };
 //! use std::path::Path;
 //!
@@ -29,14 +29,11 @@
 //! let info = get_video_info(video_path).expect("Failed to analyze video");
 //!
 //! // Process video
-//! let output_dir = Path::new("./output");
-//! let result = process_video(
-//!     video_path,
-//!     output_dir,
-//!     None, // Use input format
-//!     Quality::High,
-//!     &encoder,
-//! ).expect("Failed to process video");
+//! let config = ProcessingConfig::new()
+//!     .with_quality(Quality::High)
+//!     .with_output_dir(Some("./output".into()));
+//! let result = process_video_with_config(video_path, &config, &encoder)
+//!     .expect("Failed to process video");
 //!
 //! println!("Left output: {:?}", result.left_output);
 //! println!("Right output: {:?}", result.right_output);
@@ -1,86 +1,598 @@
 //! obs-cutter CLI - Split 32:9 OBS recordings into two 16:9 videos.
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
 
 use obs_cutter::core::{
-    check_ffmpeg, detect_hardware_encoder, format_file_size, get_video_info, process_video,
-    HardwareEncoder, Quality,
+    batch_output_dir, benchmark, check_disk_space, check_ffmpeg, check_ffprobe, detect_black_bars,
+    detect_hardware_encoder, encode_with_container_fallback, encoder_env_error,
+    estimate_output_size, format_duration, format_file_size, get_ffmpeg_path,
+    get_ffmpeg_version_parsed, get_ffprobe_path, get_input_info, get_video_info, has_filter,
+    humanize_ffmpeg_error, is_media_file, is_side_output_name, is_stdout_output,
+    is_supported_extension, join_videos_with_layout, output_part_number, parse_dimensions,
+    parse_fps, parse_scene_threshold, parse_timestamp, probe_all_encoders, process_side,
+    process_side_with_progress, process_video_with_progress, read_input_list, remux,
+    resolve_output_dir, side_output_files, side_output_path, supports_feature, test_encode,
+    verify_side_output, wait_for_stable, AudioMode, BatchJournal, ColorRange, ContainerFallback,
+    CropRect, EncodingProgress, FfmpegFeature, FitMode, HardwareEncoder, JoinLayout,
+    ObsCutterError, ProcessingConfig, ProcessingProgress, ProcessingResult, Quality, Side,
+    SideProgress, Trim, VideoInfo, ENCODER_ENV, FORCE_SOFTWARE_ENV, JOURNAL_FILE_NAME,
 };
 
+/// Set by `--progress json`: progress is emitted as JSON lines on stdout.
+static JSON_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// Set when stdout carries JSON progress or piped video; human-readable
+/// output then moves to stderr.
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// Set by `--quiet`: decorative output is suppressed and only failures are
+/// reported, on stderr.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Prints human-readable output, to stderr when stdout is reserved.
+/// Suppressed by `--quiet`.
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if QUIET.load(Ordering::Relaxed) {
+        } else if STDOUT_RESERVED.load(Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// Prints a failure. Unlike [`status!`] this is kept under `--quiet`,
+/// where it goes to stderr.
+macro_rules! failure {
+    ($($arg:tt)*) => {
+        if QUIET.load(Ordering::Relaxed) || STDOUT_RESERVED.load(Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
 #[derive(Parser)]
 #[command(name = "obs-cutter")]
 #[command(version = "2.0.0")]
 #[command(about = "Split 32:9 OBS recordings into two separate 16:9 videos", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path(s) to video file(s) to split
-    #[arg(value_name = "VIDEO", required = true, num_args = 1..)]
+    #[arg(
+        value_name = "VIDEO",
+        required_unless_present_any = ["watch", "from_file"],
+        num_args = 1..
+    )]
     videos: Vec<PathBuf>,
 
+    /// Read more video paths from FILE, one per line (`#` starts a comment)
+    #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+    from_file: Option<PathBuf>,
+
+    /// Wait for each video to stop growing before splitting it, e.g. when OBS
+    /// may still be writing it (always on with --watch)
+    #[arg(long, conflicts_with = "watch")]
+    wait_stable: bool,
+
+    /// Watch a directory and split new recordings as they appear
+    #[arg(long, value_name = "DIR", conflicts_with = "videos")]
+    watch: Option<PathBuf>,
+
+    /// Print video metadata and exit without encoding
+    #[arg(long, conflicts_with = "watch")]
+    info: bool,
+
+    /// Only change the container to --format, copying streams without cropping
+    #[arg(long, requires = "format", conflicts_with_all = ["watch", "info"])]
+    remux_only: bool,
+
     /// Output format (defaults to input format)
     #[arg(short, long, value_name = "FORMAT")]
     format: Option<String>,
 
-    /// Quality preset (lossless/high/medium)
+    /// Output format for inputs with a given extension (repeatable), e.g. --format-map mkv=mp4
+    #[arg(long, value_name = "FROM=TO", conflicts_with = "format")]
+    format_map: Vec<String>,
+
+    /// Write MKV instead when the output format can't hold the streams (e.g. PCM audio in MP4)
+    #[arg(long)]
+    container_fallback: bool,
+
+    /// Quality preset (lossless/high/medium/fast; aliases: best, archive, balanced, small)
     #[arg(short, long, value_name = "QUALITY", default_value = "lossless")]
     quality: String,
 
-    /// Output directory (defaults to input directory)
+    /// Quality preset for the left side, overriding --quality
+    #[arg(long, value_name = "QUALITY")]
+    left_quality: Option<String>,
+
+    /// Quality preset for the right side, overriding --quality
+    #[arg(long, value_name = "QUALITY")]
+    right_quality: Option<String>,
+
+    /// Output directory (defaults to input directory); `-` writes one side to stdout.
+    /// {date} or {date:%Y-%m-%d} expand to each input's capture date (UTC)
     #[arg(short, long, value_name = "DIR")]
     output: Option<PathBuf>,
 
+    /// Only produce this side (left/right); required when piping with `-o -`
+    #[arg(long, value_name = "SIDE")]
+    side: Option<String>,
+
+    /// Container format used when piping to stdout
+    #[arg(long, value_name = "FORMAT", default_value = "matroska")]
+    pipe_format: String,
+
     /// Disable hardware acceleration (force software encoding)
     #[arg(long)]
     no_hw_accel: bool,
 
+    /// Force a specific encoder (videotoolbox/nvenc/qsv/amf/vaapi/software), skipping detection
+    #[arg(long, value_name = "ENCODER", conflicts_with = "no_hw_accel")]
+    encoder: Option<String>,
+
     /// Continue processing remaining videos on error
     #[arg(long)]
     continue_on_error: bool,
+
+    /// Skip videos an interrupted run of this batch already split (tracked in the output directory)
+    #[arg(long, conflicts_with_all = ["watch", "side"])]
+    resume: bool,
+
+    /// Encoder preset instead of the quality preset's (e.g. p1-p7 for nvenc, ultrafast-placebo for software)
+    #[arg(long, value_name = "PRESET")]
+    preset: Option<String>,
+
+    /// Retry a failed encode up to N times (useful for flaky hardware encoders)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    retries: u8,
+
+    /// Retry with software encoding if the hardware encoder fails
+    #[arg(long)]
+    fallback_to_software: bool,
+
+    /// Probe each output after encoding and reject truncated files
+    #[arg(long)]
+    verify: bool,
+
+    /// Target video bitrate in kbit/s for software encoding (instead of CRF)
+    #[arg(long, value_name = "KBPS")]
+    bitrate: Option<u32>,
+
+    /// Encode in two passes to hit --bitrate precisely (software encoding only)
+    #[arg(long, requires = "bitrate")]
+    two_pass: bool,
+
+    /// Add a metadata tag to both outputs (repeatable), e.g. --metadata title="Left Feed"
+    #[arg(long, value_name = "KEY=VALUE")]
+    metadata: Vec<String>,
+
+    /// Record which side each output is in its comment tag
+    #[arg(long)]
+    tag_side: bool,
+
+    /// Output file name template using {stem}, {side} and {date} or {date:FORMAT} (capture time, UTC)
+    #[arg(long, value_name = "TEMPLATE")]
+    name: Option<String>,
+
+    /// Put each input's outputs in their own subdirectory ({stem}/left.ext, {stem}/right.ext)
+    #[arg(long)]
+    subdir: bool,
+
+    /// FFmpeg threads for decoding and software encoding. Encodes running in
+    /// parallel use jobs × threads in total, so lower it to avoid oversubscribing
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    threads: Option<u32>,
+
+    /// Split the Nth video stream (counting from 0) of files with several, e.g. screen and camera
+    #[arg(long, value_name = "N")]
+    video_stream: Option<usize>,
+
+    /// Crop as if frames were WIDTHxHEIGHT, ignoring the probed size (for broken metadata)
+    #[arg(long, value_name = "WxH", value_parser = parse_dimensions)]
+    input_dimensions: Option<(u32, u32)>,
+
+    /// Crop away black bars around the picture before splitting, e.g. 16:9
+    /// content pillarboxed into a 32:9 canvas
+    #[arg(long, conflicts_with = "input_dimensions")]
+    auto_crop: bool,
+
+    /// Force a constant frame rate, re-timing variable frame rate recordings (fixes A/V drift)
+    #[arg(long, value_name = "FPS", value_parser = parse_fps)]
+    fps: Option<f64>,
+
+    /// Cut each side into numbered segments at scene changes scoring above THRESHOLD (0-1, e.g. 0.4)
+    #[arg(
+        long,
+        value_name = "THRESHOLD",
+        value_parser = parse_scene_threshold,
+        conflicts_with_all = ["side", "start", "end", "duration"]
+    )]
+    segment_scenes: Option<f64>,
+
+    /// Cut each side into numbered chunks of this length (e.g. 3600 or 01:00:00) for huge recordings
+    #[arg(long, value_name = "TIME", conflicts_with = "segment_scenes")]
+    segment_time: Option<String>,
+
+    /// Force FFmpeg's input demuxer (e.g. h264 for raw elementary streams from capture software)
+    #[arg(long, value_name = "FORMAT")]
+    input_format: Option<String>,
+
+    /// Pass ARG to FFmpeg right before each output path (repeatable, e.g.
+    /// --ffmpeg-arg -tune --ffmpeg-arg film). Unchecked: it can override or break the encode
+    #[arg(long = "ffmpeg-arg", value_name = "ARG", allow_hyphen_values = true)]
+    ffmpeg_args: Vec<String>,
+
+    /// Scale sides taller than PIXELS down to this height; smaller inputs are left native
+    #[arg(long, value_name = "PIXELS", value_parser = clap::value_parser!(u32).range(2..))]
+    max_height: Option<u32>,
+
+    /// Scale sides wider than PIXELS down to this width; smaller inputs are left native
+    #[arg(long, value_name = "PIXELS", value_parser = clap::value_parser!(u32).range(2..))]
+    max_width: Option<u32>,
+
+    /// Pad sides that aren't 16:9 with black bars to a 16:9 frame instead of keeping their own shape
+    #[arg(long)]
+    pad: bool,
+
+    /// Fail on inputs that are not 32:9 (e.g. 3840x1080) instead of warning and splitting them anyway
+    #[arg(long)]
+    strict_dimensions: bool,
+
+    /// Output color range (full/limited); defaults to the source's range
+    #[arg(long, value_name = "RANGE")]
+    color_range: Option<String>,
+
+    /// With NVENC, decode and crop on the GPU too (needs FFmpeg's crop_cuda filter)
+    #[arg(long)]
+    gpu_pipeline: bool,
+
+    /// Fail instead of warning when the outputs may not fit on disk
+    #[arg(long)]
+    strict: bool,
+
+    /// Delete each input after both sides are written (and verified, with --verify)
+    #[arg(long, conflicts_with_all = ["side", "remux_only", "info"])]
+    delete_source: bool,
+
+    /// Skip the confirmation prompt for --delete-source
+    #[arg(short, long, requires = "delete_source")]
+    yes: bool,
+
+    /// Do not copy chapter markers into the outputs
+    #[arg(long)]
+    no_chapters: bool,
+
+    /// Leave the index at the end of MP4/MOV outputs (skips the quick pass that makes them web-friendly)
+    #[arg(long)]
+    no_faststart: bool,
+
+    /// Burn the subtitles from this file (e.g. .srt) into one side
+    #[arg(long, value_name = "FILE")]
+    burn_subs: Option<PathBuf>,
+
+    /// Side that receives burned-in subtitles (left/right)
+    #[arg(
+        long,
+        value_name = "SIDE",
+        default_value = "left",
+        requires = "burn_subs"
+    )]
+    burn_subs_side: String,
+
+    /// Start encoding at this position (HH:MM:SS[.mmm], MM:SS or seconds)
+    #[arg(long, value_name = "TIME")]
+    start: Option<String>,
+
+    /// Stop at this position; a negative value counts back from the end (e.g. -00:00:30)
+    #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+    end: Option<String>,
+
+    /// Encode this much from the start position (cannot be combined with --end)
+    #[arg(long, value_name = "TIME", conflicts_with = "end")]
+    duration: Option<String>,
+
+    /// Quickly encode only the first TIME of each video to try out settings,
+    /// writing {stem}-left-sample.ext and {stem}-right-sample.ext
+    #[arg(
+        long,
+        value_name = "TIME",
+        conflicts_with_all = ["start", "end", "duration", "segment_scenes", "segment_time"]
+    )]
+    sample: Option<String>,
+
+    /// Only report failures (on stderr); rely on the exit code for success
+    #[arg(long, conflicts_with = "info")]
+    quiet: bool,
+
+    /// Emit machine-readable progress to stdout (json: one object per line)
+    #[arg(long, value_name = "FORMAT", value_parser = ["json"])]
+    progress: Option<String>,
+
+    /// Audio handling (copy/aac/none)
+    #[arg(long, value_name = "MODE", default_value = "copy")]
+    audio: String,
+
+    /// Audio bitrate in kbit/s when re-encoding to AAC
+    #[arg(long, value_name = "KBPS", default_value_t = AudioMode::DEFAULT_AAC_BITRATE)]
+    audio_bitrate: u32,
+
+    /// Downmix re-encoded audio to this channel layout (stereo); needs --audio aac
+    #[arg(long, value_name = "LAYOUT", value_parser = ["stereo"])]
+    downmix: Option<String>,
+
+    /// Normalize loudness to EBU R128 (-16 LUFS); re-encodes copied audio to AAC
+    #[arg(long)]
+    normalize_audio: bool,
+
+    /// Keep the audio track tagged with this language (e.g. eng) instead of the default one
+    #[arg(long, value_name = "LANG")]
+    audio_lang: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Stack two videos back into one, the inverse of a split
+    Join {
+        /// Video placed on the left (or on top with --vertical)
+        left: PathBuf,
+
+        /// Video placed on the right (or below with --vertical)
+        right: PathBuf,
+
+        /// Output file
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+
+        /// Stack the videos vertically instead of side by side
+        #[arg(long)]
+        vertical: bool,
+    },
+
+    /// Encode a short clip with each available encoder and compare them
+    Benchmark {
+        /// Recording to take the clip from
+        sample: PathBuf,
+
+        /// Length of the clip in seconds
+        #[arg(long, value_name = "SECS", default_value_t = 10.0)]
+        duration: f64,
+    },
+
+    /// Check that FFmpeg, FFprobe and the encoders work, with hints for fixing them
+    Doctor {
+        /// Test software encoding instead of the detected hardware encoder
+        #[arg(long)]
+        no_hw_accel: bool,
+
+        /// Test a specific encoder (videotoolbox/nvenc/qsv/amf/vaapi/software)
+        #[arg(long, value_name = "ENCODER", conflicts_with = "no_hw_accel")]
+        encoder: Option<String>,
+    },
+}
+
+/// A progress tick as emitted by `--progress json`.
+#[derive(Serialize)]
+struct JsonProgress<'a> {
+    video_index: usize,
+    side: &'static str,
+    #[serde(flatten)]
+    progress: &'a EncodingProgress,
+    eta_secs: Option<f64>,
+}
+
+/// Writes one JSON progress line to stdout.
+fn print_json_progress(video_index: usize, side: Side, progress: &EncodingProgress) {
+    let line = JsonProgress {
+        video_index,
+        side: side.as_str(),
+        progress,
+        eta_secs: progress.eta_secs(),
+    };
+    if let Ok(json) = serde_json::to_string(&line) {
+        println!("{}", json);
+    }
+}
+
+/// A video skipped by `--resume`, as emitted by `--progress json`.
+#[derive(Serialize)]
+struct JsonSkipped<'a> {
+    video_index: usize,
+    skipped: bool,
+    path: &'a Path,
+}
+
+/// Reports a batch event that happens outside an encode. Only
+/// [`ProcessingProgress::Skipped`] is sent this way; the others come from
+/// [`process_video_with_progress`].
+fn report_batch_event(event: &ProcessingProgress) {
+    let ProcessingProgress::Skipped {
+        video_index, path, ..
+    } = event
+    else {
+        return;
+    };
+    status!(
+        "{} Skipping {} (completed in an earlier run)",
+        "ℹ".blue(),
+        path.display()
+    );
+    if JSON_PROGRESS.load(Ordering::Relaxed) {
+        let line = JsonSkipped {
+            video_index: *video_index,
+            skipped: true,
+            path,
+        };
+        if let Ok(json) = serde_json::to_string(&line) {
+            println!("{}", json);
+        }
+    }
 }
 
 /// Result of processing a single video in the batch.
 struct BatchResult {
     path: PathBuf,
     success: bool,
-    left_size: Option<u64>,
-    right_size: Option<u64>,
+    output: Option<BatchOutput>,
+    input_size: Option<u64>,
     error: Option<String>,
+    fell_back: bool,
+    /// Length of video encoded, for the speed in the summary.
+    video_duration: Option<f64>,
+}
+
+/// Outputs produced for one video.
+enum BatchOutput {
+    /// Both sides were split.
+    Split(ProcessingResult),
+    /// Only one side was produced (`--side`).
+    Single { side: Side, size: u64 },
+}
+
+impl BatchOutput {
+    fn total_size(&self) -> u64 {
+        match self {
+            BatchOutput::Split(result) => result.total_size(),
+            BatchOutput::Single { size, .. } => *size,
+        }
+    }
+
+    fn compression_ratio(&self, input_size: u64) -> f32 {
+        match self {
+            BatchOutput::Split(result) => result.compression_ratio(input_size),
+            BatchOutput::Single { size, .. } => (*size as f64 / input_size as f64) as f32,
+        }
+    }
+
+    /// Lists the output sizes, e.g. "Left: 1.2 GB, Right: 1.1 GB".
+    fn sizes(&self) -> String {
+        match self {
+            BatchOutput::Split(result) if !result.segments.is_empty() => format!(
+                "Left: {}, Right: {} ({} segments per side)",
+                format_file_size(result.left_size),
+                format_file_size(result.right_size),
+                result.segments.len() / 2
+            ),
+            BatchOutput::Split(result) => format!(
+                "Left: {}, Right: {}",
+                format_file_size(result.left_size),
+                format_file_size(result.right_size)
+            ),
+            BatchOutput::Single { side, size } => {
+                format!("{}: {}", side_label(*side), format_file_size(*size))
+            }
+        }
+    }
+}
+
+/// Returns the capitalized side name for display.
+fn side_label(side: Side) -> &'static str {
+    match side {
+        Side::Left => "Left",
+        Side::Right => "Right",
+    }
+}
+
+/// Creates a spinner showing `message`, or None under `--quiet`.
+fn spinner(message: String) -> Option<ProgressBar> {
+    if QUIET.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    spinner.set_message(message);
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    Some(spinner)
+}
+
+/// Stops `spinner`, if any, leaving `message` in its place.
+fn finish_spinner(spinner: Option<ProgressBar>, message: String) {
+    if let Some(spinner) = spinner {
+        spinner.finish_with_message(message);
+    }
+}
+
+/// Creates a determinate (0-100%) progress bar for encoding `side`.
+fn side_progress_bar(prefix: &str, side: Side) -> ProgressBar {
+    let bar = ProgressBar::new(100);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{prefix}[{bar:30.green/white}] {pos:>3}% {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    bar.set_prefix(format!("{}{:<6}", prefix, side_label(side)));
+    bar
 }
 
 fn print_header() {
-    println!("\n{}", "OBS-Cutter - Video Splitter".cyan());
-    println!("{}\n", "===========================".cyan());
+    status!("\n{}", "OBS-Cutter - Video Splitter".cyan());
+    status!("{}\n", "===========================".cyan());
 }
 
 fn print_ffmpeg_install_help() {
-    println!("\n{}", "To install FFmpeg on macOS:".yellow());
-    println!("  {}", "brew install ffmpeg".white());
-    println!("\n{}", "On Ubuntu/Debian:".yellow());
-    println!("  {}", "sudo apt-get install ffmpeg".white());
-    println!("\n{}", "On Windows:".yellow());
-    println!(
+    status!("\n{}", "To install FFmpeg on macOS:".yellow());
+    status!("  {}", "brew install ffmpeg".white());
+    status!("\n{}", "On Ubuntu/Debian:".yellow());
+    status!("  {}", "sudo apt-get install ffmpeg".white());
+    status!("\n{}", "On Windows:".yellow());
+    status!(
         "  {}",
         "Download from https://ffmpeg.org/download.html".white()
     );
 }
 
-fn setup_encoder(no_hw_accel: bool) -> HardwareEncoder {
-    if no_hw_accel {
-        println!("{} Hardware acceleration disabled by user\n", "ℹ".blue());
+fn setup_encoder(no_hw_accel: bool, forced: Option<HardwareEncoder>) -> HardwareEncoder {
+    if let Some(encoder) = forced {
+        status!(
+            "{} Using encoder: {} (forced)\n",
+            "ℹ".blue(),
+            encoder.name()
+        );
+        encoder
+    } else if no_hw_accel {
+        status!("{} Hardware acceleration disabled by user\n", "ℹ".blue());
         HardwareEncoder::None
     } else {
+        if let Some(e) = encoder_env_error() {
+            status!(
+                "{} {} is ignored: {}\n",
+                "Warning:".yellow(),
+                ENCODER_ENV,
+                e
+            );
+        }
         let detected = detect_hardware_encoder();
         if detected == HardwareEncoder::None {
-            println!(
+            status!(
                 "{} No hardware encoder detected, using software encoding\n",
                 "ℹ".blue()
             );
         } else {
-            println!(
+            status!(
                 "{} Using hardware encoder: {}\n",
                 "✓".green(),
                 detected.name()
@@ -90,177 +602,753 @@ fn setup_encoder(no_hw_accel: bool) -> HardwareEncoder {
     }
 }
 
+/// Position of the current video within a batch, used for the batch ETA.
+struct BatchClock {
+    /// When the batch started.
+    started: Instant,
+    /// Index of the current video.
+    index: usize,
+    /// Number of videos in the batch.
+    total: usize,
+}
+
+impl BatchClock {
+    /// A batch consisting of just one video, starting now.
+    fn single() -> Self {
+        Self {
+            started: Instant::now(),
+            index: 0,
+            total: 1,
+        }
+    }
+
+    /// Estimates the time left for the whole batch, given that the current
+    /// video started at `video_started` and is `fraction` (0-1) done.
+    ///
+    /// Remaining videos are assumed to take as long as the average finished
+    /// one, or as the current one when none have finished yet.
+    fn eta(&self, video_started: Instant, fraction: f64) -> Option<Duration> {
+        if fraction <= 0.0 {
+            return None;
+        }
+
+        let video_elapsed = video_started.elapsed().as_secs_f64();
+        let video_total = video_elapsed / fraction.min(1.0);
+        let per_video = if self.index > 0 {
+            video_started.duration_since(self.started).as_secs_f64() / self.index as f64
+        } else {
+            video_total
+        };
+        let videos_after = self.total.saturating_sub(self.index + 1);
+
+        Some(Duration::from_secs_f64(
+            video_total - video_elapsed + per_video * videos_after as f64,
+        ))
+    }
+}
+
 fn process_single_video(
     video_path: &Path,
-    output_dir: &Path,
-    format: Option<&str>,
-    quality: &Quality,
+    output_dir: Option<&Path>,
+    config: &ProcessingConfig,
     encoder: &HardwareEncoder,
-    video_index: usize,
-    total_videos: usize,
+    only_side: Option<Side>,
+    auto_crop: bool,
+    batch: &BatchClock,
 ) -> BatchResult {
-    let prefix = if total_videos > 1 {
-        format!("[{}/{}] ", video_index + 1, total_videos)
+    let video_index = batch.index;
+    let prefix = if batch.total > 1 {
+        format!("[{}/{}] ", video_index + 1, batch.total)
     } else {
         String::new()
     };
 
     // Check if video file exists
     if !video_path.exists() {
-        eprintln!(
-            "{}{} {}",
-            prefix,
-            "Error: Video file not found:".red(),
-            video_path.display()
-        );
+        // Reported by the summary under --quiet
+        if !QUIET.load(Ordering::Relaxed) {
+            eprintln!(
+                "{}{} {}",
+                prefix,
+                "Error: Video file not found:".red(),
+                video_path.display()
+            );
+        }
         return BatchResult {
             path: video_path.to_path_buf(),
             success: false,
-            left_size: None,
-            right_size: None,
+            output: None,
+            input_size: None,
             error: Some("File not found".to_string()),
+            fell_back: false,
+            video_duration: None,
+        };
+    }
+
+    // Catch non-video files before FFprobe errors get confusing
+    if !is_media_file(video_path) {
+        let error = ObsCutterError::NotMediaFile(video_path.to_path_buf());
+        if !QUIET.load(Ordering::Relaxed) {
+            eprintln!("{}{} {}", prefix, "Error:".red(), error);
+        }
+        return BatchResult {
+            path: video_path.to_path_buf(),
+            success: false,
+            output: None,
+            input_size: None,
+            error: Some(error.to_string()),
+            fell_back: false,
+            video_duration: None,
         };
     }
 
     // Get video information
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message(format!("{}Analyzing video...", prefix));
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let analyzing = spinner(format!("{}Analyzing video...", prefix));
 
-    let video_info = match get_video_info(video_path) {
+    let video_info = match get_input_info(video_path, config) {
         Ok(info) => info,
         Err(e) => {
-            spinner.finish_with_message(format!(
-                "{}{} Failed to analyze: {}",
-                prefix,
-                "✗".red(),
-                e
-            ));
+            finish_spinner(
+                analyzing,
+                format!("{}{} Failed to analyze: {}", prefix, "✗".red(), e),
+            );
             return BatchResult {
                 path: video_path.to_path_buf(),
                 success: false,
-                left_size: None,
-                right_size: None,
+                output: None,
+                input_size: None,
                 error: Some(e.to_string()),
+                fell_back: false,
+                video_duration: None,
             };
         }
     };
 
-    spinner.finish_with_message(format!(
-        "{}{} Video analyzed: {}x{}",
-        prefix,
-        "✓".green(),
-        video_info.width,
-        video_info.height
-    ));
+    finish_spinner(
+        analyzing,
+        format!(
+            "{}{} Video analyzed: {}x{}",
+            prefix,
+            "✓".green(),
+            video_info.width,
+            video_info.height
+        ),
+    );
 
-    // Validate video dimensions
-    if !video_info.is_valid_dimensions() {
-        println!(
+    // Validate video dimensions, unless the user overrides them
+    if let Some((width, height)) = config.override_dimensions {
+        status!(
+            "{}{} Cropping as {}x{} (--input-dimensions)",
+            prefix,
+            "ℹ".blue(),
+            width,
+            height
+        );
+    } else if !video_info.is_valid_dimensions() {
+        let (display_width, display_height) = video_info.display_size();
+        status!(
             "\n{}{} Video dimensions are {}x{}",
             prefix,
             "Warning:".yellow(),
-            video_info.width,
-            video_info.height
+            display_width,
+            display_height
         );
-        println!(
+        if let Some(rotation) = video_info.rotation {
+            status!(
+                "{}{} The stream is {}x{} but carries a {}° rotation tag",
+                prefix,
+                "Warning:".yellow(),
+                video_info.width,
+                video_info.height,
+                rotation
+            );
+        }
+        status!(
             "{}{} Expected: 3840x1080 (32:9 aspect ratio)",
             prefix,
             "Warning:".yellow()
         );
-        println!(
+        status!(
             "{}{} The output might not be as expected.\n",
             prefix,
             "Warning:".yellow()
         );
     }
 
+    // A capture with bars would put them into the outputs
+    let cropped_config;
+    let content = if auto_crop {
+        detect_content(video_path, config)
+    } else {
+        Ok(None)
+    };
+    let config = match content {
+        Err(e) => {
+            failure!("{}{} {}", prefix, "✗".red(), e);
+            return BatchResult {
+                path: video_path.to_path_buf(),
+                success: false,
+                output: None,
+                input_size: None,
+                error: Some(e.to_string()),
+                fell_back: false,
+                video_duration: None,
+            };
+        }
+        Ok(Some(content)) => {
+            status!(
+                "{}{} Cropping to the {}x{} picture at {},{} (--auto-crop)",
+                prefix,
+                "ℹ".blue(),
+                content.width,
+                content.height,
+                content.x,
+                content.y
+            );
+            cropped_config = config.clone().with_content_crop(Some(content));
+            &cropped_config
+        }
+        Ok(None) => config,
+    };
+
+    if let Some(language) = &config.audio_language {
+        if config.audio != AudioMode::None && video_info.audio_track(language).is_none() {
+            status!(
+                "{}{} No audio track is tagged '{}'; using the default track",
+                prefix,
+                "Warning:".yellow(),
+                language
+            );
+        }
+    }
+
     // Prepare output directory
-    let input_dir = video_path.parent().unwrap_or(Path::new("."));
-    let actual_output_dir = output_dir.parent().map(|_| output_dir).unwrap_or(input_dir);
+    let actual_output_dir = resolve_output_dir(video_path, output_dir);
 
     // Create output directory if it doesn't exist
     if !actual_output_dir.exists() {
-        if let Err(e) = fs::create_dir_all(actual_output_dir) {
+        if let Err(e) = fs::create_dir_all(&actual_output_dir) {
             return BatchResult {
                 path: video_path.to_path_buf(),
                 success: false,
-                left_size: None,
-                right_size: None,
+                output: None,
+                input_size: None,
                 error: Some(format!("Failed to create output directory: {}", e)),
+                fell_back: false,
+                video_duration: None,
             };
         }
     }
 
     // Print configuration for this video
     let input_name = video_path.file_name().unwrap().to_string_lossy();
-    println!("{}Processing: {}", prefix, input_name.white());
+    status!("{}Processing: {}", prefix, input_name.white());
 
-    // Process left video
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
-    spinner.set_message(format!("{}Extracting left video...", prefix));
-    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let json_progress = JSON_PROGRESS.load(Ordering::Relaxed);
+    let quiet = QUIET.load(Ordering::Relaxed);
+    let video_started = Instant::now();
+    let mut side_bar: Option<(Side, ProgressBar)> = None;
+    let mut on_progress = |side: Side, event: SideProgress| {
+        let progress = match event {
+            SideProgress::Encoding(progress) => progress,
+            SideProgress::Retrying(retry) => {
+                let message = format!(
+                    "{}{} FFmpeg failed on the {} side (attempt {}/{}), retrying: {}",
+                    prefix,
+                    "Warning:".yellow(),
+                    side,
+                    retry.attempt,
+                    retry.attempts,
+                    retry.error
+                );
+                match &side_bar {
+                    Some((_, bar)) => bar.suspend(|| status!("{}", message)),
+                    None => status!("{}", message),
+                }
+                return;
+            }
+        };
+        if json_progress {
+            print_json_progress(video_index, side, &progress);
+        }
+        if quiet {
+            return;
+        }
+
+        // Each side gets its own bar, replacing the previous side's
+        if side_bar
+            .as_ref()
+            .is_none_or(|(bar_side, _)| *bar_side != side)
+        {
+            if let Some((_, done)) = side_bar.take() {
+                done.finish_and_clear();
+            }
+            side_bar = Some((side, side_progress_bar(&prefix, side)));
+        }
+        let Some((_, bar)) = &side_bar else {
+            return;
+        };
+
+        // The right side is encoded after the left one
+        let (sides_done, sides) = match only_side {
+            Some(_) => (0.0, 1.0),
+            None if side == Side::Right => (1.0, 2.0),
+            None => (0.0, 2.0),
+        };
+        let fraction = (sides_done + f64::from(progress.percentage) / 100.0) / sides;
+
+        let mut details = vec![
+            format!("{:.1} fps", progress.fps),
+            format!("{:.2}x", progress.speed),
+        ];
+        if let Some(eta) = progress.eta_secs() {
+            details.push(format!(
+                "ETA {}",
+                format_duration(Duration::from_secs_f64(eta))
+            ));
+        }
+        if let Some(eta) = batch.eta(video_started, fraction) {
+            let label = if batch.total > 1 {
+                "Batch ETA"
+            } else {
+                "Total ETA"
+            };
+            details.push(format!("{} ~{}", label, format_duration(eta)));
+        }
+
+        bar.set_position(f64::from(progress.percentage).round() as u64);
+        bar.set_message(details.join(" | "));
+    };
 
-    let result = process_video(video_path, actual_output_dir, format, *quality, encoder);
+    let result = match only_side {
+        Some(side) => encode_single_side(
+            video_path,
+            &actual_output_dir,
+            side,
+            config,
+            encoder,
+            |progress| on_progress(side, progress),
+        ),
+        None => process_video_with_progress(
+            video_path,
+            &actual_output_dir,
+            config,
+            encoder,
+            video_index,
+            batch.total,
+            |event| match event {
+                ProcessingProgress::Encoding { side, progress, .. } => {
+                    on_progress(side, SideProgress::Encoding(progress))
+                }
+                ProcessingProgress::Retrying { side, retry, .. } => {
+                    on_progress(side, SideProgress::Retrying(retry))
+                }
+                _ => {}
+            },
+        )
+        .map(|r| {
+            let fallbacks = r.container_fallbacks.clone();
+            (r.encoder_used, BatchOutput::Split(r), fallbacks)
+        }),
+    };
+    if let Some((_, bar)) = side_bar {
+        bar.finish_and_clear();
+    }
 
     match result {
-        Ok(processing_result) => {
-            spinner.finish_with_message(format!(
-                "{}{} Split complete: {} | {}",
+        Ok((encoder_used, output, container_fallbacks)) => {
+            for fallback in &container_fallbacks {
+                status!(
+                    "{}{} The {} side doesn't fit {} ({}); wrote {} instead",
+                    prefix,
+                    "Warning:".yellow(),
+                    fallback.side,
+                    fallback.requested.display(),
+                    fallback.reason,
+                    fallback.output.display()
+                );
+            }
+            let fell_back = encoder_used != *encoder;
+            status!(
+                "{}{} {} complete: {}{}",
                 prefix,
                 "✓".green(),
-                format_file_size(processing_result.left_size),
-                format_file_size(processing_result.right_size)
-            ));
+                if only_side.is_some() { "Crop" } else { "Split" },
+                output.sizes(),
+                if fell_back {
+                    format!(" ({} failed, fell back to software)", encoder.name())
+                        .yellow()
+                        .to_string()
+                } else {
+                    String::new()
+                }
+            );
+            status!(
+                "{}{} Encoded with {}",
+                prefix,
+                "ℹ".blue(),
+                encoder_used.description()
+            );
+            if config.delete_source_on_success && only_side.is_none() {
+                status!("{}{} Deleted source: {}", prefix, "✓".green(), input_name);
+            }
 
             BatchResult {
                 path: video_path.to_path_buf(),
                 success: true,
-                left_size: Some(processing_result.left_size),
-                right_size: Some(processing_result.right_size),
+                input_size: video_info.file_size,
+                output: Some(output),
                 error: None,
+                fell_back,
+                video_duration: encoded_duration(&video_info, config),
             }
         }
         Err(e) => {
-            spinner.finish_with_message(format!("{}{} Failed: {}", prefix, "✗".red(), e));
-            BatchResult {
+            let reason = match &e {
+                ObsCutterError::FfmpegFailed(stderr) => humanize_ffmpeg_error(stderr),
+                e => e.to_string(),
+            };
+            status!("{}{} Failed: {}", prefix, "✗".red(), reason);
+            // Keep FFmpeg's own output for diagnosing the failure
+            if let ObsCutterError::FfmpegFailed(stderr) = &e {
+                for line in stderr.lines() {
+                    status!("{}    {}", prefix, line.bright_black());
+                }
+            }
+            if matches!(
+                e,
+                ObsCutterError::EncoderUnavailable(_) | ObsCutterError::ResolutionTooLarge { .. }
+            ) {
+                status!(
+                    "{}  Use --no-hw-accel to encode in software, or --fallback-to-software to retry in software automatically",
+                    prefix
+                );
+            }
+            BatchResult {
                 path: video_path.to_path_buf(),
                 success: false,
-                left_size: None,
-                right_size: None,
-                error: Some(e.to_string()),
+                output: None,
+                input_size: None,
+                error: Some(reason),
+                fell_back: false,
+                video_duration: None,
+            }
+        }
+    }
+}
+
+/// Fails if two inputs would write to the same output file, e.g. `a.mov`
+/// and `a.mkv` with `--format mp4`, or same-named inputs from different
+/// directories sharing one `--output`.
+///
+/// Scene segments only append a number to an output's name, so two inputs'
+/// segments can only clash when their unsegmented outputs do.
+fn check_output_collisions(
+    videos: &[PathBuf],
+    output_dir: Option<&Path>,
+    config: &ProcessingConfig,
+    side: Side,
+) -> Result<()> {
+    let mut claimed: HashMap<PathBuf, &Path> = HashMap::new();
+    for video_path in videos {
+        let dir = resolve_output_dir(video_path, output_dir);
+        let Ok(output) = side_output_path(video_path, &dir, side, config) else {
+            continue;
+        };
+        let previous = claimed.insert(output.clone(), video_path);
+        if let Some(other) = previous.filter(|other| *other != video_path.as_path()) {
+            anyhow::bail!(
+                "{} and {} would both write {}; rename one of them",
+                other.display(),
+                video_path.display(),
+                output.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fails if an output would overwrite one of the inputs, e.g. when re-running
+/// over a directory that already holds `foo-left.mp4` from an earlier run,
+/// or `foo-left-001.mp4` when splitting into scene segments or chunks. All
+/// collisions are listed.
+fn check_input_overwrites(
+    videos: &[PathBuf],
+    output_dir: Option<&Path>,
+    config: &ProcessingConfig,
+    sides: &[Side],
+) -> Result<()> {
+    let inputs: Vec<PathBuf> = videos.iter().map(|v| comparable_path(v)).collect();
+    // Segments and chunks are written as numbered files next to the output
+    let numbered = config.segment_scenes.is_some() || config.segment_time.is_some();
+    let mut collisions = Vec::new();
+    for video_path in videos {
+        let dir = resolve_output_dir(video_path, output_dir);
+        for side in sides {
+            let Ok(output) = side_output_path(video_path, &dir, *side, config) else {
+                continue;
+            };
+            let output = comparable_path(&output);
+            for input in &inputs {
+                if *input == output || (numbered && output_part_number(&output, input).is_some()) {
+                    collisions.push(format!(
+                        "  {} ({} side of {})",
+                        input.display(),
+                        side,
+                        video_path.display()
+                    ));
+                }
             }
         }
     }
+
+    if !collisions.is_empty() {
+        anyhow::bail!(
+            "Refusing to overwrite input files:\n{}\nRemove earlier outputs from the inputs or choose another --output",
+            collisions.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Returns `path` with its directory resolved, so different spellings of
+/// the same file compare equal even if the file doesn't exist yet.
+fn comparable_path(path: &Path) -> PathBuf {
+    if let Ok(path) = fs::canonicalize(path) {
+        return path;
+    }
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty());
+    match (dir.map(fs::canonicalize), path.file_name()) {
+        (Some(Ok(dir)), Some(name)) => dir.join(name),
+        (None, Some(name)) => std::env::current_dir()
+            .map(|cwd| cwd.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Estimates the batch's total output size per output directory and checks
+/// each against the free space there. Videos whose size cannot be estimated
+/// are skipped.
+fn check_batch_disk_space(
+    videos: &[PathBuf],
+    output_dir: Option<&Path>,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    sides: &[Side],
+) -> obs_cutter::core::Result<()> {
+    let mut required: HashMap<PathBuf, u64> = HashMap::new();
+    for video_path in videos {
+        let Ok(info) = get_input_info(video_path, config) else {
+            continue;
+        };
+        let size: Option<u64> = sides
+            .iter()
+            .map(|side| estimate_output_size(&info, config.quality_for(*side), encoder))
+            .sum();
+        if let Some(size) = size {
+            *required
+                .entry(resolve_output_dir(video_path, output_dir))
+                .or_default() += size;
+        }
+    }
+
+    for (dir, bytes) in required {
+        check_disk_space(&dir, bytes)?;
+    }
+    Ok(())
 }
 
-fn print_summary(results: &[BatchResult]) {
+/// How often the watcher checks pending files.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a file's size must stay unchanged before it is processed.
+const WATCH_STABLE_FOR: Duration = Duration::from_secs(5);
+
+/// How long `--wait-stable` waits for a video to stop growing.
+const WAIT_STABLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Looks for black bars around the picture, unless `--input-dimensions`
+/// overrides the frame size.
+fn detect_content(
+    video_path: &Path,
+    config: &ProcessingConfig,
+) -> obs_cutter::core::Result<Option<CropRect>> {
+    if config.override_dimensions.is_some() {
+        return Ok(None);
+    }
+    detect_black_bars(video_path, config)
+}
+
+/// Waits for `--wait-stable`, showing a spinner while the video is checked.
+fn wait_until_written(video_path: &Path) -> obs_cutter::core::Result<()> {
+    let waiting = spinner(format!(
+        "Waiting for {} to finish being written...",
+        video_path.display()
+    ));
+    let result = wait_for_stable(video_path, WAIT_STABLE_TIMEOUT);
+    let message = match &result {
+        Ok(()) => format!("{} {} is complete", "✓".green(), video_path.display()),
+        Err(e) => format!("{} {}", "✗".red(), e),
+    };
+    finish_spinner(waiting, message);
+    result
+}
+
+/// Returns true if the path looks like a recording that should be split.
+///
+/// Our own `-left`/`-right` outputs are ignored so that watching the output
+/// directory does not re-split them.
+fn is_watchable(path: &Path) -> bool {
+    // Outputs written into the watched directory would be split again
+    is_supported_extension(path) && !is_side_output_name(path)
+}
+
+/// Watches `dir` and processes each new video once it has finished being written.
+///
+/// The first Ctrl-C stops watching after the in-flight video completes; a
+/// second Ctrl-C cancels that video, removing its partial output, and exits.
+///
+/// FFmpeg runs in its own process group here, so it never sees Ctrl-C; the
+/// cancel flag is what stops it.
+fn watch_directory(
+    dir: &Path,
+    output_dir: Option<&Path>,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    only_side: Option<Side>,
+    auto_crop: bool,
+) -> Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let cancel = Arc::new(AtomicBool::new(false));
+    let handler_stop = Arc::clone(&stop);
+    let handler_cancel = Arc::clone(&cancel);
+    ctrlc::set_handler(move || {
+        if handler_stop.swap(true, Ordering::SeqCst) {
+            if !handler_cancel.swap(true, Ordering::SeqCst) {
+                eprintln!("\n{} Cancelling the current video", "ℹ".blue());
+            }
+            return;
+        }
+        eprintln!(
+            "\n{} Stopping after the current video (press Ctrl-C again to abort)",
+            "ℹ".blue()
+        );
+    })
+    .context("Failed to install Ctrl-C handler")?;
+    let config = &config.clone().with_cancel_flag(Some(Arc::clone(&cancel)));
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to create watcher")?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch {}", dir.display()))?;
+
+    status!(
+        "{} Watching {} for new recordings (Ctrl-C to stop)\n",
+        "ℹ".blue(),
+        dir.display().to_string().white()
+    );
+
+    // Pending files with their last observed size and when it last changed
+    let mut pending: HashMap<PathBuf, (u64, Instant)> = HashMap::new();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+
+    while !stop.load(Ordering::SeqCst) {
+        match rx.recv_timeout(WATCH_POLL_INTERVAL) {
+            Ok(Ok(event)) => {
+                if event.kind.is_create() || event.kind.is_modify() {
+                    for path in event.paths {
+                        if is_watchable(&path) && !seen.contains(&path) {
+                            pending.entry(path).or_insert((0, Instant::now()));
+                        }
+                    }
+                }
+            }
+            Ok(Err(e)) => eprintln!("{} Watch error: {}", "Warning:".yellow(), e),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        // Debounce: a file is ready once its size has been stable for a while
+        let mut ready = Vec::new();
+        pending.retain(|path, (last_size, last_change)| {
+            let Ok(metadata) = fs::metadata(path) else {
+                // File disappeared before it was processed
+                return false;
+            };
+            let size = metadata.len();
+            if size != *last_size {
+                *last_size = size;
+                *last_change = Instant::now();
+                true
+            } else if size > 0 && last_change.elapsed() >= WATCH_STABLE_FOR {
+                ready.push(path.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        for path in ready {
+            // The file may have grown again while earlier videos were split
+            if wait_for_stable(&path, WATCH_STABLE_FOR).is_err() {
+                pending.insert(path, (0, Instant::now()));
+                continue;
+            }
+
+            seen.insert(path.clone());
+            let result = process_single_video(
+                &path,
+                output_dir,
+                config,
+                encoder,
+                only_side,
+                auto_crop,
+                &BatchClock::single(),
+            );
+            if !result.success {
+                failure!(
+                    "{} {} - {}",
+                    "✗".red(),
+                    path.display(),
+                    result.error.as_deref().unwrap_or("Unknown error")
+                );
+            }
+            status!();
+
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+        }
+    }
+
+    if cancel.load(Ordering::SeqCst) {
+        failure!("{} Cancelled", "✗".red());
+        std::process::exit(130);
+    }
+
+    status!("{} Stopped watching", "ℹ".blue());
+    Ok(())
+}
+
+fn print_summary(results: &[BatchResult], elapsed: Duration) {
     let successful = results.iter().filter(|r| r.success).count();
     let failed = results.iter().filter(|r| !r.success).count();
     let total = results.len();
 
-    println!("\n{}", "═".repeat(50).cyan());
-    println!("{}", "Summary".cyan().bold());
-    println!("{}\n", "═".repeat(50).cyan());
+    status!("\n{}", "═".repeat(50).cyan());
+    status!("{}", "Summary".cyan().bold());
+    status!("{}\n", "═".repeat(50).cyan());
 
     if total == 1 {
         if successful == 1 {
-            println!("{}", "✓ Video split successfully!".green());
+            status!("{}", "✓ Video split successfully!".green());
         } else {
-            println!("{}", "✗ Video processing failed!".red());
+            status!("{}", "✗ Video processing failed!".red());
         }
     } else {
-        println!(
+        status!(
             "Total: {} | {} {} | {} {}",
             total.to_string().white().bold(),
             "✓".green(),
@@ -273,67 +1361,790 @@ fn print_summary(results: &[BatchResult]) {
     // Show file sizes for successful videos
     let successful_results: Vec<_> = results.iter().filter(|r| r.success).collect();
     if !successful_results.is_empty() && total > 1 {
-        println!("\n{}", "Processed files:".bright_black());
+        status!("\n{}", "Processed files:".bright_black());
         for result in successful_results {
             let name = result.path.file_name().unwrap().to_string_lossy();
-            let left = format_file_size(result.left_size.unwrap_or(0));
-            let right = format_file_size(result.right_size.unwrap_or(0));
-            println!(
-                "  {} → Left: {}, Right: {}",
+            let Some(output) = &result.output else {
+                continue;
+            };
+            status!(
+                "  {} → {}{}",
                 name.white(),
-                left.bright_black(),
-                right.bright_black()
+                output.sizes().bright_black(),
+                size_ratio_note(output, result.input_size).bright_black()
             );
         }
-    } else if successful == 1 {
-        let result = results.iter().find(|r| r.success).unwrap();
-        println!("\n{}", "File sizes:".bright_black());
-        println!(
-            "  Left:  {}",
-            format_file_size(result.left_size.unwrap_or(0)).bright_black()
+    } else if let Some(output) = results.iter().find_map(|r| r.output.as_ref()) {
+        let input_size = results
+            .iter()
+            .find(|r| r.success)
+            .and_then(|r| r.input_size);
+        status!("\n{}", "File sizes:".bright_black());
+        match output {
+            BatchOutput::Split(result) => {
+                status!(
+                    "  Left:  {}",
+                    format_file_size(result.left_size).bright_black()
+                );
+                status!(
+                    "  Right: {}",
+                    format_file_size(result.right_size).bright_black()
+                );
+                status!(
+                    "  Total: {}{}",
+                    format_file_size(output.total_size()).bright_black(),
+                    size_ratio_note(output, input_size).bright_black()
+                );
+            }
+            BatchOutput::Single { side, size } => {
+                status!(
+                    "  {}: {}{}",
+                    side_label(*side),
+                    format_file_size(*size).bright_black(),
+                    size_ratio_note(output, input_size).bright_black()
+                );
+            }
+        }
+    }
+
+    // Wall-clock time against the length of video encoded
+    let video_secs: f64 = results
+        .iter()
+        .filter(|r| r.success)
+        .filter_map(|r| r.video_duration)
+        .sum();
+    if video_secs > 0.0 {
+        status!(
+            "\n{} {} for {} of video ({:.1}× realtime)",
+            "Time:".bright_black(),
+            format_duration(elapsed),
+            format_duration(Duration::from_secs_f64(video_secs)),
+            video_secs / elapsed.as_secs_f64().max(f64::EPSILON)
         );
-        println!(
-            "  Right: {}",
-            format_file_size(result.right_size.unwrap_or(0)).bright_black()
+    }
+
+    // Note videos that had to fall back to software encoding
+    let fell_back_count = results.iter().filter(|r| r.fell_back).count();
+    if fell_back_count > 0 {
+        status!(
+            "\n{} {} video(s) fell back to software encoding",
+            "Note:".yellow(),
+            fell_back_count
         );
     }
 
     // Show errors for failed videos
     let failed_results: Vec<_> = results.iter().filter(|r| !r.success).collect();
     if !failed_results.is_empty() {
-        println!("\n{}", "Failed files:".red());
+        status!("\n{}", "Failed files:".red());
         for result in failed_results {
             let name = result.path.file_name().unwrap().to_string_lossy();
             let error = result.error.as_deref().unwrap_or("Unknown error");
-            println!("  {} - {}", name.red(), error.bright_black());
+            failure!("  {} - {}", name.red(), error.bright_black());
+        }
+    }
+}
+
+/// Returns how much of the recording is encoded: the trimmed length when
+/// trimming, otherwise the whole probed duration.
+fn encoded_duration(info: &VideoInfo, config: &ProcessingConfig) -> Option<f64> {
+    match config.effective_trim() {
+        Some(trim) => trim
+            .resolve(info.duration)
+            .ok()?
+            .output_duration(info.duration),
+        None => info.duration,
+    }
+}
+
+/// Describes the outputs' size relative to the input, e.g. " (1.80× original)".
+fn size_ratio_note(output: &BatchOutput, input_size: Option<u64>) -> String {
+    match input_size {
+        Some(size) if size > 0 => format!(" ({:.2}× original)", output.compression_ratio(size)),
+        _ => String::new(),
+    }
+}
+
+/// Encodes only `side` of a video, for `--side`.
+///
+/// Returns the encoder actually used, the produced output and, with
+/// `--container-fallback`, whether it was written as MKV instead.
+fn encode_single_side(
+    video_path: &Path,
+    output_dir: &Path,
+    side: Side,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+    mut on_progress: impl FnMut(SideProgress),
+) -> obs_cutter::core::Result<(HardwareEncoder, BatchOutput, Vec<ContainerFallback>)> {
+    let requested = side_output_path(video_path, output_dir, side, config)?;
+    let (encoder_used, fallback) =
+        encode_with_container_fallback(side, &requested, config, |output, config| {
+            process_side_with_progress(
+                video_path,
+                output,
+                side,
+                config,
+                encoder,
+                None,
+                &mut on_progress,
+            )
+        })?;
+    let output = fallback.as_ref().map_or(requested, |f| f.output.clone());
+
+    if config.verify {
+        verify_side_output(video_path, &output, config)?;
+    }
+
+    let size = side_output_files(&output, config)
+        .iter()
+        .filter_map(|file| fs::metadata(file).ok())
+        .map(|m| m.len())
+        .sum();
+    Ok((
+        encoder_used,
+        BatchOutput::Single { side, size },
+        fallback.into_iter().collect(),
+    ))
+}
+
+/// Writes one side of a single video to stdout.
+fn pipe_video_side(
+    cli: &Cli,
+    side: Option<Side>,
+    config: &ProcessingConfig,
+    encoder: &HardwareEncoder,
+) -> Result<()> {
+    let side = side.context("--side is required when writing to stdout with -o -")?;
+    let [video_path] = cli.videos.as_slice() else {
+        anyhow::bail!("Only one video can be written to stdout at a time");
+    };
+
+    status!(
+        "{} Writing {} side of {} to stdout ({})",
+        "ℹ".blue(),
+        side,
+        video_path.display(),
+        config.pipe_format
+    );
+    process_side(video_path, Path::new("-"), side, config, encoder)
+        .with_context(|| format!("Failed to process {}", video_path.display()))?;
+
+    Ok(())
+}
+
+/// Warns that `--delete-source` removes the inputs and asks for
+/// confirmation on stderr. Anything but "y"/"yes" declines.
+fn confirm_delete_source(verify: bool) -> Result<bool> {
+    eprintln!(
+        "{} --delete-source permanently deletes each input once both sides are written.",
+        "Warning:".yellow().bold()
+    );
+    if !verify {
+        eprintln!(
+            "{} Consider --verify so the outputs are checked before deleting.",
+            "Warning:".yellow().bold()
+        );
+    }
+    eprint!("Delete the source files after splitting? [y/N] ");
+    std::io::Write::flush(&mut std::io::stderr())?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Benchmarks every available encoder on `sample` and prints them fastest
+/// first. Returns false if no encoder succeeded.
+fn run_benchmark(sample: &Path, duration_secs: f64) -> bool {
+    let benchmarking = spinner(format!(
+        "Encoding a {}s clip with each available encoder...",
+        duration_secs
+    ));
+    let results = benchmark(sample, duration_secs);
+    if let Some(benchmarking) = benchmarking {
+        benchmarking.finish_and_clear();
+    }
+
+    if results.is_empty() {
+        failure!("{} No encoder could encode {}", "✗".red(), sample.display());
+        return false;
+    }
+
+    for (rank, (encoder, speed, size)) in results.iter().enumerate() {
+        status!(
+            "{}. {:<20} {:>6.2}x  {}",
+            rank + 1,
+            encoder.name(),
+            speed,
+            format_file_size(*size)
+        );
+    }
+    true
+}
+
+/// Checks FFmpeg, FFprobe and the available encoders, then splits a test
+/// clip with the encoder a run with the same `--no-hw-accel` and
+/// `--encoder` would pick. Prints a hint for each failed check and returns
+/// false if any failed.
+fn run_doctor(no_hw_accel: bool, forced: Option<HardwareEncoder>) -> bool {
+    match check_ffmpeg() {
+        Ok(()) => status!(
+            "{} FFmpeg {} ({})",
+            "✓".green(),
+            get_ffmpeg_version_parsed()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "(unknown version)".to_string()),
+            get_ffmpeg_path().display()
+        ),
+        Err(e) => {
+            failure!("{} {}", "✗".red(), e);
+            if matches!(e, ObsCutterError::FfmpegNotFound) {
+                print_ffmpeg_install_help();
+            }
+            // Nothing else can be checked without FFmpeg
+            return false;
+        }
+    }
+
+    let mut healthy = true;
+    match check_ffprobe() {
+        Ok(()) => status!("{} FFprobe ({})", "✓".green(), get_ffprobe_path().display()),
+        Err(e) => {
+            failure!("{} {}", "✗".red(), e);
+            failure!(
+                "  FFprobe ships with FFmpeg; reinstall FFmpeg or put ffprobe next to {}",
+                get_ffmpeg_path().display()
+            );
+            healthy = false;
         }
     }
+
+    let hardware: Vec<_> = probe_all_encoders()
+        .into_iter()
+        .filter(|encoder| encoder.is_hardware())
+        .map(|encoder| encoder.name())
+        .collect();
+    if hardware.is_empty() {
+        status!(
+            "{} No hardware encoders found; software encoding will be used",
+            "ℹ".blue()
+        );
+    } else {
+        status!("{} Hardware encoders: {}", "✓".green(), hardware.join(", "));
+    }
+
+    let encoder = setup_encoder(no_hw_accel, forced);
+    let testing = spinner(format!("Test encode with {}...", encoder.name()));
+    match test_encode(&encoder) {
+        Ok(used) => finish_spinner(
+            testing,
+            format!("{} Test encode with {}", "✓".green(), used.name()),
+        ),
+        Err(e) => {
+            if let Some(testing) = testing {
+                testing.finish_and_clear();
+            }
+            let reason = match &e {
+                ObsCutterError::FfmpegFailed(stderr) => humanize_ffmpeg_error(stderr),
+                e => e.to_string(),
+            };
+            failure!(
+                "{} Test encode with {} failed: {}",
+                "✗".red(),
+                encoder.name(),
+                reason
+            );
+            if encoder.is_hardware() {
+                failure!(
+                    "  Check the GPU driver, or encode in software with --no-hw-accel \
+                     (or {}=1)",
+                    FORCE_SOFTWARE_ENV
+                );
+            }
+            healthy = false;
+        }
+    }
+    healthy
+}
+
+/// Remuxes each video into the given container format. Returns false if any failed.
+fn remux_videos(videos: &[PathBuf], output_dir: Option<&Path>, format: &str) -> bool {
+    let mut all_ok = true;
+
+    for video_path in videos {
+        let name = video_path.file_name().unwrap_or_default().to_string_lossy();
+        let stem = video_path.file_stem().unwrap_or_default().to_string_lossy();
+        let dir = resolve_output_dir(video_path, output_dir);
+        let output = dir.join(format!("{}.{}", stem, format));
+
+        if output == *video_path {
+            failure!("{} {} is already a .{} file", "✗".red(), name, format);
+            all_ok = false;
+            continue;
+        }
+
+        if let Err(e) = fs::create_dir_all(&dir) {
+            failure!("{} {}: {}", "✗".red(), name, e);
+            all_ok = false;
+            continue;
+        }
+
+        match remux(video_path, &output) {
+            Ok(()) => status!("{} {} → {}", "✓".green(), name, output.display()),
+            Err(e) => {
+                failure!("{} {}: {}", "✗".red(), name, e);
+                all_ok = false;
+            }
+        }
+    }
+
+    all_ok
+}
+
+/// Prints probed metadata for each video. Returns false if any probe failed.
+fn print_video_infos(videos: &[PathBuf]) -> bool {
+    let mut all_ok = true;
+
+    for (index, video_path) in videos.iter().enumerate() {
+        if index > 0 {
+            status!();
+        }
+        status!("{}", video_path.display().to_string().white().bold());
+
+        let info = match get_video_info(video_path) {
+            Ok(info) => info,
+            Err(e) => {
+                status!("  {} {}", "✗".red(), e);
+                all_ok = false;
+                continue;
+            }
+        };
+
+        let unknown = || "unknown".to_string();
+        status!("  {:<14}{}x{}", "Dimensions:", info.width, info.height);
+        if let Some(rotation) = info.rotation {
+            let (width, height) = info.display_size();
+            status!(
+                "  {:<14}{}° (displays as {}x{})",
+                "Rotation:",
+                rotation,
+                width,
+                height
+            );
+        }
+        status!("  {:<14}{}", "Aspect ratio:", info.aspect_ratio());
+        status!("  {:<14}{}", "Codec:", info.codec);
+        status!(
+            "  {:<14}{}{}",
+            "Pixel format:",
+            info.color.pix_fmt.clone().unwrap_or_else(unknown),
+            if info.color.is_ten_bit() {
+                " (10-bit)"
+            } else {
+                ""
+            }
+        );
+        status!(
+            "  {:<14}{}",
+            "Frame rate:",
+            info.fps
+                .map(|f| format!("{:.2} fps", f))
+                .unwrap_or_else(unknown)
+        );
+        status!(
+            "  {:<14}{}",
+            "Duration:",
+            info.duration
+                .map(|d| format_duration(Duration::from_secs_f64(d)))
+                .unwrap_or_else(unknown)
+        );
+        status!(
+            "  {:<14}{}",
+            "File size:",
+            info.file_size.map(format_file_size).unwrap_or_else(unknown)
+        );
+        if info.is_valid_dimensions() {
+            status!("  {:<14}{}", "Splittable:", "yes".green());
+        } else {
+            status!(
+                "  {:<14}{} (expected 3840x1080)",
+                "Splittable:",
+                "no".yellow()
+            );
+        }
+    }
+
+    all_ok
+}
+
+/// Returns true if `path` looks like a glob pattern rather than a file,
+/// i.e. it has wildcards and no such file exists.
+fn is_unmatched_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '[']) && !path.exists()
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    if let Some(list) = &cli.from_file {
+        let listed = read_input_list(list)
+            .with_context(|| format!("Failed to read input list {}", list.display()))?;
+        cli.videos.extend(listed);
+    }
+
+    // A shell passes a glob that matched nothing through as is (e.g. `*.mkv`)
+    let (unmatched, videos): (Vec<_>, Vec<_>) = std::mem::take(&mut cli.videos)
+        .into_iter()
+        .partition(|path| is_unmatched_glob(path));
+    cli.videos = videos;
+    for pattern in &unmatched {
+        eprintln!(
+            "{} {} matched no files",
+            "Warning:".yellow(),
+            pattern.display()
+        );
+    }
+    if cli.command.is_none() && cli.watch.is_none() && cli.videos.is_empty() {
+        match &cli.from_file {
+            Some(list) if unmatched.is_empty() => {
+                anyhow::bail!("No video files found: {} lists no paths", list.display())
+            }
+            _ => anyhow::bail!("No video files found"),
+        }
+    }
+    let piping = cli.output.as_deref().is_some_and(is_stdout_output);
+    if piping && cli.progress.is_some() {
+        anyhow::bail!("--progress cannot be combined with -o -, which uses stdout for video");
+    }
+    if piping && cli.segment_time.is_some() {
+        anyhow::bail!("--segment-time cannot be combined with -o -, which writes a single stream");
+    }
+    JSON_PROGRESS.store(cli.progress.is_some(), Ordering::Relaxed);
+    STDOUT_RESERVED.store(cli.progress.is_some() || piping, Ordering::Relaxed);
+    QUIET.store(cli.quiet, Ordering::Relaxed);
 
     print_header();
 
+    // The doctor reports a missing FFmpeg itself
+    if let Some(Command::Doctor {
+        no_hw_accel,
+        encoder,
+    }) = &cli.command
+    {
+        let forced = encoder
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .context("Invalid encoder")?;
+        if !run_doctor(*no_hw_accel, forced) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Check if FFmpeg is installed
-    if check_ffmpeg().is_err() {
-        eprintln!("{}", "Error: FFmpeg is not installed!".red());
-        print_ffmpeg_install_help();
-        std::process::exit(1);
+    match check_ffmpeg() {
+        Ok(()) => {}
+        Err(e @ ObsCutterError::FfmpegNotExecutable(_)) => {
+            eprintln!("{} {}", "Error:".red(), e);
+            std::process::exit(1);
+        }
+        Err(_) => {
+            eprintln!("{}", "Error: FFmpeg is not installed!".red());
+            print_ffmpeg_install_help();
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(Command::Join {
+        left,
+        right,
+        output,
+        vertical,
+    }) = &cli.command
+    {
+        let layout = if *vertical {
+            JoinLayout::Vertical
+        } else {
+            JoinLayout::Horizontal
+        };
+        let joining = spinner("Joining videos...".to_string());
+        match join_videos_with_layout(left, right, output, layout) {
+            Ok(()) => finish_spinner(
+                joining,
+                format!("{} Joined into {}", "✓".green(), output.display()),
+            ),
+            Err(e) => {
+                if let Some(joining) = joining {
+                    joining.finish_and_clear();
+                }
+                failure!("{} {}", "✗".red(), e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Benchmark { sample, duration }) = &cli.command {
+        if *duration <= 0.0 {
+            anyhow::bail!("--duration must be greater than zero");
+        }
+        if !run_benchmark(sample, *duration) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Info mode: report metadata only
+    if cli.info {
+        if !print_video_infos(&cli.videos) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Remux mode: change container only
+    if cli.remux_only {
+        let format = cli.format.as_deref().unwrap_or_default();
+        if !remux_videos(&cli.videos, cli.output.as_deref(), format) {
+            std::process::exit(1);
+        }
+        return Ok(());
     }
 
     // Parse quality
     let quality: Quality = cli.quality.parse().context("Invalid quality preset")?;
+    let left_quality: Option<Quality> = cli
+        .left_quality
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .context("Invalid left quality preset")?;
+    let right_quality: Option<Quality> = cli
+        .right_quality
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .context("Invalid right quality preset")?;
 
-    // Detect hardware encoder
-    let encoder = setup_encoder(cli.no_hw_accel);
+    // Parse color range override
+    let color_range: Option<ColorRange> = cli
+        .color_range
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .context("Invalid color range")?;
 
-    // Prepare output directory
-    let output_dir = cli.output.clone().unwrap_or_else(|| PathBuf::from("."));
+    // Parse audio mode
+    let audio = match cli.audio.parse().context("Invalid audio mode")? {
+        AudioMode::Aac { .. } => AudioMode::Aac {
+            bitrate: cli.audio_bitrate,
+        },
+        // Copied audio can't be filtered, so normalizing re-encodes it
+        AudioMode::Copy if cli.normalize_audio => AudioMode::Aac {
+            bitrate: cli.audio_bitrate,
+        },
+        AudioMode::None if cli.normalize_audio => {
+            anyhow::bail!("--normalize-audio cannot be combined with --audio none")
+        }
+        AudioMode::None if cli.downmix.is_some() => {
+            anyhow::bail!("--downmix cannot be combined with --audio none")
+        }
+        mode => mode,
+    };
+
+    // Parse trim range
+    let trim = if cli.start.is_some() || cli.end.is_some() || cli.duration.is_some() {
+        let start = cli.start.as_deref().map(parse_timestamp).transpose();
+        let end = cli.end.as_deref().map(str::parse).transpose();
+        let duration = cli.duration.as_deref().map(parse_timestamp).transpose();
+        Some(Trim::new(start?, end?, duration?).context("Invalid trim range")?)
+    } else {
+        None
+    };
+
+    // Parse sample length
+    let sample = cli
+        .sample
+        .as_deref()
+        .map(parse_timestamp)
+        .transpose()
+        .context("Invalid --sample")?;
+    if sample.is_some_and(|seconds| seconds <= 0.0) {
+        anyhow::bail!("--sample must be greater than zero");
+    }
+
+    // Parse chunk length
+    let segment_time = cli
+        .segment_time
+        .as_deref()
+        .map(parse_timestamp)
+        .transpose()
+        .context("Invalid --segment-time")?;
+    if segment_time.is_some_and(|seconds| seconds <= 0.0) {
+        anyhow::bail!("--segment-time must be greater than zero");
+    }
+
+    // Parse metadata tags
+    let metadata = cli
+        .metadata
+        .iter()
+        .map(|entry| {
+            entry
+                .split_once('=')
+                .filter(|(key, _)| !key.is_empty())
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .with_context(|| format!("Invalid metadata '{}': expected KEY=VALUE", entry))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    // Parse per-extension output formats
+    let format_map = cli
+        .format_map
+        .iter()
+        .map(|entry| {
+            entry
+                .split_once('=')
+                .map(|(from, to)| (from.trim_start_matches('.'), to.trim_start_matches('.')))
+                .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .with_context(|| format!("Invalid format mapping '{}': expected FROM=TO", entry))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    // Parse single-side selection
+    let only_side: Option<Side> = cli
+        .side
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .context("Invalid side")?;
+
+    // Validate subtitle burn-in
+    let burn_subs_side: Side = cli
+        .burn_subs_side
+        .parse()
+        .context("Invalid subtitle side")?;
+    if let Some(ref subs) = cli.burn_subs {
+        if !subs.is_file() {
+            anyhow::bail!("Subtitle file not found: {}", subs.display());
+        }
+    }
+
+    // Detect hardware encoder, unless one was named explicitly
+    let forced_encoder: Option<HardwareEncoder> = cli
+        .encoder
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .context("Invalid encoder")?;
+    let encoder = setup_encoder(cli.no_hw_accel, forced_encoder);
+
+    if let Some(feature) = FfmpegFeature::required_by(&encoder) {
+        if !supports_feature(feature) {
+            status!(
+                "{} FFmpeg {} is too old for {} (needs {} or newer); encoding may fail\n",
+                "Warning:".yellow(),
+                get_ffmpeg_version_parsed()
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                encoder.name(),
+                feature.min_version()
+            );
+        }
+    }
+
+    if cli.downmix.is_some() && matches!(audio, AudioMode::Copy) {
+        status!(
+            "{} copied audio can't be remixed; --downmix needs --audio aac and is ignored\n",
+            "Warning:".yellow()
+        );
+    }
+
+    if let Some(ref preset) = cli.preset {
+        if encoder.preset_option().is_none() {
+            status!(
+                "{} {} has no presets; --preset is ignored\n",
+                "Warning:".yellow(),
+                encoder.name()
+            );
+        }
+        encoder.check_preset(preset).context("Invalid preset")?;
+    }
+
+    // Outputs go next to each input unless --output is given
+    let output_dir = cli.output.as_deref();
+
+    let config = ProcessingConfig::new()
+        .with_quality(quality)
+        .with_left_quality(left_quality)
+        .with_right_quality(right_quality)
+        .with_output_format(cli.format.clone())
+        .with_format_map(format_map)
+        .with_container_fallback(cli.container_fallback)
+        .with_output_dir(cli.output.clone())
+        .with_hardware_accel(!cli.no_hw_accel)
+        .with_retry(cli.retries)
+        .with_fallback_to_software(cli.fallback_to_software)
+        .with_verify(cli.verify)
+        .with_audio(audio)
+        .with_audio_language(cli.audio_lang.clone())
+        .with_downmix(cli.downmix.is_some())
+        .with_normalize_audio(cli.normalize_audio)
+        .with_encoder_preset(cli.preset.clone())
+        .with_target_fps(cli.fps)
+        .with_segment_scenes(cli.segment_scenes)
+        .with_segment_time(segment_time)
+        .with_input_format(cli.input_format.clone())
+        .with_extra_args(cli.ffmpeg_args.clone())
+        .with_trim(trim)
+        .with_sample(sample)
+        .with_target_bitrate(cli.bitrate)
+        .with_two_pass(cli.two_pass)
+        .with_metadata(metadata)
+        .with_tag_side(cli.tag_side)
+        .with_preserve_chapters(!cli.no_chapters)
+        .with_faststart(!cli.no_faststart)
+        .with_subdir_per_input(cli.subdir)
+        .with_name_template(cli.name.clone())
+        .with_threads(cli.threads)
+        .with_delete_source_on_success(cli.delete_source)
+        .with_gpu_pipeline(cli.gpu_pipeline)
+        .with_override_dimensions(cli.input_dimensions)
+        .with_video_stream(cli.video_stream)
+        .with_strict_dimensions(cli.strict_dimensions)
+        .with_fit(if cli.pad { FitMode::Pad } else { FitMode::Crop })
+        .with_color_range(color_range)
+        .with_max_width(cli.max_width)
+        .with_max_height(cli.max_height)
+        .with_burn_subtitles(cli.burn_subs.clone(), burn_subs_side)
+        .with_pipe_format(cli.pipe_format.clone());
+
+    if cli.delete_source && !cli.yes && !confirm_delete_source(cli.verify)? {
+        anyhow::bail!("Aborted: --delete-source was not confirmed");
+    }
+
+    // Pipe mode: write a single side to stdout
+    if piping {
+        return pipe_video_side(&cli, only_side, &config, &encoder);
+    }
+
+    // Watch mode: let in-flight encodes finish when Ctrl-C is pressed
+    if let Some(ref watch_dir) = cli.watch {
+        let config = config.with_isolated_ffmpeg_signals(true);
+        return watch_directory(
+            watch_dir,
+            output_dir,
+            &config,
+            &encoder,
+            only_side,
+            cli.auto_crop,
+        );
+    }
 
     // Print batch info
     if cli.videos.len() > 1 {
-        println!(
+        status!(
             "{} Processing {} videos\n",
             "ℹ".blue(),
             cli.videos.len().to_string().white().bold()
@@ -341,50 +2152,213 @@ fn main() -> Result<()> {
     }
 
     // Print configuration
-    println!("{} {}", "Quality:".white(), quality.as_str());
+    if left_quality.is_none() && right_quality.is_none() {
+        status!(
+            "{} {} ({})",
+            "Quality:".white(),
+            quality.as_str(),
+            quality.describe()
+        );
+    } else {
+        status!(
+            "{} left {}, right {}",
+            "Quality:".white(),
+            config.quality_for(Side::Left),
+            config.quality_for(Side::Right)
+        );
+    }
     if let Some(ref format) = cli.format {
-        println!("{} {}", "Output format:".white(), format);
+        status!("{} {}", "Output format:".white(), format);
     }
-    if output_dir.as_os_str() != "." {
-        println!("{} {}", "Output directory:".white(), output_dir.display());
+    if let Some(output_dir) = output_dir {
+        status!("{} {}", "Output directory:".white(), output_dir.display());
+    }
+    if config.audio != AudioMode::Copy {
+        status!("{} {}", "Audio:".white(), config.audio);
+    }
+    if let Some(kbps) = config.target_bitrate {
+        status!(
+            "{} {}k{}",
+            "Target bitrate:".white(),
+            kbps,
+            if config.two_pass { " (two-pass)" } else { "" }
+        );
+        if encoder.is_hardware() {
+            status!(
+                "{} --bitrate only applies to software encoding; use --encoder software",
+                "Warning:".yellow()
+            );
+        }
+    }
+    if config.retry > 0 {
+        status!("{} {}", "Retries:".white(), config.retry);
+    }
+    if let Some(threads) = config.threads {
+        status!("{} {}", "Threads:".white(), threads);
+    }
+    if config.gpu_pipeline {
+        if encoder != HardwareEncoder::Nvenc {
+            status!(
+                "{} --gpu-pipeline only applies to NVENC; cropping on the CPU",
+                "Warning:".yellow()
+            );
+        } else if !has_filter("crop_cuda") {
+            status!(
+                "{} This FFmpeg has no crop_cuda filter; cropping on the CPU",
+                "Warning:".yellow()
+            );
+        } else {
+            status!("{} decode, crop and encode on the GPU", "Pipeline:".white());
+        }
+    }
+    status!();
+
+    // Both sides share a name stem, so checking one side covers both
+    check_output_collisions(
+        &cli.videos,
+        output_dir,
+        &config,
+        only_side.unwrap_or(Side::Left),
+    )?;
+
+    let sides = match only_side {
+        Some(side) => vec![side],
+        None => Side::ALL.to_vec(),
+    };
+    check_input_overwrites(&cli.videos, output_dir, &config, &sides)?;
+
+    // Make sure the outputs are likely to fit before starting
+    if let Err(e) = check_batch_disk_space(&cli.videos, output_dir, &config, &encoder, &sides) {
+        if cli.strict {
+            anyhow::bail!(e);
+        }
+        status!("{} {}\n", "Warning:".yellow(), e);
     }
-    println!();
+
+    // Completions are journaled so an interrupted batch can be resumed;
+    // single-side runs don't complete a video, so they aren't. A dated
+    // output directory keeps one journal above its date folders.
+    let journal_path = batch_output_dir(&cli.videos, output_dir).join(JOURNAL_FILE_NAME);
+    let mut journal = match only_side {
+        Some(_) => None,
+        None if cli.resume => Some(
+            BatchJournal::load(&journal_path)
+                .with_context(|| format!("Failed to read {}", journal_path.display()))?,
+        ),
+        None => Some(BatchJournal::new(&journal_path)),
+    };
+
+    // Ctrl-C cancels the running encode, whose partial output is removed
+    let cancel = Arc::new(AtomicBool::new(false));
+    let handler_cancel = Arc::clone(&cancel);
+    ctrlc::set_handler(move || {
+        if handler_cancel.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        eprintln!(
+            "\n{} Cancelling (press Ctrl-C again to exit immediately)",
+            "ℹ".blue()
+        );
+    })
+    .context("Failed to install Ctrl-C handler")?;
+    let config = config.with_cancel_flag(Some(Arc::clone(&cancel)));
 
     // Process each video
     let mut results = Vec::new();
+    let mut skipped = 0;
+    let batch_started = Instant::now();
 
     for (index, video_path) in cli.videos.iter().enumerate() {
-        let result = process_single_video(
-            video_path,
-            &output_dir,
-            cli.format.as_deref(),
-            &quality,
-            &encoder,
-            index,
-            cli.videos.len(),
-        );
+        if cli.resume && journal.as_ref().is_some_and(|j| j.is_completed(video_path)) {
+            report_batch_event(&ProcessingProgress::Skipped {
+                video_index: index,
+                total: cli.videos.len(),
+                path: video_path.clone(),
+            });
+            skipped += 1;
+            continue;
+        }
+
+        let stable = if cli.wait_stable {
+            wait_until_written(video_path)
+        } else {
+            Ok(())
+        };
+        let result = match stable {
+            Ok(()) => process_single_video(
+                video_path,
+                output_dir,
+                &config,
+                &encoder,
+                only_side,
+                cli.auto_crop,
+                &BatchClock {
+                    started: batch_started,
+                    index,
+                    total: cli.videos.len(),
+                },
+            ),
+            Err(e) => BatchResult {
+                path: video_path.clone(),
+                success: false,
+                output: None,
+                input_size: None,
+                error: Some(e.to_string()),
+                fell_back: false,
+                video_duration: None,
+            },
+        };
 
         let failed = !result.success;
+        if let Some(journal) = journal.as_mut().filter(|_| !failed) {
+            if let Err(e) = journal.mark_completed(video_path) {
+                status!(
+                    "{} Could not update {}: {}",
+                    "Warning:".yellow(),
+                    journal.path().display(),
+                    e
+                );
+            }
+        }
         results.push(result);
 
+        if cancel.load(Ordering::SeqCst) {
+            break;
+        }
+
         // Stop on first error unless continue_on_error is set
         if failed && !cli.continue_on_error && index < cli.videos.len() - 1 {
-            eprintln!(
-                "\n{} Use {} to continue processing remaining videos",
-                "Hint:".yellow(),
-                "--continue-on-error".white()
-            );
+            if !QUIET.load(Ordering::Relaxed) {
+                eprintln!(
+                    "\n{} Use {} to continue processing remaining videos",
+                    "Hint:".yellow(),
+                    "--continue-on-error".white()
+                );
+            }
             break;
         }
 
         // Add spacing between videos
         if cli.videos.len() > 1 && index < cli.videos.len() - 1 {
-            println!();
+            status!();
         }
     }
 
+    if skipped > 0 {
+        status!(
+            "\n{} Skipped {} video(s) completed in an earlier run",
+            "ℹ".blue(),
+            skipped
+        );
+    }
+
     // Print summary
-    print_summary(&results);
+    print_summary(&results, batch_started.elapsed());
+
+    if cancel.load(Ordering::SeqCst) {
+        failure!("{} Cancelled", "✗".red());
+        std::process::exit(130);
+    }
 
     // Exit with error code if any failed
     let any_failed = results.iter().any(|r| !r.success);
@@ -392,5 +2366,17 @@ fn main() -> Result<()> {
         std::process::exit(1);
     }
 
+    // Nothing is left to resume once every video has been split
+    if let Some(journal) = journal {
+        if let Err(e) = journal.remove() {
+            status!(
+                "{} Could not remove {}: {}",
+                "Warning:".yellow(),
+                journal.path().display(),
+                e
+            );
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,190 @@
+//! End-to-end tests that run FFmpeg on a generated clip.
+//!
+//! These need FFmpeg and FFprobe installed, so they are ignored by default.
+//! Run them with `cargo test -- --ignored`.
+
+use obs_cutter::core::{
+    get_ffmpeg_path, get_ffprobe_path, get_video_info, process_video_with_config, HardwareEncoder,
+    ProcessingConfig, Quality, Trim,
+};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A scratch directory that is removed when the test ends.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("obs-cutter-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Generates a 2-second 640x360 test pattern with a tone at `path`.
+fn generate_test_video(path: &Path) {
+    let status = Command::new(get_ffmpeg_path())
+        .args(["-v", "error", "-y"])
+        .args([
+            "-f",
+            "lavfi",
+            "-i",
+            "testsrc=duration=2:size=640x360:rate=30",
+        ])
+        .args(["-f", "lavfi", "-i", "sine=duration=2"])
+        .args(["-c:v", "libx264", "-pix_fmt", "yuv420p", "-c:a", "aac"])
+        .arg(path)
+        .status()
+        .expect("FFmpeg must be installed to run ignored tests");
+    assert!(status.success(), "failed to generate {}", path.display());
+}
+
+/// Generates the test clip at `path` with chapters at 0s and 1s.
+fn generate_chaptered_video(path: &Path) {
+    let source = path.with_extension("source.mp4");
+    generate_test_video(&source);
+    let metadata = path.with_extension("ffmetadata");
+    std::fs::write(
+        &metadata,
+        ";FFMETADATA1\n\
+         [CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=1000\ntitle=One\n\
+         [CHAPTER]\nTIMEBASE=1/1000\nSTART=1000\nEND=2000\ntitle=Two\n",
+    )
+    .unwrap();
+    let status = Command::new(get_ffmpeg_path())
+        .args(["-v", "error", "-y", "-i"])
+        .arg(&source)
+        .arg("-i")
+        .arg(&metadata)
+        .args(["-map_metadata", "1", "-map_chapters", "1", "-c", "copy"])
+        .arg(path)
+        .status()
+        .expect("FFmpeg must be installed to run ignored tests");
+    assert!(status.success(), "failed to generate {}", path.display());
+}
+
+/// Returns the chapter start times of `path`, in seconds.
+fn chapter_starts(path: &Path) -> Vec<f64> {
+    let output = Command::new(get_ffprobe_path())
+        .args(["-v", "error", "-show_entries", "chapter=start_time"])
+        .args(["-of", "csv=p=0"])
+        .arg(path)
+        .output()
+        .expect("FFprobe must be installed to run ignored tests");
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect()
+}
+
+#[test]
+#[ignore = "needs FFmpeg"]
+fn test_process_video_splits_generated_clip() {
+    let dir = TempDir::new("split");
+    let input = dir.0.join("clip.mp4");
+    generate_test_video(&input);
+
+    let config = ProcessingConfig::new()
+        .with_hardware_accel(false)
+        .with_verify(true)
+        .with_output_dir(Some(dir.0.clone()));
+    let result = process_video_with_config(&input, &config, &HardwareEncoder::None).unwrap();
+
+    assert_eq!(result.left_output, dir.0.join("clip-left.mp4"));
+    assert_eq!(result.right_output, dir.0.join("clip-right.mp4"));
+    for output in [&result.left_output, &result.right_output] {
+        let info = get_video_info(output).unwrap();
+        assert_eq!((info.width, info.height), (320, 360));
+        let duration = info.duration.unwrap();
+        assert!(
+            (duration - 2.0).abs() < 0.5,
+            "{} lasts {}s",
+            output.display(),
+            duration
+        );
+    }
+    assert!(result.left_size > 0 && result.right_size > 0);
+}
+
+#[test]
+#[ignore = "needs FFmpeg"]
+fn test_process_video_with_config_uses_config_output_dir() {
+    let dir = TempDir::new("config");
+    let input = dir.0.join("clip.mp4");
+    generate_test_video(&input);
+
+    let config = ProcessingConfig::new()
+        .with_hardware_accel(false)
+        .with_output_format(Some("mkv".to_string()))
+        .with_output_dir(Some(dir.0.join("out")));
+    let result = process_video_with_config(&input, &config, &HardwareEncoder::None).unwrap();
+
+    assert_eq!(result.left_output, dir.0.join("out").join("clip-left.mkv"));
+    assert_eq!(
+        result.right_output,
+        dir.0.join("out").join("clip-right.mkv")
+    );
+    assert!(result.left_output.exists() && result.right_output.exists());
+}
+
+#[test]
+#[ignore = "needs FFmpeg"]
+fn test_process_video_shifts_chapters_by_trim() {
+    let dir = TempDir::new("chapters");
+    let input = dir.0.join("clip.mp4");
+    generate_chaptered_video(&input);
+    assert_eq!(chapter_starts(&input).len(), 2);
+
+    let config = ProcessingConfig::new()
+        .with_hardware_accel(false)
+        .with_trim(Some(Trim::new(Some(0.5), None, None).unwrap()))
+        .with_output_dir(Some(dir.0.clone()));
+    let result = process_video_with_config(&input, &config, &HardwareEncoder::None).unwrap();
+
+    for output in [&result.left_output, &result.right_output] {
+        let starts = chapter_starts(output);
+        assert_eq!(starts.len(), 2, "{} lost its chapters", output.display());
+        assert!(
+            starts[0].abs() < 0.1,
+            "{} starts at {:?}",
+            output.display(),
+            starts
+        );
+        assert!(
+            (starts[1] - 0.5).abs() < 0.1,
+            "{} starts at {:?}",
+            output.display(),
+            starts
+        );
+    }
+}
+
+#[test]
+#[ignore = "needs FFmpeg"]
+#[allow(deprecated)]
+fn test_deprecated_process_video_keeps_positional_options() {
+    let dir = TempDir::new("positional");
+    let input = dir.0.join("clip.mp4");
+    generate_test_video(&input);
+
+    let out = dir.0.join("out");
+    let result = obs_cutter::core::process_video(
+        &input,
+        &out,
+        Some("mkv"),
+        Quality::Fast,
+        &HardwareEncoder::None,
+    )
+    .unwrap();
+
+    assert_eq!(result.left_output, out.join("clip-left.mkv"));
+    assert_eq!(result.right_output, out.join("clip-right.mkv"));
+    assert!(result.left_output.exists() && result.right_output.exists());
+}